@@ -14,6 +14,7 @@ element-layer = Element Layer
 element-layer-icon = E
 add-element = Add Element
 delete-element = Delete Element
+size = Size
 toggle-visibility = Toggle Visibility
 delete-layer = Delete Layer
 delete = Delete
@@ -38,6 +39,13 @@ none = None
 jump-through = Jump Through
 empty = Empty
 solid = Solid
+slope-45-up-right = Slope 45° ◢
+slope-45-up-left = Slope 45° ◣
+slope-22-up-right-low = Slope 22.5° ◢ Low
+slope-22-up-right-high = Slope 22.5° ◢ High
+slope-22-up-left-high = Slope 22.5° ◣ High
+slope-22-up-left-low = Slope 22.5° ◣ Low
+destructible = Destructible
 
 open-map = Open Map
 no-map-loaded = No map loaded
@@ -47,3 +55,14 @@ view-offset = Camera Offset [ { $x }, { $y } ]
 cursor-position = Cursor Position [ { $x }, { $y } ]
 view-reset = Reset View
 show-grid = Show Grid
+
+select = Select
+rename = Rename
+move-layer-up = Move Layer Up
+move-layer-down = Move Layer Down
+map-exported = Map saved to { $path }
+map-export-failed = Could not export map: { $error }
+undo = Undo
+redo = Redo
+terrain = Terrain
+terrain-tilemap-mismatch = This layer uses a different tilemap than the terrain.
@@ -0,0 +1,3 @@
+watch-replay = Watch Replay
+step = Step
+replay-frame = Frame { $frame } / { $total }
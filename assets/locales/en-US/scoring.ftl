@@ -7,6 +7,10 @@ won = Winner
 tied = Tied
 intermission = Intermission
 score = Score
+player-lives = P{ $player }: { $lives } { $lives ->
+    [one] life
+   *[other] lives
+}
 
 match-complete = Match Complete
 tied-for-win = Tied For Win
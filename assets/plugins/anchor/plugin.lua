@@ -74,6 +74,8 @@ local function update()
       components:insert(ent, DropItem:create())
       local damage = DamageRegion:create()
       damage.size = anchor_meta.body_size
+      damage.damage = anchor_meta.damage
+      damage.knockback = anchor_meta.knockback
       components:insert(ent, damage)
       local damageOwner = DamageRegionOwner:create()
       damageOwner[0] = used.owner
@@ -0,0 +1,283 @@
+//! Simulates matches without a window or renderer.
+//!
+//! Every match is played by AI players and runs for a fixed number of frames, cycling through
+//! the available maps. Panics in the simulation are reported at the end and make the process exit
+//! with an error code, so it can be used to smoke test the game on CI.
+//!
+//! Matches may be recorded to replay files with `--record`, and a replay can be simulated again
+//! with `--replay` to reproduce a failure. The checksum of the final frame of every match is
+//! logged, so a replay can be checked against the run it was recorded from.
+//!
+//! Matches are played with the rules from the game's core config, and `--no-one-hit-kill` turns
+//! on player health for longer rounds. `--lives` sets how many times each player can be killed
+//! in a round.
+//!
+//! The `validate` command checks every map, including the experimental ones, for problems instead
+//! of simulating matches, and exits with an error code if any are found.
+//!
+//! ```text
+//! jumpy-headless [--matches <count>] [--frames <count>] [--players <count>]
+//!                [--map <name>] [--record <dir>] [--no-one-hit-kill] [--lives <count>]
+//! jumpy-headless --replay <file>
+//! jumpy-headless validate [--map <name>]
+//! ```
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use std::{panic::AssertUnwindSafe, path::PathBuf};
+
+    use jumpy::{headless, prelude::*};
+
+    setup_logs!("org", "fishfolk", "jumpy");
+
+    let args = Args::parse();
+
+    let asset_dir: PathBuf = std::env::var("JUMPY_ASSETS")
+        .unwrap_or_else(|_| "assets".into())
+        .into();
+    let packs_dir: PathBuf = std::env::var("JUMPY_ASSET_PACKS")
+        .unwrap_or_else(|_| "packs".into())
+        .into();
+    let mut game = headless::new_game(&asset_dir, &packs_dir);
+
+    if let Some(path) = &args.replay {
+        let replay = match Replay::load(path) {
+            Ok(replay) => Arc::new(replay),
+            Err(e) => {
+                error!("Could not load replay {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        };
+        let frames = replay.frame_count();
+        info!("Simulating replay {} for {frames} frames.", path.display());
+
+        headless::start_replay(&mut game, replay);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            if frames == 0 {
+                return;
+            }
+            while !headless::replay_finished(&game) {
+                headless::step(&mut game);
+            }
+        }));
+
+        if result.is_err() {
+            error!("Replay panicked.");
+            std::process::exit(1);
+        }
+        if let Some(checksum) = headless::world_checksum(&game) {
+            info!("Final checksum: {checksum}");
+        }
+        info!("Simulated replay without errors.");
+        return;
+    }
+
+    if args.validate {
+        let asset_server = game.shared_resource::<AssetServer>().unwrap();
+        let mut maps = headless::all_maps(&asset_server);
+        maps.extend(
+            asset_server
+                .root::<GameMeta>()
+                .core
+                .experimental_maps
+                .iter(),
+        );
+        if let Some(name) = &args.map {
+            maps.retain(|h| &asset_server.get(*h).name == name);
+        }
+        if maps.is_empty() {
+            error!("No maps to validate.");
+            std::process::exit(1);
+        }
+
+        let mut invalid_maps = 0;
+        for map in &maps {
+            let map = asset_server.get(*map);
+            let problems = validate_map(&map, &asset_server);
+            if !problems.is_empty() {
+                invalid_maps += 1;
+                error!("Map {} has {} problems:", map.name, problems.len());
+                for problem in problems {
+                    error!("  - {problem}");
+                }
+            }
+        }
+
+        if invalid_maps == 0 {
+            info!("Validated {} maps without problems.", maps.len());
+        } else {
+            error!("{invalid_maps} of {} maps have problems.", maps.len());
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let (maps, game_meta) = {
+        let asset_server = game.shared_resource::<AssetServer>().unwrap();
+        let mut maps = headless::all_maps(&asset_server);
+        if let Some(name) = &args.map {
+            maps.retain(|h| &asset_server.get(*h).name == name);
+        }
+        (maps, asset_server.root::<GameMeta>().clone())
+    };
+    let mut rules = game_meta.core.config.rules.clone();
+    if args.no_one_hit_kill {
+        rules.one_hit_kill = false;
+    }
+    if let Some(lives) = args.lives {
+        rules.lives = lives;
+    }
+    if maps.is_empty() {
+        error!("No maps to simulate.");
+        std::process::exit(1);
+    }
+
+    let mut failures = Vec::new();
+    for i in 0..args.matches {
+        let map = maps[i % maps.len()];
+        let map_name = {
+            let asset_server = game.shared_resource::<AssetServer>().unwrap();
+            asset_server.get(map).name.to_string()
+        };
+        info!(
+            "Simulating match {}/{} on {map_name} for {} frames.",
+            i + 1,
+            args.matches,
+            args.frames
+        );
+
+        let recorder = args
+            .record
+            .as_ref()
+            .map(|dir| ReplayRecorder::new(Some(dir.join(format!("match-{}.replay", i + 1)))));
+        headless::start_match(
+            &mut game,
+            MapPool::from_single_map(map),
+            headless::ai_players(&game_meta, args.players),
+            rules.clone(),
+            headless::HeadlessMatchRunner {
+                recorder,
+                ..default()
+            },
+        );
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            for _ in 0..args.frames {
+                headless::step(&mut game);
+            }
+        }));
+
+        if result.is_err() {
+            error!("Match {} on {map_name} panicked.", i + 1);
+            failures.push(map_name);
+
+            // The game may be left in an inconsistent state after a panic, so start over.
+            game = headless::new_game(&asset_dir, &packs_dir);
+        } else if let Some(checksum) = headless::world_checksum(&game) {
+            info!("Match {} final checksum: {checksum}", i + 1);
+        }
+    }
+
+    if failures.is_empty() {
+        info!("Simulated {} matches without errors.", args.matches);
+    } else {
+        error!(
+            "{} of {} matches panicked: {}",
+            failures.len(),
+            args.matches,
+            failures.join(", ")
+        );
+        std::process::exit(1);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    panic!("The headless match runner is not supported on the web.");
+}
+
+/// Command line arguments for the headless runner.
+#[cfg(not(target_arch = "wasm32"))]
+struct Args {
+    /// The number of matches to simulate.
+    matches: usize,
+    /// The number of frames to simulate per match.
+    frames: usize,
+    /// The number of AI players in each match.
+    players: usize,
+    /// Only simulate the map with this name.
+    map: Option<String>,
+    /// Directory to record a replay of every match to.
+    record: Option<std::path::PathBuf>,
+    /// Simulate this replay instead of new matches.
+    replay: Option<std::path::PathBuf>,
+    /// Validate the maps instead of simulating matches.
+    validate: bool,
+    /// Give players health instead of killing them with the first hit.
+    no_one_hit_kill: bool,
+    /// The number of lives that each player has per round.
+    lives: Option<u32>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Args {
+    fn parse() -> Self {
+        let mut args = Args {
+            matches: 1,
+            frames: 60 * 60,
+            players: jumpy::core::MAX_PLAYERS as usize,
+            map: None,
+            record: None,
+            replay: None,
+            validate: false,
+            no_one_hit_kill: false,
+            lives: None,
+        };
+
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            let mut value = || {
+                iter.next()
+                    .unwrap_or_else(|| usage(&format!("Missing value for `{arg}`")))
+            };
+            match arg.as_str() {
+                "--matches" => args.matches = parse_count(&value()),
+                "--frames" => args.frames = parse_count(&value()),
+                "--players" => {
+                    args.players = parse_count(&value()).clamp(1, jumpy::core::MAX_PLAYERS as usize)
+                }
+                "--map" => args.map = Some(value()),
+                "--record" => args.record = Some(value().into()),
+                "--replay" => args.replay = Some(value().into()),
+                "--no-one-hit-kill" => args.no_one_hit_kill = true,
+                "--lives" => args.lives = Some(parse_count(&value()).max(1) as u32),
+                "validate" => args.validate = true,
+                "-h" | "--help" => usage(""),
+                other => usage(&format!("Unknown argument `{other}`")),
+            }
+        }
+
+        args
+    }
+}
+
+/// Parse a count argument, exiting with the usage if it is invalid.
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_count(value: &str) -> usize {
+    value
+        .parse()
+        .unwrap_or_else(|_| usage(&format!("Invalid number `{value}`")))
+}
+
+/// Print the usage and exit.
+#[cfg(not(target_arch = "wasm32"))]
+fn usage(error: &str) -> ! {
+    if !error.is_empty() {
+        eprintln!("{error}\n");
+    }
+    eprintln!("Usage: jumpy-headless [--matches <count>] [--frames <count>] [--players <count>]");
+    eprintln!("                      [--map <name>] [--record <dir>] [--no-one-hit-kill]");
+    eprintln!("                      [--lives <count>]");
+    eprintln!("       jumpy-headless --replay <file>");
+    eprintln!("       jumpy-headless validate [--map <name>]");
+    std::process::exit(if error.is_empty() { 0 } else { 1 });
+}
@@ -1,8 +1,10 @@
 pub mod attachment;
 pub mod bullet;
 pub mod camera;
+pub mod checksum;
 pub mod damage;
 pub mod debug;
+pub mod destruction;
 pub mod editor;
 pub mod elements;
 pub mod globals;
@@ -11,11 +13,15 @@ pub mod item;
 pub mod lifetime;
 pub mod map;
 pub mod map_constructor;
+pub mod map_export;
 pub mod map_pool;
+pub mod map_validation;
 pub mod metadata;
 pub mod physics;
 pub mod player;
 pub mod random;
+pub mod replay;
+pub mod rules;
 pub mod scoring;
 pub mod utils;
 pub mod win_indicator;
@@ -32,10 +38,11 @@ use crate::{prelude::*, settings::PlayerControlMapping};
 
 pub mod prelude {
     pub use super::{
-        attachment::*, bullet::*, camera::*, damage::*, debug::*, editor::*, elements::prelude::*,
-        flappy_jellyfish::*, globals::*, input::*, item::*, lifetime::*, map::*,
-        map_constructor::*, map_pool::*, metadata::*, physics::*, player::*, random::*, scoring::*,
-        utils::*, win_indicator::*, FPS, MAX_PLAYERS,
+        attachment::*, bullet::*, camera::*, checksum::*, damage::*, debug::*, destruction::*,
+        editor::*, elements::prelude::*, flappy_jellyfish::*, globals::*, input::*, item::*,
+        lifetime::*, map::*, map_constructor::*, map_export::*, map_pool::*, map_validation::*,
+        metadata::*, physics::*, player::*, random::*, replay::*, rules::*, scoring::*, utils::*,
+        win_indicator::*, FPS, MAX_PLAYERS,
     };
 }
 
@@ -44,6 +51,7 @@ pub fn game_plugin(game: &mut Game) {
     AudioSource::register_schema();
     HatMeta::register_schema();
     MapMeta::register_schema();
+    AutotileMeta::register_schema();
     game.install_plugin(elements::game_plugin)
         .install_plugin(bullet::game_plugin)
         .install_plugin(win_indicator::game_plugin)
@@ -61,6 +69,9 @@ pub struct MatchPlugin {
     /// should be inputted from previous session resourc.
     pub score: MatchScore,
 
+    /// The rules of the match.
+    pub rules: MatchRules,
+
     pub session_runner: Box<dyn SessionRunner>,
 }
 
@@ -83,6 +94,7 @@ impl SessionPlugin for MatchPlugin {
         player::plugin(session);
         elements::session_plugin(session);
         damage::install(session);
+        destruction::install(session);
         camera::install(session);
         lifetime::install(session);
         random::plugin(session);
@@ -92,6 +104,7 @@ impl SessionPlugin for MatchPlugin {
         bullet::session_plugin(session);
         editor::install(session);
         scoring::session_plugin(session);
+        checksum::install(session);
 
         let current_map = self.maps.current_map;
         session.world.insert_resource(self.maps);
@@ -109,6 +122,7 @@ impl SessionPlugin for MatchPlugin {
             players: self.player_info,
         });
         session.world.insert_resource(self.score);
+        session.world.insert_resource(self.rules);
         session.runner = self.session_runner;
     }
 }
@@ -122,6 +136,19 @@ pub struct JumpyDefaultMatchRunner {
     pub last_run: Option<Instant>,
     /// Disables local input for session.
     disable_local_input: bool,
+    /// Records the match so that it can be replayed.
+    pub recorder: Option<ReplayRecorder>,
+}
+
+impl JumpyDefaultMatchRunner {
+    /// Create a new runner that records the match, saving the replay to a file if the
+    /// [`RECORD_REPLAY_ENV_VAR`] environment variable is set.
+    pub fn new() -> Self {
+        Self {
+            recorder: Some(ReplayRecorder::from_env()),
+            ..default()
+        }
+    }
 }
 
 impl SessionRunner for JumpyDefaultMatchRunner {
@@ -168,6 +195,10 @@ impl SessionRunner for JumpyDefaultMatchRunner {
             // Mark inputs as consumed for this frame
             self.input_collector.advance_frame();
 
+            if let Some(recorder) = &mut self.recorder {
+                recorder.record_frame(world);
+            }
+
             // Advance the simulation
             stages.run(world);
         };
@@ -196,7 +227,15 @@ impl SessionRunner for JumpyDefaultMatchRunner {
     }
 
     fn restart_session(&mut self) {
-        *self = JumpyDefaultMatchRunner::default();
+        // Keep recording the match across rounds.
+        let mut recorder = self.recorder.take();
+        if let Some(recorder) = &mut recorder {
+            recorder.restart_round();
+        }
+        *self = JumpyDefaultMatchRunner {
+            recorder,
+            ..default()
+        };
     }
 
     fn disable_local_input(&mut self, disable_input: bool) {
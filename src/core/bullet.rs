@@ -28,6 +28,8 @@ pub struct Bullet {
     pub direction: Vec2,
     /// The player entity that shot the bullet.
     pub owner: Entity,
+    /// The weapon that shot the bullet.
+    pub weapon: Maybe<Handle<ElementMeta>>,
 }
 
 #[derive(HasSchema, Clone, Debug, Default)]
@@ -37,6 +39,10 @@ pub struct BulletMeta {
     pub speed: f32,
     pub body_diameter: f32,
     pub atlas: Handle<Atlas>,
+    /// The damage that the bullet deals to the player it hits.
+    pub damage: f32,
+    /// The speed that the bullet knocks the player it hits back at.
+    pub knockback: f32,
 
     pub lifetime: f32,
     pub explosion_fps: f32,
@@ -121,6 +127,8 @@ fn update(
         let BulletMeta {
             speed,
             body_diameter,
+            damage,
+            knockback,
             explosion_fps,
             explosion_volume,
             explosion_sound,
@@ -130,38 +138,68 @@ fn update(
             ..
         } = &*bullet_meta;
 
-        // Move bullet
-        let position = {
+        let shape = ColliderShape::Circle {
+            diameter: *body_diameter,
+        };
+        let can_hit_player = |e: Entity| {
+            player_indexes.contains(e) && invincibles.get(e).is_none() && e != bullet.owner
+        };
+
+        // Move bullet, sweeping it along its path so that fast bullets can't pass through walls or
+        // players between frames.
+        let (position, swept_hit) = {
             let position = transforms.get_mut(entity).unwrap();
-            let delta_time = time.delta_seconds();
-            position.translation += (bullet.direction * *speed * delta_time).extend(0.0);
+            let movement = bullet.direction * *speed * time.delta_seconds();
+            let swept_hit = collision_world.cast_shape_filtered(
+                *position,
+                shape,
+                movement,
+                movement.length(),
+                &CastFilter::default()
+                    .with_actors(collisions::filtering::CollisionGroup::ALL)
+                    .excluding(entity),
+                |e| !collision_world.actors.contains(e) || can_hit_player(e),
+            );
+            position.translation += match swept_hit {
+                Some(hit) => movement.normalize_or_zero() * hit.distance,
+                None => movement,
+            }
+            .extend(0.0);
 
             let emote_size = Vec2::new(*body_diameter * 6.0, *body_diameter * 3.5);
             emote_regions.insert(entity, EmoteRegion::basic(Emote::Alarm, emote_size, true));
 
-            *position
+            (*position, swept_hit)
         };
 
         // Check actor collisions
         let mut hit_player = false;
-        collision_world
-            .actor_collisions_filtered(entity, |e| {
-                player_indexes.contains(e) && invincibles.get(e).is_none()
-            })
-            .into_iter()
-            .filter(|player| *player != bullet.owner)
-            .for_each(|player| {
-                hit_player = true;
-                commands.add(PlayerCommand::kill(player, Some(position.translation.xy())));
-            });
+        let mut players = collision_world.actor_collisions_filtered(entity, can_hit_player);
+        if let Some(hit) = swept_hit.filter(|hit| can_hit_player(hit.entity)) {
+            if !players.contains(&hit.entity) {
+                players.push(hit.entity);
+            }
+        }
+        let source = DamageSource {
+            cause: DamageCause::Item,
+            killer: Set(bullet.owner),
+            element: bullet.weapon,
+            ..default()
+        };
+        for player in players {
+            hit_player = true;
+            commands.add(PlayerCommand::damage(
+                player,
+                *damage,
+                bullet.direction.normalize_or_zero() * *knockback,
+                Some(position.translation.xy()),
+                source,
+            ));
+        }
 
         // check solid tile collisions
-        let hit_solid = collision_world.tile_collision(
-            position,
-            ColliderShape::Circle {
-                diameter: *body_diameter,
-            },
-        ) == TileCollisionKind::Solid;
+        let hit_solid = swept_hit.is_some_and(|hit| !collision_world.actors.contains(hit.entity))
+            || collision_world.tile_collision(position, shape).is_solid();
 
         // Bullet hit something
         if hit_player || hit_solid {
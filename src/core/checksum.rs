@@ -0,0 +1,500 @@
+//! Per-frame checksums of the simulation state, used to detect non-determinism.
+//!
+//! At the end of every frame a [`WorldChecksum`] is computed over the components and resources
+//! that matter most for the simulation, and stored in the [`WorldChecksums`] resource. Each kind
+//! of state gets its own hash, so when the checksums of two runs (or two peers) differ we can tell
+//! which kind of state diverged first, and from there which system is non-deterministic.
+//!
+//! In network matches the [`ChecksumSyncRunner`] sends the checksums of confirmed frames to the
+//! other players and compares them with their own, logging an error when the simulations have
+//! diverged. Checksums are also logged at the `trace` level, so the logs of two players can be
+//! compared to find the exact frame.
+
+use std::{
+    collections::{hash_map::DefaultHasher, VecDeque},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
+
+use crate::prelude::*;
+
+/// The number of frames to keep checksums for in [`WorldChecksums`].
+pub const CHECKSUM_HISTORY_LEN: usize = 10 * FPS as usize;
+
+pub fn install(session: &mut Session) {
+    session
+        .stages
+        .add_system_to_stage(CoreStage::Last, record_world_checksum);
+}
+
+/// Hashes of the simulation state for a single frame.
+///
+/// The hashes only depend on the simulation state and the build of the game, so they may be
+/// compared between runs and between peers running the same version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WorldChecksum {
+    pub transforms: u64,
+    pub kinematic_bodies: u64,
+    pub player_states: u64,
+    pub inventories: u64,
+    pub player_health: u64,
+    pub player_abilities: u64,
+    pub moving_platforms: u64,
+    pub rng: u64,
+    pub score: u64,
+}
+
+impl WorldChecksum {
+    /// Compute the checksum of the simulation state in the world.
+    pub fn new(world: &World) -> Self {
+        world.run_system(world_checksum, ())
+    }
+
+    /// Get each of the hashes paired with the name of the state it was computed from.
+    pub fn components(&self) -> [(&'static str, u64); 9] {
+        [
+            ("Transform", self.transforms),
+            ("KinematicBody", self.kinematic_bodies),
+            ("PlayerState", self.player_states),
+            ("Inventory", self.inventories),
+            ("PlayerHealth", self.player_health),
+            ("PlayerAbilities", self.player_abilities),
+            ("MovingPlatform", self.moving_platforms),
+            ("GlobalRng", self.rng),
+            ("MatchScore", self.score),
+        ]
+    }
+
+    /// Get a single hash combining all of the state.
+    pub fn combined(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Get the name of the first kind of state that differs between the two checksums.
+    pub fn first_difference(&self, other: &Self) -> Option<&'static str> {
+        self.components()
+            .into_iter()
+            .zip(other.components())
+            .find(|((_, a), (_, b))| a != b)
+            .map(|((name, _), _)| name)
+    }
+}
+
+impl std::fmt::Display for WorldChecksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x} (", self.combined())?;
+        for (i, (name, hash)) in self.components().into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{name}: {hash:016x}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Resource containing the checksums of the most recent frames of the session.
+#[derive(HasSchema, Clone, Default, Debug)]
+pub struct WorldChecksums {
+    /// The number of frames simulated in the session.
+    pub frame: u32,
+    /// The checksums of the last [`CHECKSUM_HISTORY_LEN`] frames, oldest first.
+    pub history: VecDeque<(u32, WorldChecksum)>,
+}
+
+impl WorldChecksums {
+    /// Get the checksum for the given frame, if it is still in the history.
+    pub fn get(&self, frame: u32) -> Option<&WorldChecksum> {
+        self.history
+            .iter()
+            .find(|(f, _)| *f == frame)
+            .map(|(_, checksum)| checksum)
+    }
+
+    /// Find the first frame that both histories have a checksum for where the checksums differ.
+    ///
+    /// Returns the frame and the name of the first kind of state that differs in it.
+    pub fn first_difference(&self, other: &Self) -> Option<(u32, &'static str)> {
+        self.history.iter().find_map(|(frame, checksum)| {
+            let other = other.get(*frame)?;
+            checksum
+                .first_difference(other)
+                .map(|component| (*frame, component))
+        })
+    }
+}
+
+/// Hash a float by its bits, so that even differences in the last bit are detected.
+fn hash_f32(value: f32, hasher: &mut impl Hasher) {
+    value.to_bits().hash(hasher);
+}
+
+fn hash_vec2(value: Vec2, hasher: &mut impl Hasher) {
+    hash_f32(value.x, hasher);
+    hash_f32(value.y, hasher);
+}
+
+/// System that computes the [`WorldChecksum`].
+fn world_checksum(
+    entities: Res<Entities>,
+    transforms: Comp<Transform>,
+    bodies: Comp<KinematicBody>,
+    player_states: Comp<PlayerState>,
+    inventories: Comp<Inventory>,
+    player_healths: Comp<PlayerHealth>,
+    player_abilities: Comp<PlayerAbilities>,
+    moving_platforms: Comp<MovingPlatform>,
+    rng: Res<GlobalRng>,
+    score: ResInit<MatchScore>,
+    lives: ResInit<PlayerLives>,
+    rules: Res<MatchRules>,
+) -> WorldChecksum {
+    let mut checksum = WorldChecksum::default();
+
+    let mut hasher = DefaultHasher::new();
+    for (ent, transform) in entities.iter_with(&transforms) {
+        ent.hash(&mut hasher);
+        for value in transform
+            .translation
+            .to_array()
+            .into_iter()
+            .chain(transform.rotation.to_array())
+            .chain(transform.scale.to_array())
+        {
+            hash_f32(value, &mut hasher);
+        }
+    }
+    checksum.transforms = hasher.finish();
+
+    let mut hasher = DefaultHasher::new();
+    for (ent, body) in entities.iter_with(&bodies) {
+        ent.hash(&mut hasher);
+        hash_vec2(body.velocity, &mut hasher);
+        body.shape.hash(&mut hasher);
+        hash_f32(body.angular_velocity, &mut hasher);
+        hash_f32(body.gravity, &mut hasher);
+        hash_f32(body.bounciness, &mut hasher);
+        hash_f32(body.material.friction, &mut hasher);
+        hash_f32(body.material.bounciness, &mut hasher);
+        hash_f32(body.material.speed, &mut hasher);
+        hash_f32(body.slope_gradient, &mut hasher);
+        [
+            body.is_on_ground,
+            body.was_on_ground,
+            body.is_on_platform,
+            body.has_mass,
+            body.has_friction,
+            body.can_rotate,
+            body.is_deactivated,
+            body.fall_through,
+            body.is_spawning,
+            body.is_controlled,
+        ]
+        .hash(&mut hasher);
+        hash_vec2(body.last_update_position, &mut hasher);
+        hash_f32(body.last_update_rotation, &mut hasher);
+        hash_f32(body.submersion, &mut hasher);
+    }
+    checksum.kinematic_bodies = hasher.finish();
+
+    let mut hasher = DefaultHasher::new();
+    for (ent, state) in entities.iter_with(&player_states) {
+        ent.hash(&mut hasher);
+        state.current.as_str().hash(&mut hasher);
+        state.age.hash(&mut hasher);
+        state.last.as_str().hash(&mut hasher);
+    }
+    checksum.player_states = hasher.finish();
+
+    let mut hasher = DefaultHasher::new();
+    for (ent, inventory) in entities.iter_with(&inventories) {
+        ent.hash(&mut hasher);
+        inventory.0.hash(&mut hasher);
+    }
+    checksum.inventories = hasher.finish();
+
+    let mut hasher = DefaultHasher::new();
+    for (ent, health) in entities.iter_with(&player_healths) {
+        ent.hash(&mut hasher);
+        hash_f32(health.health, &mut hasher);
+        hash_f32(health.max_health, &mut hasher);
+        health.armor.hash(&mut hasher);
+    }
+    checksum.player_health = hasher.finish();
+
+    let mut hasher = DefaultHasher::new();
+    for (ent, abilities) in entities.iter_with(&player_abilities) {
+        ent.hash(&mut hasher);
+        for charges in [
+            abilities.double_jump,
+            abilities.air_dash,
+            abilities.ground_pound,
+        ] {
+            charges.charges.hash(&mut hasher);
+            hash_f32(charges.cooldown, &mut hasher);
+        }
+        for charges in [
+            abilities.meta.double_jump.charges,
+            abilities.meta.air_dash.charges,
+            abilities.meta.ground_pound.charges,
+        ] {
+            charges.hash(&mut hasher);
+        }
+    }
+    checksum.player_abilities = hasher.finish();
+
+    let mut hasher = DefaultHasher::new();
+    for (ent, platform) in entities.iter_with(&moving_platforms) {
+        ent.hash(&mut hasher);
+        hash_vec2(platform.start, &mut hasher);
+        platform.target.hash(&mut hasher);
+        platform.reversing.hash(&mut hasher);
+    }
+    checksum.moving_platforms = hasher.finish();
+
+    // Draw from a copy of the generator so that we don't advance the real one.
+    checksum.rng = rng.0.clone().gen_u64();
+
+    let mut hasher = DefaultHasher::new();
+    score.rounds_completed().hash(&mut hasher);
+    for player in 0..MAX_PLAYERS {
+        score.score(PlayerIdx(player)).hash(&mut hasher);
+        lives.remaining(PlayerIdx(player), &rules).hash(&mut hasher);
+    }
+    checksum.score = hasher.finish();
+
+    checksum
+}
+
+/// Records the checksum of the frame in the [`WorldChecksums`] resource.
+fn record_world_checksum(world: &World) {
+    let checksum = WorldChecksum::new(world);
+
+    let mut checksums = world.init_resource::<WorldChecksums>();
+    let frame = checksums.frame;
+    trace!("Frame {frame} checksum: {checksum}");
+
+    if checksums.history.len() >= CHECKSUM_HISTORY_LEN {
+        checksums.history.pop_front();
+    }
+    checksums.history.push_back((frame, checksum));
+    checksums.frame += 1;
+}
+
+/// How often the [`ChecksumSyncRunner`] sends the checksum of a frame to the other players.
+#[cfg(not(target_arch = "wasm32"))]
+pub const CHECKSUM_SYNC_INTERVAL: u32 = FPS as u32;
+
+/// The first byte of every [`ChecksumMessage`], which tells them apart from the other messages
+/// sent over the reliable channel of the socket.
+#[cfg(not(target_arch = "wasm32"))]
+pub const CHECKSUM_MESSAGE_MAGIC: u8 = 97;
+
+/// Network message with the checksum of a confirmed frame.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Serialize, Deserialize)]
+pub struct ChecksumMessage {
+    /// Always [`CHECKSUM_MESSAGE_MAGIC`]. This is the first field, so that it's the first byte of
+    /// the message.
+    pub magic: u8,
+    /// The number of rounds that had been started before the frame's round.
+    pub round: u32,
+    pub frame: u32,
+    pub checksum: WorldChecksum,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ChecksumMessage {
+    /// Parse a checksum message, returning [`None`] if the data is some other kind of message.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        postcard::from_bytes::<Self>(data)
+            .ok()
+            .filter(|message| message.magic == CHECKSUM_MESSAGE_MAGIC)
+    }
+}
+
+/// Resource with the reliable network messages of a network match that haven't been handled yet.
+///
+/// Everything that talks to the other players during a match, like the [`ChecksumSyncRunner`] and
+/// the scoring menu, shares the reliable channel of the socket. Whichever of them receives new
+/// messages first puts them in this inbox, and each of them only takes the messages that are meant
+/// for it, leaving the rest for the others.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(HasSchema, Clone, Default)]
+pub struct ReliableMessageInbox(pub Arc<Mutex<Vec<(u32, Vec<u8>)>>>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ReliableMessageInbox {
+    /// Add the messages that were just received from the socket to the inbox, and take the
+    /// messages that `parse` understands out of it, along with the index of the player that sent
+    /// them.
+    pub fn take<T>(
+        &self,
+        received: Vec<(u32, Vec<u8>)>,
+        mut parse: impl FnMut(&[u8]) -> Option<T>,
+    ) -> Vec<(u32, T)> {
+        let mut messages = self.0.lock().unwrap();
+        messages.extend(received);
+        let mut taken = Vec::new();
+        messages.retain(|(player, data)| match parse(data) {
+            Some(message) => {
+                taken.push((*player, message));
+                false
+            }
+            None => true,
+        });
+        taken
+    }
+}
+
+/// Session runner that wraps the session runner of a network match to compare the
+/// [`WorldChecksums`] of every [`CHECKSUM_SYNC_INTERVAL`] frames with the other players.
+///
+/// Only frames that are older than the prediction window are compared, because the frames after
+/// them may still be rolled back and simulated again.
+///
+/// The runner inserts a [`ReliableMessageInbox`] into the world, which the other users of the
+/// socket during the match should receive their messages through.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct ChecksumSyncRunner {
+    pub runner: Box<dyn SessionRunner>,
+    pub socket: bones_framework::networking::NetworkMatchSocket,
+    /// The number of frames after which a frame can't be rolled back anymore.
+    pub confirmation_delay: u32,
+    round: u32,
+    last_sent: Option<u32>,
+    /// Checksums received from the other players that haven't been compared yet.
+    pending: Vec<(u32, ChecksumMessage)>,
+    inbox: ReliableMessageInbox,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ChecksumSyncRunner {
+    pub fn new(
+        runner: Box<dyn SessionRunner>,
+        socket: bones_framework::networking::NetworkMatchSocket,
+        max_prediction_window: usize,
+    ) -> Self {
+        Self {
+            runner,
+            socket,
+            confirmation_delay: max_prediction_window as u32 + 1,
+            round: 0,
+            last_sent: None,
+            pending: Vec::new(),
+            inbox: default(),
+        }
+    }
+
+    /// Send the checksums of the newly confirmed frames and compare the ones that were received.
+    fn sync_checksums(&mut self, checksums: &WorldChecksums) {
+        use bones_framework::networking::SocketTarget;
+
+        let confirmed = checksums.frame.saturating_sub(self.confirmation_delay);
+
+        for (frame, checksum) in &checksums.history {
+            if *frame >= confirmed
+                || frame % CHECKSUM_SYNC_INTERVAL != 0
+                || self.last_sent.is_some_and(|last| *frame <= last)
+            {
+                continue;
+            }
+            let message = ChecksumMessage {
+                magic: CHECKSUM_MESSAGE_MAGIC,
+                round: self.round,
+                frame: *frame,
+                checksum: *checksum,
+            };
+            self.socket
+                .send_reliable(SocketTarget::All, &postcard::to_allocvec(&message).unwrap());
+            self.last_sent = Some(*frame);
+        }
+
+        for (player, message) in self
+            .inbox
+            .take(self.socket.recv_reliable(), ChecksumMessage::parse)
+        {
+            // Other players may already be in the next round
+            if message.round >= self.round {
+                self.pending.push((player, message));
+            }
+        }
+
+        let round = self.round;
+        self.pending.retain(|(player, message)| {
+            if message.round > round || message.frame >= confirmed {
+                return true;
+            }
+            // Frames that are no longer in the history can't be compared anymore.
+            if let Some(checksum) = checksums.get(message.frame) {
+                if let Some(component) = checksum.first_difference(&message.checksum) {
+                    error!(
+                        "Desync detected with player {player} in round {round} at frame {}, \
+                        {component} differs. Local checksum: {checksum}, remote checksum: {}",
+                        message.frame, message.checksum
+                    );
+                }
+            }
+            false
+        });
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SessionRunner for ChecksumSyncRunner {
+    fn step(&mut self, frame_start: Instant, world: &mut World, stages: &mut SystemStages) {
+        world.insert_resource(self.inbox.clone());
+        self.runner.step(frame_start, world, stages);
+
+        if let Some(checksums) = world.get_resource::<WorldChecksums>() {
+            let checksums = (*checksums).clone();
+            self.sync_checksums(&checksums);
+        }
+    }
+
+    fn restart_session(&mut self) {
+        self.runner.restart_session();
+        self.round += 1;
+        self.last_sent = None;
+        let round = self.round;
+        self.pending.retain(|(_, message)| message.round >= round);
+    }
+
+    fn disable_local_input(&mut self, disable_input: bool) {
+        self.runner.disable_local_input(disable_input);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn inbox_leaves_other_messages_for_their_consumers() {
+        let checksum = postcard::to_allocvec(&ChecksumMessage {
+            magic: CHECKSUM_MESSAGE_MAGIC,
+            round: 0,
+            frame: 60,
+            checksum: default(),
+        })
+        .unwrap();
+        // Some other message, like the ones sent by the scoring menu.
+        let other = vec![0, 1, 183];
+
+        let inbox = ReliableMessageInbox::default();
+        let taken = inbox.take(
+            vec![(1, other.clone()), (2, checksum)],
+            ChecksumMessage::parse,
+        );
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].0, 2);
+        assert_eq!(taken[0].1.frame, 60);
+
+        // The other message is still there for whoever it's meant for.
+        let taken = inbox.take(Vec::new(), |data| (data == other).then_some(()));
+        assert_eq!(taken, [(1, ())]);
+        assert!(inbox.0.lock().unwrap().is_empty());
+    }
+}
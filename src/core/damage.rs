@@ -1,6 +1,11 @@
-//! Damage / kill regions.
+//! Damage / kill regions and player health.
 //!
-//! Any player that intersects a damage region will be killed.
+//! Any player that intersects a damage region will be damaged. With the
+//! [`one_hit_kill`][MatchRules::one_hit_kill] rule, which is the default, that kills the player.
+//! Otherwise the damage is taken from the player's [`PlayerHealth`], see [`PlayerCommand::damage`].
+//!
+//! Damage and kills carry a [`DamageSource`] saying who dealt them with what, which ends up in the
+//! [`PlayerDamaged`] and [`PlayerKilled`] events.
 
 use crate::prelude::*;
 
@@ -10,21 +15,43 @@ use super::utils::Rect;
 pub fn install(session: &mut Session) {
     DamageRegion::register_schema();
     DamageRegionOwner::register_schema();
+    DamageRegionSource::register_schema();
+    DamageSource::register_schema();
+    DamageCause::register_schema();
+    PlayerHealth::register_schema();
+    PlayerDamaged::register_schema();
+    MatchRules::register_schema();
 
     session
         .stages
-        .add_system_to_stage(CoreStage::PostUpdate, kill_players_in_damage_region);
+        .add_system_to_stage(CoreStage::PreUpdate, hydrate_player_health)
+        .add_system_to_stage(CoreStage::PostUpdate, clear_player_damaged)
+        .add_system_to_stage(CoreStage::PostUpdate, damage_players_in_damage_region);
 }
 
 /// A rectangular damage region.
-///
-/// While this _might_ change in the future, damage regions will kill players immediately, so there
-/// is no "damage" field.
-#[derive(Debug, Clone, Default, HasSchema)]
+#[derive(Debug, Clone, HasSchema)]
 #[repr(C)]
 pub struct DamageRegion {
     /// The size of the damage region in pixels
     pub size: Vec2,
+    /// The amount of health that players lose when they are hit by the region.
+    ///
+    /// This defaults to [`f32::INFINITY`], so that regions which don't set it kill the players
+    /// they hit, no matter how much health they have, even when one-hit kills are turned off.
+    pub damage: f32,
+    /// The speed that players are knocked away from the center of the region at when hit.
+    pub knockback: f32,
+}
+
+impl Default for DamageRegion {
+    fn default() -> Self {
+        Self {
+            size: Vec2::ZERO,
+            damage: f32::INFINITY,
+            knockback: 0.0,
+        }
+    }
 }
 
 impl DamageRegion {
@@ -42,14 +69,189 @@ impl DamageRegion {
 #[repr(C)]
 pub struct DamageRegionOwner(pub Entity);
 
-/// System that will eliminate players that are intersecting with a damage region.
-fn kill_players_in_damage_region(
+/// A component that may be added to a damage region entity to indicate what the damage is dealt
+/// by.
+///
+/// Unlike with [`DamageRegionOwner`], the killer in the source is harmed by the damage region, so
+/// this is used for things like explosions, which hurt the player that set them off too. Damage
+/// regions without a source are attributed to their owner.
+#[derive(Debug, Clone, Copy, HasSchema, Default)]
+#[repr(C)]
+pub struct DamageRegionSource(pub DamageSource);
+
+/// The kind of thing that damaged or killed a player.
+#[derive(Debug, Clone, Copy, HasSchema, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DamageCause {
+    /// Nothing is known about what dealt the damage.
+    #[default]
+    Unknown,
+    /// An item, such as a weapon or an explosive.
+    Item,
+    /// A hazard that is part of the map, such as spikes.
+    Hazard,
+    /// Another player landing on top of the player.
+    Stomp,
+    /// Falling out of the map.
+    OutOfBounds,
+}
+
+/// Who damaged or killed a player, and with what.
+#[derive(Debug, Clone, Copy, HasSchema, Default)]
+#[repr(C)]
+pub struct DamageSource {
+    /// The kind of thing that dealt the damage.
+    pub cause: DamageCause,
+    /// The player that dealt the damage, such as the player that used the weapon.
+    pub killer: Maybe<Entity>,
+    /// The index of the [`killer`][Self::killer].
+    ///
+    /// This is filled in when the damage is dealt, so that it's known even after the killer has
+    /// been despawned.
+    pub killer_idx: Maybe<u32>,
+    /// The element that dealt the damage, such as the weapon that was used or the hazard.
+    pub element: Maybe<Handle<ElementMeta>>,
+}
+
+impl DamageSource {
+    /// Damage dealt by an item.
+    pub fn item(element: Handle<ElementMeta>) -> Self {
+        Self {
+            cause: DamageCause::Item,
+            element: Set(element),
+            ..default()
+        }
+    }
+
+    /// Damage dealt by a hazard that is part of the map.
+    pub fn hazard(element: Handle<ElementMeta>) -> Self {
+        Self {
+            cause: DamageCause::Hazard,
+            element: Set(element),
+            ..default()
+        }
+    }
+
+    /// Damage dealt by a player landing on top of the player.
+    pub fn stomp(killer: Entity) -> Self {
+        Self {
+            cause: DamageCause::Stomp,
+            killer: Set(killer),
+            ..default()
+        }
+    }
+
+    /// Falling out of the map.
+    pub fn out_of_bounds() -> Self {
+        Self {
+            cause: DamageCause::OutOfBounds,
+            ..default()
+        }
+    }
+
+    /// Set the player that dealt the damage.
+    pub fn with_killer(mut self, killer: Entity) -> Self {
+        self.killer = Set(killer);
+        self
+    }
+
+    /// Set the element that dealt the damage.
+    pub fn with_element(mut self, element: Handle<ElementMeta>) -> Self {
+        self.element = Set(element);
+        self
+    }
+
+    /// Fill in the [`killer_idx`][Self::killer_idx] of the source, if the killer is a player.
+    pub fn with_killer_idx(mut self, player_indexes: &ComponentStore<PlayerIdx>) -> Self {
+        if let Set(killer) = self.killer {
+            if let Some(idx) = player_indexes.get(killer) {
+                self.killer_idx = Set(idx.0);
+            }
+        }
+        self
+    }
+}
+
+/// The health and armor of a player.
+///
+/// This is only used when the [`one_hit_kill`][MatchRules::one_hit_kill] rule is turned off.
+#[derive(Debug, Clone, Copy, Default, HasSchema)]
+#[repr(C)]
+pub struct PlayerHealth {
+    /// The remaining health of the player. The player is killed when it runs out.
+    pub health: f32,
+    /// The health that the player spawned with.
+    pub max_health: f32,
+    /// The number of hits that the player can take without losing any health.
+    pub armor: u32,
+}
+
+/// Component added to a player for a frame after they survive being damaged.
+///
+/// Damage that kills a player adds [`PlayerKilled`] instead.
+#[derive(Debug, Clone, HasSchema, Default)]
+#[repr(C)]
+pub struct PlayerDamaged {
+    /// The health that the player lost.
+    pub damage: f32,
+    /// The number of hits that were absorbed by the player's armor.
+    pub absorbed: u32,
+    /// The position that the player was last hit from.
+    pub hit_from: Maybe<Vec2>,
+    /// What the player was last hit by.
+    pub source: DamageSource,
+}
+
+/// System that gives newly spawned players the health from their [`PlayerStatsMeta`].
+fn hydrate_player_health(
+    entities: Res<Entities>,
+    assets: Res<AssetServer>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    mut healths: CompMut<PlayerHealth>,
+) {
+    let mut not_hydrated_bitset = healths.bitset().clone();
+    not_hydrated_bitset.bit_not();
+    not_hydrated_bitset.bit_and(player_indexes.bitset());
+
+    let new_players = entities
+        .iter_with_bitset(&not_hydrated_bitset)
+        .collect::<Vec<_>>();
+    for player_ent in new_players {
+        let player_idx = player_indexes.get(player_ent).unwrap();
+        let player_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let health = assets.get(player_handle).stats.health;
+        healths.insert(
+            player_ent,
+            PlayerHealth {
+                health,
+                max_health: health,
+                armor: 0,
+            },
+        );
+    }
+}
+
+/// System that removes the [`PlayerDamaged`] events from the last frame.
+fn clear_player_damaged(entities: Res<Entities>, mut players_damaged: CompMut<PlayerDamaged>) {
+    let damaged = entities
+        .iter_with(&players_damaged)
+        .map(|(ent, _)| ent)
+        .collect::<Vec<_>>();
+    for ent in damaged {
+        players_damaged.remove(ent);
+    }
+}
+
+/// System that will damage players that are intersecting with a damage region.
+fn damage_players_in_damage_region(
     entities: Res<Entities>,
     mut commands: Commands,
     player_indexes: Comp<PlayerIdx>,
     transforms: Comp<Transform>,
     damage_regions: Comp<DamageRegion>,
     damage_region_owners: Comp<DamageRegionOwner>,
+    damage_region_sources: Comp<DamageRegionSource>,
     bodies: Comp<KinematicBody>,
     invincibles: CompMut<Invincibility>,
 ) {
@@ -72,12 +274,23 @@ fn kill_players_in_damage_region(
                     continue;
                 }
             }
+            let source = match (damage_region_sources.get(ent), owner) {
+                (Some(source), _) => source.0,
+                (None, Some(owner)) => DamageSource::default().with_killer(owner.0),
+                (None, None) => DamageSource::default(),
+            };
 
             let damage_rect = damage_region.collider_rect(transform.translation);
             if player_rect.overlaps(&damage_rect) {
-                commands.add(PlayerCommand::kill(
+                let hit_from = transform.translation.xy();
+                let knockback =
+                    (player_rect.center() - hit_from).normalize_or_zero() * damage_region.knockback;
+                commands.add(PlayerCommand::damage(
                     player_ent,
-                    Some(transform.translation.xy()),
+                    damage_region.damage,
+                    knockback,
+                    Some(hit_from),
+                    source,
                 ));
             }
         }
@@ -0,0 +1,212 @@
+//! Destructible map tiles.
+//!
+//! Tiles marked [`destructible`][MapTileMeta::destructible] in the map are spawned with the
+//! [`DestructibleTile`] component, and can be destroyed by explosions with [`TileDestruction`].
+//! Damage regions with the [`DestroysTiles`] component destroy the tiles inside of them when they
+//! are spawned.
+
+use crate::prelude::*;
+
+/// Install this module.
+pub fn install(session: &mut Session) {
+    DestructibleTile::register_schema();
+    DestroysTiles::register_schema();
+
+    session
+        .stages
+        .add_system_to_stage(CoreStage::PostUpdate, destroy_tiles_in_damage_regions);
+}
+
+/// Marker component for map tiles that can be destroyed by explosions.
+#[derive(HasSchema, Default, Debug, Clone, Copy)]
+pub struct DestructibleTile;
+
+/// Component for [`DamageRegion`]s that destroy the [`DestructibleTile`]s inside of them, such as
+/// explosions.
+///
+/// The tiles are destroyed once, on the frame the region is spawned, after which the component is
+/// removed.
+#[derive(HasSchema, Default, Debug, Clone, Copy)]
+pub struct DestroysTiles;
+
+/// Destroy the tiles inside of newly spawned [`DestroysTiles`] damage regions.
+fn destroy_tiles_in_damage_regions(
+    mut commands: Commands,
+    entities: Res<Entities>,
+    transforms: Comp<Transform>,
+    damage_regions: Comp<DamageRegion>,
+    mut destroys_tiles: CompMut<DestroysTiles>,
+) {
+    let mut rects = Vec::new();
+    let mut regions = Vec::new();
+    for (ent, (_, transform, damage_region)) in
+        entities.iter_with((&destroys_tiles, &transforms, &damage_regions))
+    {
+        rects.push((transform.translation.truncate(), damage_region.size));
+        regions.push(ent);
+    }
+    for ent in regions {
+        destroys_tiles.remove(ent);
+    }
+
+    if !rects.is_empty() {
+        commands.add(move |mut tile_destruction: TileDestruction| {
+            for (center, size) in &rects {
+                tile_destruction.destroy_tiles_in_rect(*center, *size);
+            }
+        });
+    }
+}
+
+impl_system_param! {
+    /// A system parameter for destroying [`DestructibleTile`]s.
+    ///
+    /// Destroying a tile removes it from its [`TileLayer`], updates the [`CollisionWorld`], and
+    /// updates the [`NavGraph`] around it. All of this state is part of the world, so destroyed
+    /// tiles are restored when the world is rolled back.
+    pub struct TileDestruction<'a> {
+        commands: Commands<'a>,
+        entities: ResMutInit<'a, Entities>,
+        spawned_map_meta: Res<'a, SpawnedMapMeta>,
+        nav_graph: ResMutInit<'a, NavGraph>,
+        spawned_map_layer_metas: Comp<'a, SpawnedMapLayerMeta>,
+        tile_layers: CompMut<'a, TileLayer>,
+        tile_collisions: Comp<'a, TileCollisionKind>,
+        destructible_tiles: Comp<'a, DestructibleTile>,
+    }
+}
+
+impl<'a> TileDestruction<'a> {
+    /// Destroy the tile with the given layer index and map grid position, if it's destructible.
+    ///
+    /// Returns whether the tile was destroyed.
+    pub fn destroy_tile(&mut self, layer_idx: u32, pos: UVec2) -> bool {
+        let destroyed = self.remove_tile(layer_idx, pos);
+        if destroyed {
+            self.update_collisions(vec![(layer_idx, pos)]);
+            self.update_nav_graph(&[pos]);
+        }
+        destroyed
+    }
+
+    /// Destroy all of the destructible tiles that have their center inside of the rectangle with
+    /// the given center and size, such as the damage region of an explosion.
+    ///
+    /// Returns the number of tiles destroyed.
+    pub fn destroy_tiles_in_rect(&mut self, center: Vec2, size: Vec2) -> usize {
+        let tile_size = self.spawned_map_meta.tile_size;
+        let grid_size = self.spawned_map_meta.grid_size;
+        if tile_size.cmple(Vec2::ZERO).any() {
+            return 0;
+        }
+
+        let min = ((center - size / 2.0) / tile_size)
+            .floor()
+            .max(Vec2::ZERO)
+            .as_uvec2();
+        let max = ((center + size / 2.0) / tile_size)
+            .ceil()
+            .as_uvec2()
+            .min(grid_size);
+        let mut candidates = Vec::new();
+        for (_, (layer, meta)) in self
+            .entities
+            .iter_with((&self.tile_layers, &self.spawned_map_layer_metas))
+        {
+            for x in min.x..max.x {
+                for y in min.y..max.y {
+                    let pos = uvec2(x, y);
+                    let tile_center = (pos.as_vec2() + 0.5) * tile_size;
+                    if (tile_center - center).abs().cmple(size / 2.0).all()
+                        && layer.get(pos).is_some()
+                    {
+                        candidates.push((meta.layer_idx, pos));
+                    }
+                }
+            }
+        }
+
+        let mut removed = Vec::new();
+        let mut destroyed = Vec::new();
+        for (layer_idx, pos) in candidates {
+            if self.remove_tile(layer_idx, pos) {
+                removed.push((layer_idx, pos));
+                if !destroyed.contains(&pos) {
+                    destroyed.push(pos);
+                }
+            }
+        }
+        if !destroyed.is_empty() {
+            self.update_collisions(removed);
+            self.update_nav_graph(&destroyed);
+        }
+        destroyed.len()
+    }
+
+    /// Remove the tile from its layer, without updating the collision world or the navigation
+    /// graph.
+    fn remove_tile(&mut self, layer_idx: u32, pos: UVec2) -> bool {
+        let Some((_, (layer, _))) = self
+            .entities
+            .iter_with((&mut self.tile_layers, &self.spawned_map_layer_metas))
+            .find(|x| x.1 .1.layer_idx == layer_idx)
+        else {
+            return false;
+        };
+        let Some(tile_ent) = layer.get(pos) else {
+            return false;
+        };
+        if !self.destructible_tiles.contains(tile_ent) {
+            return false;
+        }
+
+        layer.set(pos, None);
+        self.entities.kill(tile_ent);
+        true
+    }
+
+    /// Update the collision world for the removed tiles, given by their layer index and map grid
+    /// position, all at once.
+    fn update_collisions(&mut self, mut removed: Vec<(u32, UVec2)>) {
+        removed.sort_by_key(|(layer_idx, _)| *layer_idx);
+        self.commands
+            .add(move |mut collision_world: CollisionWorld| {
+                for layer in removed.chunk_by(|a, b| a.0 == b.0) {
+                    let positions = layer.iter().map(|(_, pos)| *pos).collect::<Vec<_>>();
+                    collision_world.update_tiles_at(layer[0].0, &positions);
+                }
+            });
+    }
+
+    /// Update the navigation graph around the given tile positions.
+    fn update_nav_graph(&mut self, changed: &[UVec2]) {
+        let layers = self
+            .entities
+            .iter_with(&self.tile_layers)
+            .map(|(_, layer)| layer)
+            .collect::<Vec<_>>();
+        let collision_at = |pos: UVec2| {
+            let mut collision = TileCollisionKind::Empty;
+            for layer in &layers {
+                let Some(kind) = layer
+                    .get(pos)
+                    .and_then(|tile_ent| self.tile_collisions.get(tile_ent))
+                else {
+                    continue;
+                };
+                // Solid tiles block the way no matter what the other layers have at this position.
+                if collision == TileCollisionKind::Empty || *kind == TileCollisionKind::Solid {
+                    collision = *kind;
+                }
+            }
+            collision
+        };
+
+        patch_nav_graph(
+            &mut self.nav_graph.0,
+            self.spawned_map_meta.grid_size,
+            changed,
+            collision_at,
+        );
+    }
+}
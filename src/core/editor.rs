@@ -1,6 +1,10 @@
 //! Map editing implementation.
 //!
 //! Allows you to edit the game map while the game is running.
+//!
+//! Every [`EditorInput`] applied to the map records the edits that revert it in the
+//! [`EditorHistory`] resource, so that it can be undone with [`EditorInput::Undo`] and redone with
+//! [`EditorInput::Redo`].
 
 use crate::core::map_constructor::{shiftnanigans::ShiftnanigansMapConstructor, MapConstructor};
 use crate::prelude::*;
@@ -26,14 +30,77 @@ impl_system_param! {
         entities: ResMutInit<'a, Entities>,
         spawned_map_meta: ResMutInit<'a, SpawnedMapMeta>,
         element_handles: CompMut<'a, ElementHandle>,
+        element_sizes: CompMut<'a, ElementSize>,
         transforms: CompMut<'a, Transform>,
         spawned_map_layer_metas: CompMut<'a, SpawnedMapLayerMeta>,
         tile_layers: CompMut<'a, TileLayer>,
         tiles: CompMut<'a, Tile>,
         tile_collisions: CompMut<'a, TileCollisionKind>,
+        destructible_tiles: CompMut<'a, DestructibleTile>,
+        physics_materials: CompMut<'a, PhysicsMaterial>,
         map: Res<'a, LoadedMap>,
+        meta: Root<'a, GameMeta>,
         element_kill_callbacks: Comp<'a, ElementKillCallback>,
         spawner_manager: SpawnerManager<'a>,
+        assets: Res<'a, AssetServer>,
+        history: ResMutInit<'a, EditorHistory>,
+    }
+}
+
+/// The maximum number of edits kept in the [`EditorHistory`].
+pub const EDITOR_HISTORY_LEN: usize = 100;
+
+/// Resource containing the edits that can be undone and redone in the map editor.
+#[derive(HasSchema, Clone, Default, Debug)]
+pub struct EditorHistory {
+    /// The edits that revert each of the applied editor inputs, most recent last.
+    undo: Vec<Vec<HistoryEdit>>,
+    /// The edits that re-apply each of the undone editor inputs, most recently undone last.
+    redo: Vec<Vec<HistoryEdit>>,
+    /// Elements that were deleted and spawned again by undoing or redoing, mapped to the entity
+    /// they were spawned again as, so that edits referring to the old entity still apply.
+    respawned: HashMap<Entity, Entity>,
+}
+
+impl EditorHistory {
+    /// Whether there is an edit that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether there is an edit that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forget all edits.
+    pub fn clear(&mut self) {
+        *self = default();
+    }
+
+    /// Get the entity that the given element entity was last spawned again as.
+    fn resolve(&self, mut entity: Entity) -> Entity {
+        while let Some(respawned) = self.respawned.get(&entity) {
+            entity = *respawned;
+        }
+        entity
+    }
+}
+
+/// A single edit in the [`EditorHistory`].
+#[derive(Clone, Debug)]
+struct HistoryEdit {
+    input: EditorInput,
+    /// If the input spawns an element, the entity of the deleted element that it restores.
+    restores: Option<Entity>,
+}
+
+impl From<EditorInput> for HistoryEdit {
+    fn from(input: EditorInput) -> Self {
+        Self {
+            input,
+            restores: None,
+        }
     }
 }
 
@@ -44,7 +111,7 @@ impl<'a> MapManager<'a> {
         element_meta_handle: &Handle<ElementMeta>,
         translation: &Vec2,
         layer_index: u32,
-    ) {
+    ) -> Entity {
         let entity = self.entities.create();
         // TODO remove element handles as the underlying elements are removed
         self.element_handles
@@ -60,6 +127,7 @@ impl<'a> MapManager<'a> {
                 layer_idx: layer_index,
             },
         );
+        entity
     }
     /// Create a new layer with the given name.
     pub fn create_layer(&mut self, name: Ustr) {
@@ -157,6 +225,10 @@ impl<'a> MapManager<'a> {
         transform.translation.x = position.x;
         transform.translation.y = position.y;
     }
+    /// Set the size of an element that can be resized, such as water, in tiles.
+    pub fn resize_element(&mut self, entity: Entity, size: UVec2) {
+        self.element_sizes.insert(entity, ElementSize(size));
+    }
     /// Delete an element off of the map.
     pub fn delete_element(&mut self, entity: Entity) {
         if let Some(element_kill_callback) = self.element_kill_callbacks.get(entity) {
@@ -239,12 +311,40 @@ impl<'a> MapManager<'a> {
                 }
             }
 
+            // Give the tile the physics material of its tileset
+            if let (Some(entity), Some(idx)) = (tile_layer.get(position), tilemap_tile_index) {
+                match self
+                    .meta
+                    .core
+                    .tile_material(&self.assets, tile_layer.atlas, *idx)
+                {
+                    Some(material) => self.physics_materials.insert(entity, material),
+                    None => self.physics_materials.remove(entity),
+                };
+            }
+
             self.commands
                 .add(move |mut collision_world: CollisionWorld| {
                     collision_world.update_tile(layer_index, position);
                 });
         };
     }
+    /// Set whether a tile on the given layer can be destroyed by explosions.
+    pub fn set_tile_destructible(&mut self, layer_index: u32, position: UVec2, destructible: bool) {
+        let Some(entity) = self
+            .entities
+            .iter_with((&self.tile_layers, &self.spawned_map_layer_metas))
+            .find(|x| x.1 .1.layer_idx == layer_index)
+            .and_then(|(_, (tile_layer, _))| tile_layer.get(position))
+        else {
+            return;
+        };
+        if destructible {
+            self.destructible_tiles.insert(entity, DestructibleTile);
+        } else {
+            self.destructible_tiles.remove(entity);
+        }
+    }
     /// Swap the position of two layers.
     pub fn swap_layer(&mut self, layer_index: u32, is_downward: bool) {
         let origin_layer_index = layer_index;
@@ -307,67 +407,444 @@ impl<'a> MapManager<'a> {
             self.delete_element(entity);
         });
     }
-}
+    /// Apply an editor input to the map, recording it in the [`EditorHistory`] so that it can be
+    /// undone.
+    ///
+    /// Randomizing the tiles can't be undone, so it clears the history.
+    pub fn edit(&mut self, input: EditorInput) {
+        // Consecutive moves or resizes of the same element, like while it is being dragged, are
+        // undone together.
+        let merge_move = self.history.redo.is_empty()
+            && match (&input, self.history.undo.last().map(Vec::as_slice)) {
+                (
+                    EditorInput::MoveEntity { entity, .. },
+                    Some(
+                        [HistoryEdit {
+                            input: EditorInput::MoveEntity { entity: last, .. },
+                            ..
+                        }],
+                    ),
+                )
+                | (
+                    EditorInput::ResizeElement { entity, .. },
+                    Some(
+                        [HistoryEdit {
+                            input: EditorInput::ResizeElement { entity: last, .. },
+                            ..
+                        }],
+                    ),
+                ) => self.history.resolve(*entity) == *last,
+                _ => false,
+            };
+        let is_randomize = matches!(input, EditorInput::RandomizeTiles { .. });
 
-/// Handles user input comming from the editor and makes the required changes to the map.
-fn handle_editor_input(player_inputs: Res<MatchInputs>, mut map_manager: MapManager) {
-    for player in &player_inputs.players {
-        if let Some(editor_input) = &player.editor_input {
-            match editor_input {
-                EditorInput::SpawnElement {
-                    handle,
-                    translation,
-                    layer,
-                } => {
-                    map_manager.create_element(handle, translation, *layer as u32);
+        let inverse = self.apply_edits(vec![input.into()]);
+        if is_randomize {
+            self.history.clear();
+            return;
+        }
+        if inverse.is_empty() {
+            return;
+        }
+
+        self.history.redo.clear();
+        if !merge_move {
+            if self.history.undo.len() >= EDITOR_HISTORY_LEN {
+                self.history.undo.remove(0);
+            }
+            self.history.undo.push(inverse);
+        }
+    }
+    /// Undo the last edit made with [`MapManager::edit`].
+    pub fn undo(&mut self) {
+        if let Some(edits) = self.history.undo.pop() {
+            let inverse = self.apply_edits(edits);
+            self.history.redo.push(inverse);
+        }
+    }
+    /// Redo the last edit that was undone with [`MapManager::undo`].
+    pub fn redo(&mut self) {
+        if let Some(edits) = self.history.redo.pop() {
+            let inverse = self.apply_edits(edits);
+            self.history.undo.push(inverse);
+        }
+    }
+    /// Apply the edits in order, returning the edits that revert all of them.
+    fn apply_edits(&mut self, edits: Vec<HistoryEdit>) -> Vec<HistoryEdit> {
+        let inverses = edits
+            .into_iter()
+            .map(|edit| self.apply_edit(edit))
+            .collect::<Vec<_>>();
+        // The edits must be reverted in the opposite order to the one they were applied in.
+        inverses.into_iter().rev().flatten().collect()
+    }
+    /// Apply a single edit, returning the edits that revert it.
+    fn apply_edit(&mut self, edit: HistoryEdit) -> Vec<HistoryEdit> {
+        let HistoryEdit { input, restores } = edit;
+        match input {
+            EditorInput::SpawnElement {
+                handle,
+                translation,
+                layer,
+            } => {
+                let entity = self.create_element(&handle, &translation, layer as u32);
+                if let Some(deleted) = restores {
+                    self.history.respawned.insert(deleted, entity);
                 }
-                EditorInput::CreateLayer { id } => {
-                    map_manager.create_layer(ustr(id));
+                vec![EditorInput::DeleteEntity { entity }.into()]
+            }
+            EditorInput::MoveEntity { entity, pos } => {
+                let entity = self.history.resolve(entity);
+                let Some(transform) = self.transforms.get(entity) else {
+                    return Vec::new();
+                };
+                let previous = transform.translation.truncate();
+                self.move_element(entity, &pos);
+                vec![EditorInput::MoveEntity {
+                    entity,
+                    pos: previous,
+                }
+                .into()]
+            }
+            EditorInput::DeleteEntity { entity } => {
+                let entity = self.history.resolve(entity);
+                let inverse = self.element_spawn_edits(entity);
+                self.delete_element(entity);
+                inverse
+            }
+            EditorInput::ResizeElement { entity, size } => {
+                let entity = self.history.resolve(entity);
+                if !self.element_handles.contains(entity) || size.cmpeq(UVec2::ZERO).any() {
+                    return Vec::new();
                 }
-                EditorInput::DeleteLayer { layer } => {
-                    map_manager.delete_layer(*layer as u32);
+                let previous = self.element_sizes.get(entity).map(|size| size.0);
+                if previous == Some(size) {
+                    return Vec::new();
                 }
-                EditorInput::RenameLayer {
+                self.resize_element(entity, size);
+                // Elements that were just spawned again don't have a size yet, and are deleted
+                // by the edit that reverts their spawn instead.
+                previous
+                    .map(|previous| {
+                        EditorInput::ResizeElement {
+                            entity,
+                            size: previous,
+                        }
+                        .into()
+                    })
+                    .into_iter()
+                    .collect()
+            }
+            EditorInput::CreateLayer { id } => {
+                let layer = self.get_layers_total() as u8;
+                self.create_layer(ustr(&id));
+                vec![EditorInput::DeleteLayer { layer }.into()]
+            }
+            EditorInput::DeleteLayer { layer } => {
+                if layer as usize >= self.get_layers_total() {
+                    return Vec::new();
+                }
+                let inverse = self.layer_restore_edits(layer as u32);
+                self.delete_layer(layer as u32);
+                inverse
+            }
+            EditorInput::RenameLayer { layer, name } => {
+                let Some(previous) = self.layer_name(layer as u32) else {
+                    return Vec::new();
+                };
+                self.rename_layer(layer as u32, &name);
+                vec![EditorInput::RenameLayer {
                     layer,
-                    name: new_name,
-                } => map_manager.rename_layer(*layer as u32, new_name),
-                EditorInput::MoveEntity { entity, pos } => {
-                    map_manager.move_element(*entity, pos);
+                    name: previous,
                 }
-                EditorInput::DeleteEntity { entity } => {
-                    map_manager.delete_element(*entity);
+                .into()]
+            }
+            EditorInput::MoveLayer { layer, down } => {
+                let other = if down {
+                    layer.checked_add(1)
+                } else {
+                    layer.checked_sub(1)
+                };
+                let Some(other) = other.filter(|other| (*other as usize) < self.get_layers_total())
+                else {
+                    return Vec::new();
+                };
+                self.swap_layer(layer as u32, down);
+                vec![EditorInput::MoveLayer {
+                    layer: other,
+                    down: !down,
                 }
-                EditorInput::SetTilemap { layer, handle } => {
-                    map_manager.set_layer_tilemap(*layer as u32, handle);
+                .into()]
+            }
+            EditorInput::SetTilemap { layer, handle } => {
+                let previous = self.layer_tilemap(layer as u32);
+                self.set_layer_tilemap(layer as u32, &handle);
+                vec![EditorInput::SetTilemap {
+                    layer,
+                    handle: previous,
                 }
-                EditorInput::SetTile {
+                .into()]
+            }
+            EditorInput::SetTile {
+                layer,
+                pos,
+                tilemap_tile_idx,
+                collision,
+                destructible,
+            } => {
+                let (previous_idx, previous_collision, previous_destructible) =
+                    self.get_tile(layer as u32, pos);
+                self.set_tile(layer as u32, pos, &tilemap_tile_idx, collision);
+                self.set_tile_destructible(layer as u32, pos, destructible);
+                vec![EditorInput::SetTile {
                     layer,
                     pos,
-                    tilemap_tile_idx,
-                    collision,
-                } => {
-                    map_manager.set_tile(*layer as u32, *pos, tilemap_tile_idx, *collision);
+                    tilemap_tile_idx: previous_idx,
+                    collision: previous_collision,
+                    destructible: previous_destructible,
                 }
-                EditorInput::MoveLayer { layer, down } => {
-                    map_manager.swap_layer(*layer as u32, *down)
+                .into()]
+            }
+            EditorInput::PaintTerrain {
+                layer,
+                pos,
+                terrain,
+                erase,
+            } => {
+                let edits = self.terrain_edits(layer as u32, pos, terrain, erase);
+                self.apply_edits(edits.into_iter().map(HistoryEdit::from).collect())
+            }
+            EditorInput::RenameMap { name } => {
+                let previous = self.spawned_map_meta.name.to_string();
+                self.rename_map(&name);
+                vec![EditorInput::RenameMap { name: previous }.into()]
+            }
+            EditorInput::RandomizeTiles {
+                tile_layers,
+                element_layers,
+                tile_size,
+            } => {
+                let map_constructor = ShiftnanigansMapConstructor::new(
+                    self.get_size(),
+                    tile_size,
+                    &tile_layers,
+                    &element_layers,
+                );
+                map_constructor.construct_map(self);
+                Vec::new()
+            }
+            // Undo and redo are handled by `handle_editor_input` and are never recorded.
+            EditorInput::Undo | EditorInput::Redo => Vec::new(),
+        }
+    }
+    /// Get the name of the layer with the given index.
+    fn layer_name(&self, layer_index: u32) -> Option<String> {
+        self.spawned_map_meta
+            .layer_names
+            .iter()
+            .nth(layer_index as usize)
+            .map(|name| name.to_string())
+    }
+    /// Get the tilemap of the given layer, if it has one.
+    fn layer_tilemap(&self, layer_index: u32) -> Option<Handle<Atlas>> {
+        self.entities
+            .iter_with((&self.tile_layers, &self.spawned_map_layer_metas))
+            .find(|(_, (_, layer))| layer.layer_idx == layer_index)
+            .map(|(_, (tile_layer, _))| tile_layer.atlas)
+            .filter(|atlas| *atlas != default())
+    }
+    /// Get the tilemap index, collision kind, and whether the tile is destructible, of a tile on
+    /// the given layer.
+    fn get_tile(
+        &self,
+        layer_index: u32,
+        position: UVec2,
+    ) -> (Option<u32>, TileCollisionKind, bool) {
+        self.entities
+            .iter_with((&self.tile_layers, &self.spawned_map_layer_metas))
+            .find(|(_, (_, layer))| layer.layer_idx == layer_index)
+            .and_then(|(_, (tile_layer, _))| tile_layer.get(position))
+            .and_then(|entity| {
+                let idx = self.tiles.get(entity)?.idx;
+                let collision = self.tile_collisions.get(entity).copied();
+                let destructible = self.destructible_tiles.contains(entity);
+                Some((Some(idx), collision.unwrap_or_default(), destructible))
+            })
+            .unwrap_or_default()
+    }
+    /// Get the tile edits that paint or erase a terrain tile, and update the terrain tiles around
+    /// it to match.
+    fn terrain_edits(
+        &self,
+        layer_index: u32,
+        position: UVec2,
+        terrain: Handle<AutotileMeta>,
+        erase: bool,
+    ) -> Vec<EditorInput> {
+        let terrain = self.assets.get(terrain).clone();
+        let layer = layer_index as u8;
+        if layer_index as usize >= self.get_layers_total() {
+            return Vec::new();
+        }
+
+        let mut edits = Vec::new();
+        match self.layer_tilemap(layer_index) {
+            None => edits.push(EditorInput::SetTilemap {
+                layer,
+                handle: Some(terrain.tilemap),
+            }),
+            Some(tilemap) if tilemap != terrain.tilemap => return Vec::new(),
+            Some(_) => (),
+        }
+
+        let center = position.as_ivec2();
+        let grid_size = self.get_size().as_ivec2();
+        let is_terrain = |pos: IVec2| {
+            if pos.cmplt(IVec2::ZERO).any() || pos.cmpge(grid_size).any() {
+                false
+            } else if pos == center {
+                !erase
+            } else {
+                self.get_tile(layer_index, pos.as_uvec2()).0.is_some()
+            }
+        };
+
+        if erase {
+            edits.push(EditorInput::SetTile {
+                layer,
+                pos: position,
+                tilemap_tile_idx: None,
+                collision: TileCollisionKind::Empty,
+                destructible: false,
+            });
+        }
+        for (x, y) in std::iter::once((0, 0)).chain(neighbours::OFFSETS.map(|(x, y, _)| (x, y))) {
+            let pos = center + ivec2(x, y);
+            if !is_terrain(pos) {
+                continue;
+            }
+            let (collision, destructible) = if pos == center {
+                (terrain.collision, self.get_tile(layer_index, position).2)
+            } else {
+                // Only update the neighbours painted with this terrain, so that tiles placed by
+                // hand are kept.
+                match self.get_tile(layer_index, pos.as_uvec2()) {
+                    (Some(idx), collision, destructible) if terrain.contains(idx) => {
+                        (collision, destructible)
+                    }
+                    _ => continue,
                 }
-                EditorInput::RenameMap { name } => {
-                    map_manager.rename_map(name);
+            };
+            let neighbours = neighbours::OFFSETS
+                .iter()
+                .filter(|(x, y, _)| is_terrain(pos + ivec2(*x, *y)))
+                .fold(0, |neighbours, (_, _, flag)| neighbours | flag);
+            edits.push(EditorInput::SetTile {
+                layer,
+                pos: pos.as_uvec2(),
+                tilemap_tile_idx: Some(terrain.tile_idx(neighbours)),
+                collision,
+                destructible,
+            });
+        }
+        edits
+    }
+    /// Get the edits that spawn the given map element again after it is deleted, with the size
+    /// that it had.
+    fn element_spawn_edits(&self, entity: Entity) -> Vec<HistoryEdit> {
+        let Some(handle) = self.element_handles.get(entity).map(|handle| handle.0) else {
+            return Vec::new();
+        };
+        let (Some(transform), Some(layer_meta)) = (
+            self.transforms.get(entity),
+            self.spawned_map_layer_metas.get(entity),
+        ) else {
+            return Vec::new();
+        };
+        let mut edits = vec![HistoryEdit {
+            input: EditorInput::SpawnElement {
+                handle,
+                translation: transform.translation.truncate(),
+                layer: layer_meta.layer_idx as u8,
+            },
+            restores: Some(entity),
+        }];
+        if let Some(size) = self.element_sizes.get(entity) {
+            edits.push(
+                EditorInput::ResizeElement {
+                    entity,
+                    size: size.0,
                 }
-                EditorInput::RandomizeTiles {
-                    tile_layers,
-                    element_layers,
-                    tile_size,
-                } => {
-                    let map_constructor = ShiftnanigansMapConstructor::new(
-                        map_manager.get_size(),
-                        *tile_size,
-                        tile_layers,
-                        element_layers,
+                .into(),
+            );
+        }
+        edits
+    }
+    /// Get the edits that create the given layer again after it is deleted, with all of its tiles
+    /// and elements.
+    fn layer_restore_edits(&self, layer_index: u32) -> Vec<HistoryEdit> {
+        let layer = layer_index as u8;
+        let name = self.layer_name(layer_index).unwrap_or_default();
+
+        // The layer is created after the remaining layers, and then moved back to its index.
+        let mut edits: Vec<HistoryEdit> = vec![EditorInput::CreateLayer { id: name }.into()];
+        let last = self.get_layers_total() as u8 - 1;
+        edits.extend(
+            (layer + 1..=last)
+                .rev()
+                .map(|current| EditorInput::MoveLayer {
+                    layer: current,
+                    down: false,
+                })
+                .map(HistoryEdit::from),
+        );
+        edits.push(
+            EditorInput::SetTilemap {
+                layer,
+                handle: self.layer_tilemap(layer_index),
+            }
+            .into(),
+        );
+
+        let grid_size = self.spawned_map_meta.grid_size;
+        for y in 0..grid_size.y {
+            for x in 0..grid_size.x {
+                let pos = uvec2(x, y);
+                if let (Some(idx), collision, destructible) = self.get_tile(layer_index, pos) {
+                    edits.push(
+                        EditorInput::SetTile {
+                            layer,
+                            pos,
+                            tilemap_tile_idx: Some(idx),
+                            collision,
+                            destructible,
+                        }
+                        .into(),
                     );
-                    map_constructor.construct_map(&mut map_manager);
                 }
             }
         }
+
+        edits.extend(
+            self.entities
+                .iter_with((&self.element_handles, &self.spawned_map_layer_metas))
+                .filter(|(_, (_, layer_meta))| layer_meta.layer_idx == layer_index)
+                .flat_map(|(entity, _)| self.element_spawn_edits(entity)),
+        );
+        edits
+    }
+}
+
+/// Handles user input comming from the editor and makes the required changes to the map.
+fn handle_editor_input(mut player_inputs: ResMut<MatchInputs>, mut map_manager: MapManager) {
+    for player in &mut player_inputs.players {
+        // Take the input so that it is only applied once.
+        if let Some(editor_input) = player.editor_input.take() {
+            match editor_input {
+                EditorInput::Undo => map_manager.undo(),
+                EditorInput::Redo => map_manager.redo(),
+                editor_input => map_manager.edit(editor_input),
+            }
+        }
     }
 }
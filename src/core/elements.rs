@@ -18,6 +18,7 @@ pub mod jellyfish;
 pub mod kick_bomb;
 pub mod machine_gun;
 pub mod mine;
+pub mod moving_platform;
 pub mod musket;
 pub mod periscope;
 pub mod player_spawner;
@@ -29,13 +30,14 @@ pub mod sproinger;
 pub mod stomp_boots;
 pub mod sword;
 pub mod urchin;
+pub mod water;
 
 pub mod prelude {
     pub use super::{
         buss::*, crab::*, crate_item::*, decoration::*, fish_school::*, grenade::*, jellyfish::*,
-        kick_bomb::*, machine_gun::*, mine::*, musket::*, periscope::*, player_spawner::*,
-        slippery::*, slippery_seaweed::*, snail::*, spike::*, sproinger::*, stomp_boots::*,
-        sword::*, urchin::*, *,
+        kick_bomb::*, machine_gun::*, mine::*, moving_platform::*, musket::*, periscope::*,
+        player_spawner::*, slippery::*, slippery_seaweed::*, snail::*, spike::*, sproinger::*,
+        stomp_boots::*, sword::*, urchin::*, water::*, *,
     };
 }
 
@@ -48,6 +50,11 @@ pub struct ElementMeta {
     pub data: Handle<SchemaBox>,
     pub editor: ElementEditorMeta,
     pub plugin: Handle<LuaPlugin>,
+    /// The physics material of the bodies and solids that the element spawns, if any.
+    ///
+    /// This works for every kind of element, so for example the same patch element can be made
+    /// icy or sticky just by giving it a different material.
+    pub material: Handle<PhysicsMaterial>,
 }
 
 #[derive(HasSchema, Default, Debug, Clone, Copy)]
@@ -103,6 +110,11 @@ pub struct ElementHandle(pub Handle<ElementMeta>);
 #[repr(C)]
 pub struct ElementSolid(pub Entity);
 
+/// The size in tiles of a map element that can be resized in the editor, such as water.
+#[derive(Clone, Copy, HasSchema, Default, Debug, Deref, DerefMut)]
+#[repr(C)]
+pub struct ElementSize(pub UVec2);
+
 #[derive(Clone, HasSchema)]
 #[schema(no_default)]
 pub struct ElementKillCallback {
@@ -203,6 +215,23 @@ impl<'a> SpawnerManager<'a> {
         self.spawners.insert(entity, spawner);
     }
 
+    /// Replaces the elements spawned by the provided spawner entity, returning the elements that it
+    /// spawned before.
+    pub fn replace_spawned_elements(
+        &mut self,
+        entity: Entity,
+        spawned_elements: Vec<Entity>,
+    ) -> Vec<Entity> {
+        let Some(spawner) = self.spawners.get(entity) else {
+            self.create_spawner(entity, spawned_elements);
+            return Vec::new();
+        };
+        self.spawner_entities
+            .entities_per_spawner_group_identifier
+            .insert(spawner.group_identifier.clone(), spawned_elements)
+            .unwrap_or_default()
+    }
+
     /// Stores the spawned elements as having come from the same group of spawners as the spawner_elements.
     pub fn insert_spawned_entity_into_grouped_spawner<T: HasSchema>(
         &mut self,
@@ -239,6 +268,18 @@ impl<'a> SpawnerManager<'a> {
     pub fn is_entity_a_spawner(&self, entity: Entity) -> bool {
         self.spawners.contains(entity)
     }
+    /// Get the elements spawned by the provided spawner entity, along with the ones spawned by the
+    /// rest of its group.
+    pub fn spawned_elements(&self, entity: Entity) -> &[Entity] {
+        self.spawners
+            .get(entity)
+            .and_then(|spawner| {
+                self.spawner_entities
+                    .entities_per_spawner_group_identifier
+                    .get(&spawner.group_identifier)
+            })
+            .map_or(&[][..], Vec::as_slice)
+    }
     /// Kills the provided spawner entity and any spawned entities (if applicable)
     pub fn kill_spawner_entity(
         &mut self,
@@ -286,6 +327,7 @@ macro_rules! install_plugins {
     ($($module:ident),* $(,)?) => {
         pub fn session_plugin(session: &mut Session) {
             ElementHandle::register_schema();
+            ElementSize::register_schema();
             MapElementHydrated::register_schema();
             DehydrateOutOfBounds::register_schema();
 
@@ -296,6 +338,11 @@ macro_rules! install_plugins {
             $(
                 session.install_plugin($module::session_plugin);
             )*
+
+            // Added after the element plugins, so that it runs after they hydrate their elements.
+            session
+                .stages
+                .add_system_to_stage(CoreStage::PreUpdate, apply_element_materials);
         }
 
         pub fn game_plugin(game: &mut Game) {
@@ -320,6 +367,7 @@ install_plugins!(
     kick_bomb,
     mine,
     machine_gun,
+    moving_platform,
     musket,
     buss,
     player_spawner,
@@ -332,8 +380,42 @@ install_plugins!(
     stomp_boots,
     sword,
     urchin,
+    water,
 );
 
+/// Give the bodies and solids of hydrated map elements, and of the entities that they spawned, the
+/// [`material`][ElementMeta::material] of their element.
+///
+/// Entities that already have a material, such as the ones given to them by the element itself,
+/// keep it.
+fn apply_element_materials(
+    entities: Res<Entities>,
+    element_handles: Comp<ElementHandle>,
+    hydrated: Comp<MapElementHydrated>,
+    assets: Res<AssetServer>,
+    spawner_manager: SpawnerManager,
+    bodies: Comp<KinematicBody>,
+    solids: Comp<Solid>,
+    mut physics_materials: CompMut<PhysicsMaterial>,
+) {
+    for (entity, (element_handle, _)) in entities.iter_with((&element_handles, &hydrated)) {
+        let element_meta = assets.get(element_handle.0);
+        if element_meta.material == default() {
+            continue;
+        }
+        let material = *assets.get(element_meta.material);
+
+        let spawned = spawner_manager.spawned_elements(entity);
+        for target in std::iter::once(entity).chain(spawned.iter().copied()) {
+            if (bodies.contains(target) || solids.contains(target))
+                && !physics_materials.contains(target)
+            {
+                physics_materials.insert(target, material);
+            }
+        }
+    }
+}
+
 fn handle_out_of_bounds_items(
     mut commands: Commands,
     mut hydrated: CompMut<MapElementHydrated>,
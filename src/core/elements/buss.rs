@@ -158,6 +158,7 @@ fn update(
     mut bodies: CompMut<KinematicBody>,
 ) {
     for (entity, (buss, element_handle)) in entities.iter_with((&mut busses, &element_handles)) {
+        let element = element_handle.0;
         let element_meta = assets.get(element_handle.0);
 
         let asset = assets.get(element_meta.data);
@@ -270,6 +271,7 @@ fn update(
                                     ent,
                                     Bullet {
                                         owner: player,
+                                        weapon: Set(element),
                                         direction: if player_flip_x {
                                             vec2(-1.0, (rng.f32() - 0.5) * bullet_spread)
                                         } else {
@@ -8,6 +8,9 @@ pub struct CannonMeta {
     pub fin_anim: Ustr,
 
     pub body_size: Vec2,
+    /// The collider of the cannon with the muzzle on the right, used instead of the `body_size`
+    /// rectangle and mirrored with the sprite.
+    pub body_polygon: SVec<Vec2>,
     pub bounciness: f32,
     pub can_rotate: bool,
     pub throw_velocity: f32,
@@ -59,6 +62,7 @@ fn hydrate(
     mut cannons: CompMut<Cannon>,
     mut atlas_sprites: CompMut<AtlasSprite>,
     mut bodies: CompMut<KinematicBody>,
+    mut mirror_colliders: CompMut<MirrorCollider>,
     mut transforms: CompMut<Transform>,
     mut items: CompMut<Item>,
     mut item_throws: CompMut<ItemThrow>,
@@ -85,6 +89,7 @@ fn hydrate(
             grab_offset,
             max_ammo,
             body_size,
+            body_polygon,
             can_rotate,
             bounciness,
             throw_velocity,
@@ -123,10 +128,14 @@ fn hydrate(
             transforms.insert(entity, transform);
             element_handles.insert(entity, element_handle);
             hydrated.insert(entity, MapElementHydrated);
+            mirror_colliders.insert(entity, MirrorCollider::default());
             bodies.insert(
                 entity,
                 KinematicBody {
-                    shape: ColliderShape::Rectangle { size: *body_size },
+                    shape: ColliderShape::from_body_polygon(
+                        body_polygon,
+                        ColliderShape::Rectangle { size: *body_size },
+                    ),
                     has_mass: true,
                     has_friction: true,
                     can_rotate: *can_rotate,
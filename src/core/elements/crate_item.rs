@@ -17,6 +17,11 @@ pub struct CrateMeta {
     pub bounce_sound_volume: f64,
 
     pub throw_velocity: f32,
+    /// The damage that a thrown crate deals to the players it hits.
+    pub damage: f32,
+    /// The speed that a thrown crate knocks the players it hits back at, in the direction it's
+    /// moving.
+    pub knockback: f32,
 
     pub body_size: Vec2,
     pub grab_offset: Vec2,
@@ -220,6 +225,8 @@ fn update_thrown_crates(
             bounce_sound_volume,
             crate_break_state_1,
             crate_break_state_2,
+            damage,
+            knockback,
             ..
         }) = asset.try_cast_ref()
         else {
@@ -228,6 +235,12 @@ fn update_thrown_crates(
 
         thrown_crate.damage_delay.tick(time.delta());
         thrown_crate.break_timeout.tick(time.delta());
+        let source = DamageSource::item(element_handle.0).with_killer(thrown_crate.owner);
+        let hit_from = transform.translation.xy();
+        let (damage, knockback) = (*damage, body.velocity.normalize_or_zero() * *knockback);
+        let damage_player = |player: Entity| {
+            PlayerCommand::damage(player, damage, knockback, Some(hit_from), source)
+        };
 
         let colliding_with_tile = {
             let collider = collision_world.get_collider(entity);
@@ -271,22 +284,19 @@ fn update_thrown_crates(
             .collect::<Vec<_>>();
 
         for player_entity in &colliding_with_players {
-            commands.add(PlayerCommand::kill(
-                *player_entity,
-                Some(transform.translation.xy()),
-            ));
+            commands.add(damage_player(*player_entity));
         }
-        let kill_nearby_colliding: bool = kill_all_colliding_if_freshly_thrown(
+        let damage_nearby_colliding: bool = damage_all_colliding_if_freshly_thrown(
             thrown_crate,
             &collision_world,
             &players,
             &invincibles,
             &mut commands,
-            transform,
+            damage_player,
         );
 
         if !colliding_with_players.is_empty()
-            || kill_nearby_colliding
+            || damage_nearby_colliding
             || thrown_crate.break_timeout.finished()
             || thrown_crate.crate_break_state >= 4
             || body.is_on_ground && body.velocity.length_squared() < 0.1
@@ -326,13 +336,13 @@ fn update_thrown_crates(
     }
 }
 
-fn kill_all_colliding_if_freshly_thrown(
+fn damage_all_colliding_if_freshly_thrown(
     thrown_crate: &ThrownCrate,
     collision_world: &CollisionWorld,
     players: &Comp<PlayerIdx>,
     invincibles: &CompMut<Invincibility>,
     commands: &mut Commands,
-    transform: &Transform,
+    damage_player: impl Fn(Entity) -> StaticSystem<(), ()>,
 ) -> bool {
     if thrown_crate.damage_delay.finished() {
         return false;
@@ -346,16 +356,10 @@ fn kill_all_colliding_if_freshly_thrown(
     if !colliding_with_players.is_empty() {
         for player_entity in &colliding_with_players {
             if invincibles.get(*player_entity).is_none() {
-                commands.add(PlayerCommand::kill(
-                    *player_entity,
-                    Some(transform.translation.xy()),
-                ));
+                commands.add(damage_player(*player_entity));
             }
         }
-        commands.add(PlayerCommand::kill(
-            thrown_crate.owner,
-            Some(transform.translation.xy()),
-        ));
+        commands.add(damage_player(thrown_crate.owner));
         true
     } else {
         false
@@ -20,6 +20,10 @@ pub struct FlappyJellyfishMeta {
     pub explosion_sound: Handle<AudioSource>,
     pub damage_region_size: Vec2,
     pub damage_region_lifetime: f32,
+    /// The push that the explosion gives to the bodies around it.
+    pub explosion_impulse: RadialImpulse,
+    /// The damage that the explosion deals to the players caught in it.
+    pub explosion_damage: f32,
 }
 
 impl FlappyJellyfishMeta {
@@ -232,6 +236,8 @@ fn explode_flappy_jellyfish(
     mut sprites: CompMut<AtlasSprite>,
     mut animated_sprites: CompMut<AnimatedSprite>,
     mut damage_regions: CompMut<DamageRegion>,
+    mut damage_region_sources: CompMut<DamageRegionSource>,
+    mut radial_impulses: CompMut<RadialImpulse>,
     mut lifetimes: CompMut<Lifetime>,
     mut dehydrate_jellyfish: CompMut<DehydrateJellyfish>,
 ) {
@@ -282,12 +288,13 @@ fn explode_flappy_jellyfish(
          * Get explosion data
          */
 
-        let Some(flappy_meta) = element_handles
-            .get(flappy.jellyfish)
-            .map(|element_h| assets.get(element_h.0))
-            .map(|element_meta| assets.get(element_meta.data))
-            .as_deref()
-            .and_then(SchemaBox::try_get_flappy_meta)
+        let Some(element_handle) = element_handles.get(flappy.jellyfish).copied() else {
+            return;
+        };
+        let element_meta = assets.get(element_handle.0);
+        let Some(flappy_meta) = assets
+            .get(element_meta.data)
+            .try_get_flappy_meta()
             .map(|flappy_h| assets.get(flappy_h))
         else {
             return;
@@ -339,12 +346,19 @@ fn explode_flappy_jellyfish(
                 damage_ent,
                 DamageRegion {
                     size: flappy_meta.damage_region_size,
+                    damage: flappy_meta.explosion_damage,
+                    ..default()
                 },
             );
+            damage_region_sources.insert(
+                damage_ent,
+                DamageRegionSource(DamageSource::item(element_handle.0).with_killer(flappy.owner)),
+            );
             lifetimes.insert(
                 damage_ent,
                 Lifetime::new(flappy_meta.damage_region_lifetime),
             );
+            radial_impulses.insert(damage_ent, flappy_meta.explosion_impulse);
         }
 
         /*
@@ -10,6 +10,10 @@ pub struct GrenadeMeta {
     pub grab_offset: Vec2,
     pub damage_region_size: Vec2,
     pub damage_region_lifetime: f32,
+    /// The push that the explosion gives to the bodies around it.
+    pub explosion_impulse: RadialImpulse,
+    /// The damage that the explosion deals to the players caught in it.
+    pub explosion_damage: f32,
     pub throw_velocity: f32,
     pub explosion_lifetime: f32,
     pub explosion_frames: u32,
@@ -223,6 +227,8 @@ fn update_lit_grenades(
             explosion_volume,
             damage_region_lifetime,
             damage_region_size,
+            explosion_impulse,
+            explosion_damage,
             explosion_lifetime,
             explosion_atlas,
             explosion_fps,
@@ -279,14 +285,21 @@ fn update_lit_grenades(
             // Clone types for move into closure
             let damage_region_size = *damage_region_size;
             let damage_region_lifetime = *damage_region_lifetime;
+            let explosion_impulse = *explosion_impulse;
+            let explosion_damage = *explosion_damage;
             let explosion_lifetime = *explosion_lifetime;
             let explosion_atlas = *explosion_atlas;
             let explosion_fps = *explosion_fps;
             let explosion_frames = *explosion_frames;
+
+            let source = DamageSource::item(element_handle.0).with_killer(grenade.owner);
             commands.add(
                 move |mut entities: ResMutInit<Entities>,
                       mut transforms: CompMut<Transform>,
                       mut damage_regions: CompMut<DamageRegion>,
+                      mut damage_region_sources: CompMut<DamageRegionSource>,
+                      mut radial_impulses: CompMut<RadialImpulse>,
+                      mut destroys_tiles: CompMut<DestroysTiles>,
                       mut lifetimes: CompMut<Lifetime>,
                       mut sprites: CompMut<AtlasSprite>,
                       mut animated_sprites: CompMut<AnimatedSprite>| {
@@ -300,9 +313,14 @@ fn update_lit_grenades(
                         ent,
                         DamageRegion {
                             size: damage_region_size,
+                            damage: explosion_damage,
+                            ..default()
                         },
                     );
+                    damage_region_sources.insert(ent, DamageRegionSource(source));
                     lifetimes.insert(ent, Lifetime::new(damage_region_lifetime));
+                    radial_impulses.insert(ent, explosion_impulse);
+                    destroys_tiles.insert(ent, DestroysTiles);
 
                     // Spawn the explosion animation
                     let ent = entities.create();
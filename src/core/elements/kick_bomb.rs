@@ -5,10 +5,16 @@ use crate::prelude::*;
 #[repr(C)]
 pub struct KickBombMeta {
     pub body_diameter: f32,
+    /// Collider used instead of the `body_diameter` circle, if it isn't empty.
+    pub body_polygon: SVec<Vec2>,
     pub fin_anim: Ustr,
     pub grab_offset: Vec2,
     pub damage_region_size: Vec2,
     pub damage_region_lifetime: f32,
+    /// The push that the explosion gives to the bodies around it.
+    pub explosion_impulse: RadialImpulse,
+    /// The damage that the explosion deals to the players caught in it.
+    pub explosion_damage: f32,
     pub kick_velocity: Vec2,
     pub kickable: bool,
     pub throw_velocity: f32,
@@ -55,6 +61,8 @@ pub struct LitKickBomb {
     fuse_time: Timer,
     kicking: bool,
     kicks: u32,
+    /// The player that lit or last kicked the bomb, who is blamed for its explosion.
+    pub owner: Maybe<Entity>,
 }
 
 /// Component containing the kick bombs's metadata handle.
@@ -121,6 +129,13 @@ impl KickBombCommand {
                 ..
             } = *assets.get(kick_bomb_meta_handle);
 
+            let shape = ColliderShape::from_body_polygon(
+                &assets.get(kick_bomb_meta_handle).body_polygon,
+                ColliderShape::Circle {
+                    diameter: body_diameter,
+                },
+            );
+
             kick_bomb_handles.insert(entity, KickBombHandle(kick_bomb_meta_handle));
             items.insert(entity, Item);
             item_throws.insert(
@@ -141,9 +156,7 @@ impl KickBombCommand {
             bodies.insert(
                 entity,
                 KinematicBody {
-                    shape: ColliderShape::Circle {
-                        diameter: body_diameter,
-                    },
+                    shape,
                     gravity: game_meta.core.physics.gravity,
                     has_mass: true,
                     has_friction: true,
@@ -161,6 +174,7 @@ impl KickBombCommand {
                         fuse_time: Timer::new(fuse_time, TimerMode::Once),
                         kicking: false,
                         kicks: 0,
+                        owner: Unset,
                     },
                 );
 
@@ -259,7 +273,8 @@ fn update_idle_kick_bombs(
             ..
         } = *kick_bomb_meta;
 
-        if items_used.remove(entity).is_some() {
+        if let Some(item_used) = items_used.remove(entity) {
+            let owner = item_used.owner;
             audio_center.play_sound(fuse_sound, fuse_sound_volume);
             let animated_sprite = animated_sprites.get_mut(entity).unwrap();
             animated_sprite.frames = (lit_frames_start..lit_frames_end).collect();
@@ -275,6 +290,7 @@ fn update_idle_kick_bombs(
                             fuse_time: Timer::new(fuse_time, TimerMode::Once),
                             kicking: false,
                             kicks: 0,
+                            owner: Set(owner),
                         },
                     );
                 },
@@ -301,6 +317,7 @@ fn update_lit_kick_bombs(
     time: Res<Time>,
     spawners: Comp<DehydrateOutOfBounds>,
     invincibles: CompMut<Invincibility>,
+    element_handles: Comp<ElementHandle>,
 ) {
     for (entity, (kick_bomb, kick_bomb_handle, spawner)) in
         entities.iter_with((&mut lit_grenades, &kick_bomb_handles, &Optional(&spawners)))
@@ -314,6 +331,8 @@ fn update_lit_kick_bombs(
             kickable,
             damage_region_lifetime,
             damage_region_size,
+            explosion_impulse,
+            explosion_damage,
             explosion_lifetime,
             explosion_atlas,
             explosion_fps,
@@ -362,6 +381,7 @@ fn update_lit_kick_bombs(
                 {
                     if !std::mem::replace(&mut kick_bomb.kicking, true) {
                         kick_bomb.kicks += 1;
+                        kick_bomb.owner = Set(player_entity);
                     }
 
                     // Explode on the 3rd kick.
@@ -415,10 +435,21 @@ fn update_lit_kick_bombs(
             explosion_transform.translation.z = -10.0; // On top of almost everything
             explosion_transform.rotation = Quat::IDENTITY;
 
+            let mut source = DamageSource {
+                cause: DamageCause::Item,
+                killer: kick_bomb.owner,
+                ..default()
+            };
+            if let Some(element_handle) = element_handles.get(entity) {
+                source = source.with_element(element_handle.0);
+            }
             commands.add(
                 move |mut entities: ResMutInit<Entities>,
                       mut transforms: CompMut<Transform>,
                       mut damage_regions: CompMut<DamageRegion>,
+                      mut damage_region_sources: CompMut<DamageRegionSource>,
+                      mut radial_impulses: CompMut<RadialImpulse>,
+                      mut destroys_tiles: CompMut<DestroysTiles>,
                       mut lifetimes: CompMut<Lifetime>,
                       mut sprites: CompMut<AtlasSprite>,
                       mut animated_sprites: CompMut<AnimatedSprite>| {
@@ -432,9 +463,14 @@ fn update_lit_kick_bombs(
                         ent,
                         DamageRegion {
                             size: damage_region_size,
+                            damage: explosion_damage,
+                            ..default()
                         },
                     );
+                    damage_region_sources.insert(ent, DamageRegionSource(source));
                     lifetimes.insert(ent, Lifetime::new(damage_region_lifetime));
+                    radial_impulses.insert(ent, explosion_impulse);
+                    destroys_tiles.insert(ent, DestroysTiles);
 
                     // Spawn the explosion animation
                     let ent = entities.create();
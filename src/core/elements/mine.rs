@@ -9,6 +9,14 @@ pub struct MineMeta {
 
     pub damage_region_size: Vec2,
     pub damage_region_lifetime: f32,
+    /// The push that the explosion gives to the bodies around it.
+    pub explosion_impulse: RadialImpulse,
+    /// The damage that the explosion deals to the players caught in it.
+    pub explosion_damage: f32,
+    /// The damage that the mine deals to the players that set it off, on top of its explosion.
+    pub damage: f32,
+    /// The speed that the mine knocks the players that set it off away from it at.
+    pub knockback: f32,
     pub explosion_atlas: Handle<Atlas>,
     pub explosion_lifetime: f32,
     pub explosion_frames: u32,
@@ -50,6 +58,8 @@ pub struct IdleMine;
 pub struct ThrownMine {
     // The mine won't explode until this timer finishes.
     arm_delay: Timer,
+    /// The player that threw the mine.
+    owner: Entity,
 }
 
 fn hydrate(
@@ -160,6 +170,7 @@ fn update_idle_mines(
                                     Duration::from_secs_f32(arm_delay),
                                     TimerMode::Once,
                                 ),
+                                owner: player,
                             },
                         );
                     },
@@ -209,6 +220,10 @@ fn update_thrown_mines(
             armed_fps,
             damage_region_size,
             damage_region_lifetime,
+            explosion_impulse,
+            explosion_damage,
+            damage,
+            knockback,
             explosion_volume,
             arm_sound_volume,
             explosion_lifetime,
@@ -240,10 +255,19 @@ fn update_thrown_mines(
 
             trauma_events.send(6.0);
 
+            let source = DamageSource::item(element_handle.0).with_killer(thrown_mine.owner);
             for player in &colliding_with_players {
-                commands.add(PlayerCommand::kill(
+                let hit_from = mine_transform.translation.xy();
+                let direction = transforms
+                    .get(*player)
+                    .map(|transform| (transform.translation.xy() - hit_from).normalize_or_zero())
+                    .unwrap_or_default();
+                commands.add(PlayerCommand::damage(
                     *player,
-                    Some(mine_transform.translation.xy()),
+                    *damage,
+                    direction * *knockback,
+                    Some(hit_from),
+                    source,
                 ));
             }
 
@@ -254,14 +278,20 @@ fn update_thrown_mines(
             // Clone types for move into closure
             let damage_region_size = *damage_region_size;
             let damage_region_lifetime = *damage_region_lifetime;
+            let explosion_impulse = *explosion_impulse;
+            let explosion_damage = *explosion_damage;
             let explosion_lifetime = *explosion_lifetime;
             let explosion_atlas = *explosion_atlas;
             let explosion_fps = *explosion_fps;
             let explosion_frames = *explosion_frames;
+
             commands.add(
                 move |mut entities: ResMutInit<Entities>,
                       mut transforms: CompMut<Transform>,
                       mut damage_regions: CompMut<DamageRegion>,
+                      mut damage_region_sources: CompMut<DamageRegionSource>,
+                      mut radial_impulses: CompMut<RadialImpulse>,
+                      mut destroys_tiles: CompMut<DestroysTiles>,
                       mut lifetimes: CompMut<Lifetime>,
                       mut sprites: CompMut<AtlasSprite>,
                       mut animated_sprites: CompMut<AnimatedSprite>| {
@@ -279,9 +309,14 @@ fn update_thrown_mines(
                         damage_ent,
                         DamageRegion {
                             size: damage_region_size,
+                            damage: explosion_damage,
+                            ..default()
                         },
                     );
+                    damage_region_sources.insert(damage_ent, DamageRegionSource(source));
                     lifetimes.insert(damage_ent, Lifetime::new(damage_region_lifetime));
+                    radial_impulses.insert(damage_ent, explosion_impulse);
+                    destroys_tiles.insert(damage_ent, DestroysTiles);
 
                     // Spawn the explosion animation
                     let ent = entities.create();
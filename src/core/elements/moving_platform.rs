@@ -0,0 +1,352 @@
+use crate::prelude::*;
+
+/// A platform that moves along a path of waypoints, carrying the bodies standing on it.
+#[derive(HasSchema, Default, Debug, Clone)]
+#[type_data(metadata_asset("moving_platform"))]
+#[repr(C)]
+pub struct MovingPlatformMeta {
+    /// The tileset that the platform is drawn with.
+    pub atlas: Handle<Atlas>,
+    /// The tile indices that the platform is made of, from left to right. The platform is one tile
+    /// high.
+    pub tiles: SVec<u32>,
+    /// The points that the platform moves through, relative to where it's placed on the map.
+    ///
+    /// The path starts where the platform is placed, so that point doesn't need to be included.
+    pub waypoints: SVec<Vec2>,
+    /// The speed that the platform moves at, in pixels per second.
+    pub speed: f32,
+    /// Whether the platform goes back through the waypoints in reverse after reaching the last
+    /// one, instead of going straight back to where it started.
+    pub ping_pong: bool,
+    /// Whether bodies can jump up through the platform and drop down through it, instead of it
+    /// being solid from every side.
+    pub jump_through: bool,
+}
+
+pub fn game_plugin(game: &mut Game) {
+    MovingPlatformMeta::register_schema();
+    game.init_shared_resource::<AssetServer>();
+}
+
+pub fn session_plugin(session: &mut Session) {
+    session
+        .stages
+        .add_system_to_stage(CoreStage::PreUpdate, hydrate)
+        .add_system_to_stage(CoreStage::PostUpdate, update);
+}
+
+/// Component for a moving platform's progress along its path.
+///
+/// The platform is a separate entity from its map element, which stays where the platform was
+/// placed, so that exporting the map or undoing edits to it don't depend on where the platform has
+/// moved to.
+#[derive(Clone, Debug, HasSchema, Default)]
+pub struct MovingPlatform {
+    /// The map element entity that the platform was spawned for.
+    pub element: Entity,
+    /// Where the platform was placed on the map, which is the start of its path.
+    pub start: Vec2,
+    /// The point of the path that the platform is moving towards, where `0` is the start and the
+    /// waypoints come after it.
+    pub target: u32,
+    /// Whether the platform is going back through its waypoints, if it ping-pongs.
+    pub reversing: bool,
+}
+
+impl MovingPlatform {
+    /// Get the position of a point of the path.
+    fn point(&self, waypoints: &SVec<Vec2>, idx: u32) -> Vec2 {
+        match idx.checked_sub(1) {
+            Some(waypoint) => self.start + waypoints[waypoint as usize],
+            None => self.start,
+        }
+    }
+
+    /// Start moving towards the next point of the path.
+    fn next_target(&mut self, waypoint_count: u32, ping_pong: bool) {
+        if ping_pong {
+            if self.reversing && self.target == 0 {
+                self.reversing = false;
+            } else if !self.reversing && self.target == waypoint_count {
+                self.reversing = true;
+            }
+            self.target = if self.reversing {
+                self.target - 1
+            } else {
+                self.target + 1
+            };
+        } else {
+            self.target = (self.target + 1) % (waypoint_count + 1);
+        }
+    }
+}
+
+fn hydrate(
+    mut entities: ResMutInit<Entities>,
+    mut hydrated: CompMut<MapElementHydrated>,
+    element_handles: Comp<ElementHandle>,
+    assets: Res<AssetServer>,
+    mut moving_platforms: CompMut<MovingPlatform>,
+    mut solids: CompMut<Solid>,
+    mut transforms: CompMut<Transform>,
+    mut atlas_sprites: CompMut<AtlasSprite>,
+    mut attachments: CompMut<Attachment>,
+    mut spawner_manager: SpawnerManager,
+) {
+    let mut not_hydrated_bitset = hydrated.bitset().clone();
+    not_hydrated_bitset.bit_not();
+    not_hydrated_bitset.bit_and(element_handles.bitset());
+
+    let new_entities = entities
+        .iter_with_bitset(&not_hydrated_bitset)
+        .collect::<Vec<_>>();
+    for entity in new_entities {
+        let element_handle = element_handles.get(entity).unwrap();
+        let element_meta = assets.get(element_handle.0);
+
+        let Ok(MovingPlatformMeta {
+            atlas,
+            tiles,
+            jump_through,
+            ..
+        }) = assets.get(element_meta.data).try_cast_ref()
+        else {
+            continue;
+        };
+
+        let transform = *transforms.get(entity).unwrap();
+        let tile_size = assets.get(*atlas).tile_size;
+        let size = vec2(tile_size.x * tiles.len() as f32, tile_size.y);
+
+        hydrated.insert(entity, MapElementHydrated);
+        let platform_ent = entities.create();
+        transforms.insert(platform_ent, transform);
+        moving_platforms.insert(
+            platform_ent,
+            MovingPlatform {
+                element: entity,
+                start: transform.translation.xy(),
+                ..default()
+            },
+        );
+        solids.insert(
+            platform_ent,
+            Solid {
+                pos: transform.translation.xy(),
+                size,
+                jump_through: *jump_through,
+                ..default()
+            },
+        );
+
+        // Draw the platform with a sprite for each of its tiles, which follow the platform.
+        let mut spawned = vec![platform_ent];
+        for (i, idx) in tiles.iter().enumerate() {
+            let offset = vec2((i as f32 + 0.5) * tile_size.x - size.x / 2.0, 0.0).extend(0.0);
+            let tile_ent = entities.create();
+            transforms.insert(
+                tile_ent,
+                Transform::from_translation(transform.translation + offset),
+            );
+            atlas_sprites.insert(
+                tile_ent,
+                AtlasSprite {
+                    atlas: *atlas,
+                    index: *idx,
+                    ..default()
+                },
+            );
+            attachments.insert(
+                tile_ent,
+                Attachment {
+                    entity: platform_ent,
+                    offset,
+                    ..default()
+                },
+            );
+            spawned.push(tile_ent);
+        }
+        spawner_manager.create_spawner(entity, spawned);
+    }
+}
+
+fn update(
+    mut commands: Commands,
+    entities: Res<Entities>,
+    element_handles: Comp<ElementHandle>,
+    assets: Res<AssetServer>,
+    time: Res<Time>,
+    mut moving_platforms: CompMut<MovingPlatform>,
+    mut collision_world: CollisionWorld,
+    mut transforms: CompMut<Transform>,
+    bodies: Comp<KinematicBody>,
+    dynamic_bodies: Comp<DynamicBody>,
+    player_indexes: Comp<PlayerIdx>,
+) {
+    for (entity, platform) in entities.iter_with(&mut moving_platforms) {
+        let (Some(element_handle), Some(element_transform)) = (
+            element_handles.get(platform.element),
+            transforms.get(platform.element).copied(),
+        ) else {
+            continue;
+        };
+        let element_meta = assets.get(element_handle.0);
+
+        let asset = assets.get(element_meta.data);
+        let Ok(MovingPlatformMeta {
+            waypoints,
+            speed,
+            ping_pong,
+            ..
+        }) = asset.try_cast_ref()
+        else {
+            unreachable!();
+        };
+        let Some(solid) = collision_world.solids.get(entity).copied() else {
+            continue;
+        };
+
+        // Move the platform along with its path if its element was moved in the editor.
+        let placed = element_transform.translation.xy();
+        let mut from = solid.pos;
+        if placed != platform.start {
+            from += placed - platform.start;
+            platform.start = placed;
+            collision_world.move_solid(entity, from);
+        }
+
+        // Move along the path, going on to the next point whenever one is reached. The number of
+        // points passed in one frame is limited, in case the path has no length.
+        let mut pos = from;
+        let mut distance = speed * time.delta().as_secs_f32();
+        if !waypoints.is_empty() {
+            for _ in 0..=waypoints.len() * 2 {
+                let to_target = platform.point(waypoints, platform.target) - pos;
+                let length = to_target.length();
+                if length > distance {
+                    pos += to_target / length * distance;
+                    break;
+                }
+                pos += to_target;
+                distance -= length;
+                platform.next_target(waypoints.len() as u32, *ping_pong);
+            }
+        }
+        let delta = pos - from;
+
+        // Find the bodies that are standing on top of the platform.
+        let half_size = solid.size / 2.0;
+        let riders = entities
+            .iter_with(&bodies)
+            .filter(|(rider, body)| {
+                let is_dynamic = dynamic_bodies
+                    .get(*rider)
+                    .map(|x| x.is_dynamic)
+                    .unwrap_or(false);
+                if body.is_deactivated || !body.is_on_ground || is_dynamic {
+                    return false;
+                }
+                let rect = body.bounding_box(*transforms.get(*rider).unwrap());
+                (rect.min.y - (from.y + half_size.y)).abs() <= 1.0
+                    && rect.max.x > from.x - half_size.x
+                    && rect.min.x < from.x + half_size.x
+            })
+            .map(|(rider, _)| rider)
+            .filter(|rider| collision_world.actors.contains(*rider))
+            .collect::<Vec<_>>();
+
+        // Move one axis at a time, so that the bodies in the way are pushed out of the side of the
+        // platform that they were hit by.
+        let mut crushed = Vec::new();
+        for axis_delta in [vec2(delta.x, 0.0), vec2(0.0, delta.y)] {
+            if axis_delta == Vec2::ZERO {
+                continue;
+            }
+            let platform_pos = collision_world.solids.get(entity).unwrap().pos;
+            let new_pos = platform_pos + axis_delta;
+
+            // Push the bodies that the platform moves into, unless bodies can pass through it.
+            // They are moved before the platform, so that they don't collide with it, and bodies
+            // that can't be pushed far enough because something else is in the way are crushed.
+            if !solid.jump_through {
+                let pushed = entities
+                    .iter_with(&bodies)
+                    .filter(|(body_ent, body)| {
+                        let is_dynamic = dynamic_bodies
+                            .get(*body_ent)
+                            .map(|x| x.is_dynamic)
+                            .unwrap_or(false);
+                        !body.is_deactivated
+                            && !is_dynamic
+                            && !riders.contains(body_ent)
+                            && collision_world.actors.contains(*body_ent)
+                    })
+                    .filter_map(|(body_ent, body)| {
+                        let rect = body.bounding_box(*transforms.get(body_ent).unwrap());
+                        let overlaps = rect.max.x > new_pos.x - half_size.x
+                            && rect.min.x < new_pos.x + half_size.x
+                            && rect.max.y > new_pos.y - half_size.y
+                            && rect.min.y < new_pos.y + half_size.y;
+                        let push = if axis_delta.x > 0.0 {
+                            vec2(new_pos.x + half_size.x - rect.min.x, 0.0)
+                        } else if axis_delta.x < 0.0 {
+                            vec2(new_pos.x - half_size.x - rect.max.x, 0.0)
+                        } else if axis_delta.y > 0.0 {
+                            vec2(0.0, new_pos.y + half_size.y - rect.min.y)
+                        } else {
+                            vec2(0.0, new_pos.y - half_size.y - rect.max.y)
+                        };
+                        overlaps.then_some((body_ent, push))
+                    })
+                    .collect::<Vec<_>>();
+                for (body_ent, push) in pushed {
+                    let blocked = if push.x != 0.0 {
+                        collision_world.move_horizontal(&mut transforms, body_ent, push.x)
+                    } else {
+                        collision_world.move_vertical(&mut transforms, body_ent, push.y)
+                    };
+                    if blocked && !crushed.contains(&body_ent) {
+                        crushed.push(body_ent);
+                    }
+                }
+            }
+
+            // Carry the riders. When the platform goes down they are moved after it, so that they
+            // land on it, and otherwise they are moved before it, so that they don't run into it.
+            if axis_delta.y >= 0.0 {
+                for rider in &riders {
+                    collision_world.move_horizontal(&mut transforms, *rider, axis_delta.x);
+                    let blocked =
+                        collision_world.move_vertical(&mut transforms, *rider, axis_delta.y);
+                    if blocked && !crushed.contains(rider) {
+                        crushed.push(*rider);
+                    }
+                }
+                collision_world.move_solid(entity, new_pos);
+            } else {
+                collision_world.move_solid(entity, new_pos);
+                for rider in &riders {
+                    collision_world.move_vertical(&mut transforms, *rider, axis_delta.y);
+                }
+            }
+        }
+
+        // Kill the players that are crushed between the platform and something else.
+        for player in crushed {
+            if player_indexes.contains(player) {
+                let hit_from = transforms.get(entity).unwrap().translation.xy();
+                commands.add(PlayerCommand::damage(
+                    player,
+                    f32::INFINITY,
+                    Vec2::ZERO,
+                    Some(hit_from),
+                    DamageSource::hazard(element_handle.0),
+                ));
+            }
+        }
+
+        let z = element_transform.translation.z;
+        transforms.get_mut(entity).unwrap().translation = pos.extend(z);
+    }
+}
@@ -156,6 +156,7 @@ fn update(
     mut bodies: CompMut<KinematicBody>,
 ) {
     for (entity, (musket, element_handle)) in entities.iter_with((&mut muskets, &element_handles)) {
+        let element = element_handle.0;
         let element_meta = assets.get(element_handle.0);
 
         let asset = assets.get(element_meta.data);
@@ -262,6 +263,7 @@ fn update(
                                 ent,
                                 Bullet {
                                     owner: player,
+                                    weapon: Set(element),
                                     direction: if player_flip_x {
                                         vec2(-1.0, 0.0)
                                     } else {
@@ -158,6 +158,7 @@ fn update(
     for (entity, (periscope, element_handle)) in
         entities.iter_with((&mut periscopes, &element_handles))
     {
+        let element = element_handle.0;
         let element_meta = assets.get(element_handle.0);
 
         let asset = assets.get(element_meta.data);
@@ -264,6 +265,7 @@ fn update(
                                 ent,
                                 Bullet {
                                     owner: player,
+                                    weapon: Set(element),
                                     direction: if player_flip_x {
                                         vec2(-1.0, 0.0)
                                     } else {
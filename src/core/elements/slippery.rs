@@ -6,8 +6,6 @@ use crate::prelude::*;
 pub struct SlipperyMeta {
     pub atlas: Handle<Atlas>,
     pub body_size: Vec2,
-    pub player_slide: f32,
-    pub body_friction: f32,
 }
 
 pub fn game_plugin(game: &mut Game) {
@@ -18,15 +16,13 @@ pub fn game_plugin(game: &mut Game) {
 pub fn session_plugin(session: &mut Session) {
     session
         .stages
-        .add_system_to_stage(CoreStage::PreUpdate, hydrate)
-        .add_system_to_stage(CoreStage::PreUpdate, update);
+        .add_system_to_stage(CoreStage::PreUpdate, hydrate);
 }
 
+/// A patch that gives the bodies touching it the [`material`][ElementMeta::material] of its
+/// element, such as ice or sticky kelp.
 #[derive(Clone, Debug, HasSchema, Default)]
-pub struct Slippery {
-    pub player_slide: f32,
-    pub body_friction: f32,
-}
+pub struct Slippery;
 
 fn hydrate(
     entities: Res<Entities>,
@@ -45,12 +41,7 @@ fn hydrate(
         let element_handle = element_handles.get(entity).unwrap();
         let element_meta = assets.get(element_handle.0);
 
-        if let Ok(SlipperyMeta {
-            atlas,
-            body_size,
-            player_slide,
-            body_friction,
-        }) = assets.get(element_meta.data).try_cast_ref()
+        if let Ok(SlipperyMeta { atlas, body_size }) = assets.get(element_meta.data).try_cast_ref()
         {
             hydrated.insert(entity, MapElementHydrated);
             atlas_sprites.insert(entity, AtlasSprite::new(*atlas));
@@ -62,31 +53,7 @@ fn hydrate(
                     ..default()
                 },
             );
-            slippery.insert(
-                entity,
-                Slippery {
-                    player_slide: *player_slide,
-                    body_friction: *body_friction,
-                },
-            );
-        }
-    }
-}
-
-pub fn update(
-    entities: Res<Entities>,
-    slippery: CompMut<Slippery>,
-    collision_world: CollisionWorld,
-    mut bodies: CompMut<KinematicBody>,
-) {
-    for (slippery_ent, slippery) in entities.iter_with(&slippery) {
-        for (p_ent, body) in entities.iter_with(&mut bodies) {
-            if collision_world
-                .actor_collisions(p_ent)
-                .contains(&slippery_ent)
-            {
-                body.frame_friction_override = Some(slippery.body_friction);
-            }
+            slippery.insert(entity, Slippery);
         }
     }
 }
@@ -9,6 +9,10 @@ pub struct SpikeMeta {
     pub start_frame: u32,
     pub end_frame: u32,
     pub fps: f32,
+    /// The damage that the spikes deal to the players touching them.
+    pub damage: f32,
+    /// The speed that the spikes knock the players touching them away at.
+    pub knockback: f32,
 }
 
 pub fn game_plugin(game: &mut Game) {
@@ -85,15 +89,38 @@ fn update(
     invincibles: CompMut<Invincibility>,
     mut commands: Commands,
     transforms: Comp<Transform>,
+    element_handles: Comp<ElementHandle>,
+    assets: Res<AssetServer>,
 ) {
-    for (entity, (_, pos)) in entities.iter_with((&mut spikes, &transforms)) {
+    for (entity, (_, pos, element_handle)) in
+        entities.iter_with((&mut spikes, &transforms, &element_handles))
+    {
+        let element_meta = assets.get(element_handle.0);
+        let Ok(SpikeMeta {
+            damage, knockback, ..
+        }) = assets.get(element_meta.data).try_cast_ref()
+        else {
+            continue;
+        };
+
         collision_world
             .actor_collisions_filtered(entity, |e| {
                 player_indexes.contains(e) && invincibles.get(e).is_none()
             })
             .into_iter()
             .for_each(|player| {
-                commands.add(PlayerCommand::kill(player, Some(pos.translation.xy())));
+                let hit_from = pos.translation.xy();
+                let direction = transforms
+                    .get(player)
+                    .map(|transform| (transform.translation.xy() - hit_from).normalize_or_zero())
+                    .unwrap_or_default();
+                commands.add(PlayerCommand::damage(
+                    player,
+                    *damage,
+                    direction * *knockback,
+                    Some(hit_from),
+                    DamageSource::hazard(element_handle.0),
+                ));
             });
     }
 }
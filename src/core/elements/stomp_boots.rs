@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-#[derive(HasSchema, Default, Debug, Clone)]
+#[derive(HasSchema, Debug, Clone)]
 #[type_data(metadata_asset("stomp_boots"))]
 #[repr(C)]
 pub struct StompBootsMeta {
@@ -9,6 +9,23 @@ pub struct StompBootsMeta {
 
     pub body_size: Vec2,
     pub grab_offset: Vec2,
+    /// The damage that the wearer deals to the players they land on, lethal by default.
+    pub damage: f32,
+    /// The speed that the wearer knocks the players they land on down at.
+    pub knockback: f32,
+}
+
+impl Default for StompBootsMeta {
+    fn default() -> Self {
+        Self {
+            map_icon: default(),
+            player_decoration: default(),
+            body_size: default(),
+            grab_offset: default(),
+            damage: f32::INFINITY,
+            knockback: 200.0,
+        }
+    }
 }
 
 pub fn game_plugin(game: &mut Game) {
@@ -25,7 +42,10 @@ pub fn session_plugin(session: &mut Session) {
 }
 
 /// Marker component added to things ( presumably players, but not necessarily! ) that are wearing
-/// stomp boots
+/// stomp boots.
+///
+/// The boots give the wearer a point of [`PlayerHealth::armor`], and break once it has absorbed a
+/// hit.
 #[derive(Debug, Clone, Copy, Default, HasSchema)]
 pub struct WearingStompBoots {
     stomp_boots: Entity,
@@ -148,9 +168,14 @@ fn update(
                           mut transforms: CompMut<Transform>,
                           mut worn_stomp_boots: CompMut<WornStompBoots>,
                           mut respawn_points: CompMut<DehydrateOutOfBounds>,
-                          mut wearing_stomp_boots: CompMut<WearingStompBoots>| {
+                          mut wearing_stomp_boots: CompMut<WearingStompBoots>,
+                          mut healths: CompMut<PlayerHealth>| {
                         entities.kill(entity);
 
+                        if let Some(health) = healths.get_mut(player) {
+                            health.armor += 1;
+                        }
+
                         let attachment_ent = entities.create();
                         let attachment = Attachment {
                             entity: player,
@@ -188,9 +213,12 @@ fn update_wearer(
     collision_world: CollisionWorld,
     kinematic_bodies: Comp<KinematicBody>,
     killed_players: Comp<PlayerKilled>,
+    players_damaged: Comp<PlayerDamaged>,
     mut hydrated: CompMut<MapElementHydrated>,
     spawners: Comp<DehydrateOutOfBounds>,
     transforms: Comp<Transform>,
+    element_handles: Comp<ElementHandle>,
+    assets: Res<AssetServer>,
 ) {
     for (entity, WearingStompBoots { stomp_boots }) in entities.iter_with(&wearing_stomp_boots) {
         if killed_players.get(entity).is_some() {
@@ -205,6 +233,25 @@ fn update_wearer(
             continue;
         }
 
+        if players_damaged
+            .get(entity)
+            .is_some_and(|damaged| damaged.absorbed > 0)
+        {
+            // The boots break after absorbing a hit, so respawn them
+            if let Some(spawner) = spawners.get(*stomp_boots) {
+                hydrated.remove(**spawner);
+            }
+            let stomp_boots = *stomp_boots;
+            commands.add(
+                move |mut entities: ResMutInit<Entities>,
+                      mut wearing_stomp_boots: CompMut<WearingStompBoots>| {
+                    wearing_stomp_boots.remove(entity);
+                    entities.kill(stomp_boots);
+                },
+            );
+            continue;
+        }
+
         let kinematic_body = kinematic_bodies.get(entity).unwrap();
         if kinematic_body.velocity.y > 0.
             || kinematic_body.is_on_ground
@@ -212,6 +259,26 @@ fn update_wearer(
         {
             continue;
         }
+        // The boots keep the element handle of their spawner, so they still stomp after the
+        // spawner is gone, falling back to the default meta if the element can't be read.
+        let element_handle = element_handles.get(*stomp_boots).or_else(|| {
+            spawners
+                .get(*stomp_boots)
+                .and_then(|spawner| element_handles.get(**spawner))
+        });
+        let default_meta = StompBootsMeta::default();
+        let StompBootsMeta {
+            damage, knockback, ..
+        } = element_handle
+            .and_then(|handle| {
+                let element_meta = assets.get(handle.0);
+                assets.get(element_meta.data).try_cast_ref().ok()
+            })
+            .unwrap_or(&default_meta);
+        let source = match element_handle {
+            Some(handle) => DamageSource::stomp(entity).with_element(handle.0),
+            None => DamageSource::stomp(entity),
+        };
         collision_world
             .actor_collisions_filtered(entity, |e| player_indexes.contains(e))
             .into_iter()
@@ -227,9 +294,12 @@ fn update_wearer(
                         .center()
                         .y
                 {
-                    commands.add(PlayerCommand::kill(
+                    commands.add(PlayerCommand::damage(
                         player,
+                        *damage,
+                        Vec2::NEG_Y * *knockback,
                         Some(player_transform.translation.xy()),
+                        source,
                     ))
                 }
             });
@@ -9,9 +9,22 @@ pub struct SwordMeta {
     pub sound: Handle<AudioSource>,
     pub sound_volume: f64,
     pub body_size: Vec2,
+    /// The collider of the sword pointing to the right, which is mirrored when the sword faces
+    /// left. See [`ColliderShape::from_body_polygon`].
+    pub body_polygon: SVec<Vec2>,
     pub fin_anim: Ustr,
     pub grab_offset: Vec2,
     pub killing_speed: f32,
+    /// The damage that a swing deals to the players it hits.
+    pub damage: f32,
+    /// The speed that players hit by a swing are knocked back at.
+    pub knockback: f32,
+    /// The damage that a thrown sword moving faster than the
+    /// [`killing_speed`][Self::killing_speed] deals to the players it hits.
+    pub thrown_damage: f32,
+    /// The speed that players hit by a thrown sword are knocked back at, in the direction it's
+    /// moving.
+    pub thrown_knockback: f32,
     pub angular_velocity: f32,
     pub can_rotate: bool,
     pub bounciness: f32,
@@ -58,6 +71,7 @@ fn hydrate(
     mut swords: CompMut<Sword>,
     mut atlas_sprites: CompMut<AtlasSprite>,
     mut bodies: CompMut<KinematicBody>,
+    mut mirror_colliders: CompMut<MirrorCollider>,
     mut transforms: CompMut<Transform>,
     mut items: CompMut<Item>,
     mut item_throws: CompMut<ItemThrow>,
@@ -83,6 +97,7 @@ fn hydrate(
             fin_anim,
             grab_offset,
             body_size,
+            body_polygon,
             can_rotate,
             bounciness,
             throw_velocity,
@@ -114,10 +129,14 @@ fn hydrate(
             transforms.insert(entity, transform);
             element_handles.insert(entity, element_handle);
             hydrated.insert(entity, MapElementHydrated);
+            mirror_colliders.insert(entity, MirrorCollider::default());
             bodies.insert(
                 entity,
                 KinematicBody {
-                    shape: ColliderShape::Rectangle { size: *body_size },
+                    shape: ColliderShape::from_body_polygon(
+                        body_polygon,
+                        ColliderShape::Rectangle { size: *body_size },
+                    ),
                     has_mass: true,
                     has_friction: true,
                     can_rotate: *can_rotate,
@@ -154,6 +173,24 @@ fn update(
     for (entity, (sword, element_handle)) in entities.iter_with((&mut swords, &element_handles)) {
         let element_meta = assets.get(element_handle.0);
 
+        let asset = assets.get(element_meta.data);
+        let Ok(SwordMeta {
+            cooldown_frames,
+            sound,
+            sound_volume,
+            killing_speed,
+            damage,
+            knockback,
+            thrown_damage,
+            thrown_knockback,
+            ..
+        }) = asset.try_cast_ref()
+        else {
+            unreachable!();
+        };
+        let (damage, knockback) = (*damage, *knockback);
+        let element = element_handle.0;
+
         // Helper to spawn a damage region for the sword attack
         let mut spawn_damage_region = |pos: Vec3, size: Vec2, owner: Entity| {
             commands.add(
@@ -162,6 +199,7 @@ fn update(
                       mut emote_regions: CompMut<EmoteRegion>,
                       mut damage_regions: CompMut<DamageRegion>,
                       mut damage_region_owners: CompMut<DamageRegionOwner>,
+                      mut damage_region_sources: CompMut<DamageRegionSource>,
                       mut lifetimes: CompMut<Lifetime>| {
                     let entity = entities.create();
 
@@ -178,25 +216,24 @@ fn update(
                     );
 
                     lifetimes.insert(entity, Lifetime::new(2.0 / 60.0));
-                    damage_regions.insert(entity, DamageRegion { size });
+                    damage_regions.insert(
+                        entity,
+                        DamageRegion {
+                            size,
+                            damage,
+                            knockback,
+                        },
+                    );
                     transforms.insert(entity, Transform::from_translation(pos));
                     damage_region_owners.insert(entity, DamageRegionOwner(owner));
+                    damage_region_sources.insert(
+                        entity,
+                        DamageRegionSource(DamageSource::item(element).with_killer(owner)),
+                    );
                 },
             );
         };
 
-        let asset = assets.get(element_meta.data);
-        let Ok(SwordMeta {
-            cooldown_frames,
-            sound,
-            sound_volume,
-            killing_speed,
-            ..
-        }) = asset.try_cast_ref()
-        else {
-            unreachable!();
-        };
-
         // If the item is being held
         if let Some(Inv { player, .. }) = player_inventories.find_item(entity) {
             let sprite = sprites.get_mut(entity).unwrap();
@@ -309,9 +346,12 @@ fn update(
                     })
                     .into_iter()
                     .for_each(|player| {
-                        commands.add(PlayerCommand::kill(
+                        commands.add(PlayerCommand::damage(
                             player,
+                            *thrown_damage,
+                            body.velocity.normalize_or_zero() * *thrown_knockback,
                             Some(sword_transform.translation.xy()),
+                            DamageSource::item(element),
                         ))
                     });
             }
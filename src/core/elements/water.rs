@@ -0,0 +1,205 @@
+use crate::prelude::*;
+
+/// A rectangular body of water that bodies can float and players can swim in.
+#[derive(HasSchema, Default, Debug, Clone)]
+#[type_data(metadata_asset("water"))]
+#[repr(C)]
+pub struct WaterMeta {
+    /// The tileset that the water is drawn with.
+    pub atlas: Handle<Atlas>,
+    /// The tile index used for the top row of the water.
+    pub surface_tile: u32,
+    /// The tile index used for the rest of the water.
+    pub tile: u32,
+    /// The size of the water in tiles, when it isn't given a size where it's placed on the map.
+    ///
+    /// Each placement of the water can be resized in the editor, see [`ElementSize`].
+    pub default_size: UVec2,
+    /// See [`WaterVolume::gravity_scale`].
+    pub gravity_scale: f32,
+    /// See [`WaterVolume::terminal_velocity_scale`].
+    pub terminal_velocity_scale: f32,
+    /// See [`WaterVolume::buoyancy`].
+    pub buoyancy: f32,
+    /// See [`WaterVolume::drag`].
+    pub drag: f32,
+}
+
+pub fn game_plugin(game: &mut Game) {
+    WaterMeta::register_schema();
+    game.init_shared_resource::<AssetServer>();
+}
+
+pub fn session_plugin(session: &mut Session) {
+    session
+        .stages
+        .add_system_to_stage(CoreStage::PreUpdate, hydrate)
+        .add_system_to_stage(CoreStage::PreUpdate, resize);
+}
+
+fn hydrate(
+    mut entities: ResMutInit<Entities>,
+    mut hydrated: CompMut<MapElementHydrated>,
+    element_handles: Comp<ElementHandle>,
+    assets: Res<AssetServer>,
+    mut element_sizes: CompMut<ElementSize>,
+    mut water_volumes: CompMut<WaterVolume>,
+    mut transforms: CompMut<Transform>,
+    mut atlas_sprites: CompMut<AtlasSprite>,
+    mut attachments: CompMut<Attachment>,
+    mut spawner_manager: SpawnerManager,
+) {
+    let mut not_hydrated_bitset = hydrated.bitset().clone();
+    not_hydrated_bitset.bit_not();
+    not_hydrated_bitset.bit_and(element_handles.bitset());
+
+    let new_entities = entities
+        .iter_with_bitset(&not_hydrated_bitset)
+        .collect::<Vec<_>>();
+    for entity in new_entities {
+        let element_handle = element_handles.get(entity).unwrap();
+        let element_meta = assets.get(element_handle.0);
+
+        let Ok(water_meta) = assets.get(element_meta.data).try_cast_ref::<WaterMeta>() else {
+            continue;
+        };
+        let WaterMeta {
+            atlas,
+            default_size,
+            gravity_scale,
+            terminal_velocity_scale,
+            buoyancy,
+            drag,
+            ..
+        } = water_meta;
+
+        // Use the default size unless the water was given a size where it was placed.
+        let size = match element_sizes.get(entity) {
+            Some(size) => **size,
+            None => {
+                element_sizes.insert(entity, ElementSize(*default_size));
+                *default_size
+            }
+        };
+        let tile_size = assets.get(*atlas).tile_size;
+
+        hydrated.insert(entity, MapElementHydrated);
+        water_volumes.insert(
+            entity,
+            WaterVolume {
+                size: size.as_vec2() * tile_size,
+                gravity_scale: *gravity_scale,
+                terminal_velocity_scale: *terminal_velocity_scale,
+                buoyancy: *buoyancy,
+                drag: *drag,
+            },
+        );
+
+        let tile_ents = spawn_tiles(
+            entity,
+            water_meta,
+            size,
+            tile_size,
+            &mut entities,
+            &mut transforms,
+            &mut atlas_sprites,
+            &mut attachments,
+        );
+        spawner_manager.create_spawner(entity, tile_ents);
+    }
+}
+
+/// Update the water volume and tiles of water that was resized in the editor.
+fn resize(
+    mut entities: ResMutInit<Entities>,
+    element_handles: Comp<ElementHandle>,
+    assets: Res<AssetServer>,
+    element_sizes: Comp<ElementSize>,
+    mut water_volumes: CompMut<WaterVolume>,
+    mut transforms: CompMut<Transform>,
+    mut atlas_sprites: CompMut<AtlasSprite>,
+    mut attachments: CompMut<Attachment>,
+    mut spawner_manager: SpawnerManager,
+) {
+    let resized = entities
+        .iter_with((&element_handles, &element_sizes, &water_volumes))
+        .filter_map(|(entity, (element_handle, size, volume))| {
+            let element_meta = assets.get(element_handle.0);
+            let water_meta = assets.get(element_meta.data);
+            let water_meta = water_meta.try_cast_ref::<WaterMeta>().ok()?;
+            let tile_size = assets.get(water_meta.atlas).tile_size;
+            (size.as_vec2() * tile_size != volume.size).then_some((entity, **size, tile_size))
+        })
+        .collect::<Vec<_>>();
+
+    for (entity, size, tile_size) in resized {
+        let element_handle = element_handles.get(entity).unwrap();
+        let element_meta = assets.get(element_handle.0);
+        let water_meta = assets.get(element_meta.data);
+        let water_meta = water_meta.cast_ref::<WaterMeta>();
+
+        water_volumes.get_mut(entity).unwrap().size = size.as_vec2() * tile_size;
+        let tile_ents = spawn_tiles(
+            entity,
+            water_meta,
+            size,
+            tile_size,
+            &mut entities,
+            &mut transforms,
+            &mut atlas_sprites,
+            &mut attachments,
+        );
+        for tile_ent in spawner_manager.replace_spawned_elements(entity, tile_ents) {
+            entities.kill(tile_ent);
+        }
+    }
+}
+
+/// Spawn a sprite for each of the tiles of the water, which follow the water if it's moved in the
+/// editor.
+#[allow(clippy::too_many_arguments)]
+fn spawn_tiles(
+    entity: Entity,
+    water_meta: &WaterMeta,
+    size: UVec2,
+    tile_size: Vec2,
+    entities: &mut Entities,
+    transforms: &mut CompMut<Transform>,
+    atlas_sprites: &mut CompMut<AtlasSprite>,
+    attachments: &mut CompMut<Attachment>,
+) -> Vec<Entity> {
+    let translation = transforms.get(entity).unwrap().translation;
+    let water_size = size.as_vec2() * tile_size;
+    let mut tile_ents = Vec::new();
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let offset =
+                ((uvec2(x, y).as_vec2() + 0.5) * tile_size - water_size / 2.0) * vec2(1.0, -1.0);
+            let offset = offset.extend(0.0);
+            let tile_ent = entities.create();
+            transforms.insert(tile_ent, Transform::from_translation(translation + offset));
+            atlas_sprites.insert(
+                tile_ent,
+                AtlasSprite {
+                    atlas: water_meta.atlas,
+                    index: if y == 0 {
+                        water_meta.surface_tile
+                    } else {
+                        water_meta.tile
+                    },
+                    ..default()
+                },
+            );
+            attachments.insert(
+                tile_ent,
+                Attachment {
+                    entity,
+                    offset,
+                    ..default()
+                },
+            );
+            tile_ents.push(tile_ent);
+        }
+    }
+    tile_ents
+}
@@ -106,6 +106,13 @@ pub enum EditorInput {
         /// The entity to delete.
         entity: Entity,
     },
+    /// Resize an element that can be resized, such as water.
+    ResizeElement {
+        /// The entity to resize.
+        entity: Entity,
+        /// The new size of the element, in tiles.
+        size: UVec2,
+    },
     /// Create a new layer
     CreateLayer {
         /// The name of the layer.
@@ -144,6 +151,19 @@ pub enum EditorInput {
         tilemap_tile_idx: Option<u32>,
         /// The tile collision kind
         collision: TileCollisionKind,
+        /// Whether the tile can be destroyed by explosions.
+        destructible: bool,
+    },
+    /// Paint or erase a terrain tile, picking the tiles around it with auto-tiling.
+    PaintTerrain {
+        /// The layer index of the layer to paint on.
+        layer: u8,
+        /// The position of the tile to paint.
+        pos: UVec2,
+        /// The terrain to paint with.
+        terrain: Handle<AutotileMeta>,
+        /// Whether to erase the terrain tile instead of painting it.
+        erase: bool,
     },
     RenameMap {
         name: String,
@@ -153,4 +173,8 @@ pub enum EditorInput {
         element_layers: Vec<ElementLayer>,
         tile_size: Vec2,
     },
+    /// Undo the last edit made to the map.
+    Undo,
+    /// Redo the last edit that was undone.
+    Redo,
 }
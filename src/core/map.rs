@@ -148,14 +148,18 @@ fn spawn_map(
     mut entities: ResMutInit<Entities>,
     mut clear_color: ResMutInit<ClearColor>,
     assets: Res<AssetServer>,
+    meta: Root<GameMeta>,
     map: Res<LoadedMap>,
     mut map_spawned: ResMutInit<MapSpawned>,
     mut tiles: CompMut<Tile>,
     mut tile_layers: CompMut<TileLayer>,
     mut transforms: CompMut<Transform>,
     mut element_handles: CompMut<ElementHandle>,
+    mut element_sizes: CompMut<ElementSize>,
     mut tile_collisions: CompMut<TileCollisionKind>,
     mut tile_dynamic_colliders: CompMut<TileDynamicCollider>,
+    mut destructible_tiles: CompMut<DestructibleTile>,
+    mut physics_materials: CompMut<PhysicsMaterial>,
     mut parallax_bg_sprites: CompMut<ParallaxBackgroundSprite>,
     mut sprites: CompMut<Sprite>,
     mut nav_graph: ResMutInit<NavGraph>,
@@ -250,12 +254,21 @@ fn spawn_map(
                 if tile_meta.collision != TileCollisionKind::Empty {
                     tile_collisions.insert(tile_ent, tile_meta.collision);
                 }
+                if tile_meta.destructible {
+                    destructible_tiles.insert(tile_ent, DestructibleTile);
+                }
+                if let Some(material) = meta.core.tile_material(&assets, tilemap, tile_meta.idx) {
+                    physics_materials.insert(tile_ent, material);
+                }
 
                 let atlas = assets.get(tilemap);
                 let optional_tile_colliders = &atlas.tile_collision;
-                // If metadata has valid dynamics collider, create component for it.
-                if let Some(extra_collider) =
-                    optional_tile_colliders.get(&format!("{}", tile_meta.idx))
+                // If metadata has valid dynamics collider, create component for it. Slopes are
+                // skipped, because the rectangular collider wouldn't match the slope's surface, and
+                // the slope's own collider is used for dynamic bodies instead.
+                if let Some(extra_collider) = optional_tile_colliders
+                    .get(&format!("{}", tile_meta.idx))
+                    .filter(|_| !tile_meta.collision.is_slope())
                 {
                     if extra_collider.has_area() {
                         // clamp collider between 0.0, 1.0
@@ -298,6 +311,9 @@ fn spawn_map(
                 Transform::from_translation(element_meta.pos.extend(layer_z)),
             );
             element_handles.insert(element_ent, ElementHandle(element_meta.element));
+            if element_meta.size != UVec2::ZERO {
+                element_sizes.insert(element_ent, ElementSize(element_meta.size));
+            }
         }
     }
 
@@ -317,13 +333,17 @@ fn handle_out_of_bounds_players(
     for (player_ent, (_player_idx, transform)) in entities.iter_with((&player_indexes, &transforms))
     {
         if map.is_out_of_bounds(&transform.translation) {
-            commands.add(PlayerCommand::kill(player_ent, None));
+            commands.add(PlayerCommand::kill(
+                player_ent,
+                None,
+                DamageSource::out_of_bounds(),
+            ));
         }
     }
 }
 
 /// Helper method to create a navigation graph from the map metadata.
-fn create_nav_graph(meta: &MapMeta) -> Arc<NavGraphInner> {
+pub(crate) fn create_nav_graph(meta: &MapMeta) -> Arc<NavGraphInner> {
     // Load the navigation graph
     let mut graph = NavGraphInner::default();
 
@@ -335,42 +355,179 @@ fn create_nav_graph(meta: &MapMeta) -> Arc<NavGraphInner> {
     }
 
     // Find all solid tiles and remove them from the traversable tiles list, while also recording
-    // the jump-through tiles.
-    let mut semi_solids = HashSet::default();
+    // the jump-through tiles and the slopes.
+    let mut tiles = NavTiles::default();
     for layer in &meta.layers {
         for tile in &layer.tiles {
-            if tile.collision == TileCollisionKind::JumpThrough {
-                semi_solids.insert(NavNode(tile.pos.as_ivec2()));
-            } else if tile.collision != TileCollisionKind::Empty {
-                graph.remove_node(NavNode(tile.pos.as_ivec2()));
-            }
+            tiles.insert(&mut graph, NavNode(tile.pos.as_ivec2()), tile.collision);
         }
     }
 
     // Calculate possible movements from every node
+    for node in graph.nodes().collect::<Vec<_>>() {
+        add_nav_edges(&mut graph, &tiles, node);
+    }
+
+    Arc::new(graph)
+}
+
+/// How far around a changed tile the edges of the navigation graph need to be calculated again.
+///
+/// Every edge reaches at most four nodes sideways and three nodes up, and looks at the tiles one
+/// node further than that.
+const NAV_EDGE_REACH: IVec2 = IVec2::new(5, 4);
+
+/// Helper method to update the navigation graph around the given tile positions, after the
+/// collisions of the tiles at those positions changed.
+///
+/// `collision_at` returns the collision of the map at a tile position. Only the tiles near the
+/// changed positions are looked at. The graph is copied on write, so that the previous one stays
+/// valid in earlier snapshots of the world.
+pub(crate) fn patch_nav_graph(
+    graph: &mut Arc<NavGraphInner>,
+    grid_size: UVec2,
+    changed: &[UVec2],
+    collision_at: impl Fn(UVec2) -> TileCollisionKind,
+) {
+    let Some(changed_min) = changed.iter().copied().reduce(UVec2::min) else {
+        return;
+    };
+    let changed_max = changed.iter().copied().fold(changed_min, UVec2::max);
+    let graph = Arc::make_mut(graph);
+
+    // Re-add the nodes of the changed tiles, and record the tiles that the edges of the affected
+    // nodes depend on.
+    for pos in changed {
+        graph.add_node(NavNode(pos.as_ivec2()));
+    }
+    let tiles_min = (changed_min.as_ivec2() - NAV_EDGE_REACH * 2).max(IVec2::ZERO);
+    let tiles_max = (changed_max.as_ivec2() + NAV_EDGE_REACH * 2).min(grid_size.as_ivec2() - 1);
+    let mut tiles = NavTiles::default();
+    for x in tiles_min.x..=tiles_max.x {
+        for y in tiles_min.y..=tiles_max.y {
+            let node = NavNode(ivec2(x, y));
+            tiles.insert(graph, node, collision_at(node.0.as_uvec2()));
+        }
+    }
+
+    // Only the edges of the nodes around the changed tiles need to be calculated again.
+    let mut affected = HashSet::default();
+    for pos in changed {
+        for x in -NAV_EDGE_REACH.x..=NAV_EDGE_REACH.x {
+            for y in -NAV_EDGE_REACH.y..=NAV_EDGE_REACH.y {
+                let node = NavNode(pos.as_ivec2() + ivec2(x, y));
+                if graph.contains_node(node) {
+                    affected.insert(node);
+                }
+            }
+        }
+    }
+    let mut affected = affected.into_iter().collect::<Vec<_>>();
+    affected.sort();
+    for node in affected {
+        let targets = graph.neighbors(node).collect::<Vec<_>>();
+        for target in targets {
+            graph.remove_edge(node, target);
+        }
+        add_nav_edges(graph, &tiles, node);
+    }
+}
+
+/// The tiles of the map that aren't simply solid or empty, used to calculate the edges of the
+/// navigation graph.
+///
+/// Solid tiles don't have a node in the graph.
+#[derive(Default)]
+struct NavTiles {
+    /// Solid tiles can be wall jumped off of.
+    solids: HashSet<NavNode>,
+    semi_solids: HashSet<NavNode>,
+    /// Slopes stay traversable, because they can be walked along while standing inside of the
+    /// slope tile.
+    slopes: HashMap<NavNode, TileCollisionKind>,
+}
+
+impl NavTiles {
+    /// Record the tile with the given collision, removing its node from the graph if it's solid.
+    fn insert(&mut self, graph: &mut NavGraphInner, node: NavNode, collision: TileCollisionKind) {
+        if collision == TileCollisionKind::JumpThrough {
+            self.semi_solids.insert(node);
+        } else if collision.is_slope() {
+            self.slopes.insert(node, collision);
+        } else if collision != TileCollisionKind::Empty {
+            self.solids.insert(node);
+            graph.remove_node(node);
+        }
+    }
+}
+
+/// Add the edges for all of the possible movements from the node.
+fn add_nav_edges(graph: &mut NavGraphInner, tiles: &NavTiles, node: NavNode) {
     macro_rules! is_solid {
         ($node:expr) => {
-            !graph.contains_node($node) || semi_solids.contains(&$node)
+            !graph.contains_node($node)
+                || tiles.semi_solids.contains(&$node)
+                || tiles.slopes.contains_key(&$node)
         };
     }
 
-    for node in graph.nodes().collect::<Vec<_>>() {
-        // walk left or right along the ground
-        let has_ground = is_solid!(node.below());
-        let maybe_has_ground =
-            has_ground || is_solid!(node.below().left()) || is_solid!(node.below().right());
+    // walk left or right along the ground
+    let has_ground = is_solid!(node.below()) || tiles.slopes.contains_key(&node);
+    let maybe_has_ground =
+        has_ground || is_solid!(node.below().left()) || is_solid!(node.below().right());
 
-        /////////////////
-        // Grounded
-        /////////////////
+    /////////////////
+    // Grounded
+    /////////////////
+
+    if maybe_has_ground {
+        // Moving Right
+        let right = node.right();
+        if graph.contains_node(right) {
+            graph.add_edge(
+                node,
+                right,
+                NavGraphEdge {
+                    inputs: [PlayerControl {
+                        moving: true,
+                        move_direction: vec2(1.0, 0.0),
+                        ..default()
+                    }]
+                    .into(),
+                    distance: node.distance(&right),
+                },
+            );
+        }
+
+        // Moving Left
+        let left = node.left();
+        if graph.contains_node(left) {
+            graph.add_edge(
+                node,
+                left,
+                NavGraphEdge {
+                    inputs: [PlayerControl {
+                        moving: true,
+                        move_direction: vec2(-1.0, 0.0),
+                        ..default()
+                    }]
+                    .into(),
+                    distance: node.distance(&left),
+                },
+            );
+        }
 
-        if maybe_has_ground {
-            // Moving Right
-            let right = node.right();
-            if graph.contains_node(right) {
+        // Walking up a slope, onto the tile above the next one
+        if let Some((left_height, right_height)) = tiles
+            .slopes
+            .get(&node)
+            .and_then(|kind| kind.slope_heights())
+        {
+            let up_right = node.right().above();
+            if right_height >= 1.0 && graph.contains_node(up_right) {
                 graph.add_edge(
                     node,
-                    right,
+                    up_right,
                     NavGraphEdge {
                         inputs: [PlayerControl {
                             moving: true,
@@ -378,17 +535,16 @@ fn create_nav_graph(meta: &MapMeta) -> Arc<NavGraphInner> {
                             ..default()
                         }]
                         .into(),
-                        distance: node.distance(&right),
+                        distance: node.distance(&up_right),
                     },
                 );
             }
 
-            // Moving Left
-            let left = node.left();
-            if graph.contains_node(left) {
+            let up_left = node.left().above();
+            if left_height >= 1.0 && graph.contains_node(up_left) {
                 graph.add_edge(
                     node,
-                    left,
+                    up_left,
                     NavGraphEdge {
                         inputs: [PlayerControl {
                             moving: true,
@@ -396,359 +552,412 @@ fn create_nav_graph(meta: &MapMeta) -> Arc<NavGraphInner> {
                             ..default()
                         }]
                         .into(),
-                        distance: node.distance(&left),
+                        distance: node.distance(&up_left),
                     },
                 );
             }
         }
+    }
 
-        if has_ground {
-            /////////////////
-            // JUMPING
-            /////////////////
-            let above1 = node.above();
-            let above2 = above1.above();
-            let above3 = above2.above();
-            let contains_above1 = graph.contains_node(above1);
-            let contains_above2 = graph.contains_node(above2);
-            let contains_above3 = graph.contains_node(above3);
+    if has_ground {
+        /////////////////
+        // JUMPING
+        /////////////////
+        let above1 = node.above();
+        let above2 = above1.above();
+        let above3 = above2.above();
+        let contains_above1 = graph.contains_node(above1);
+        let contains_above2 = graph.contains_node(above2);
+        let contains_above3 = graph.contains_node(above3);
+
+        if contains_above1 {
+            // Jump staight up
+            graph.add_edge(
+                node,
+                above1,
+                NavGraphEdge {
+                    inputs: [PlayerControl {
+                        jump_just_pressed: true,
+                        jump_pressed: true,
+                        ..default()
+                    }]
+                    .into(),
+                    distance: node.distance(&above1),
+                },
+            );
+        }
+        if contains_above2 && contains_above1 {
+            // Jump staight up
+            graph.add_edge(
+                node,
+                above2,
+                NavGraphEdge {
+                    inputs: [PlayerControl {
+                        jump_just_pressed: true,
+                        jump_pressed: true,
+                        ..default()
+                    }]
+                    .into(),
+                    distance: node.distance(&above2),
+                },
+            );
+        }
+        if contains_above3 && contains_above2 && contains_above1 {
+            // Jump staight up
+            graph.add_edge(
+                node,
+                above2,
+                NavGraphEdge {
+                    inputs: [PlayerControl {
+                        jump_just_pressed: true,
+                        jump_pressed: true,
+                        ..default()
+                    }]
+                    .into(),
+                    distance: node.distance(&above3),
+                },
+            );
+        }
 
-            if contains_above1 {
-                // Jump staight up
-                graph.add_edge(
-                    node,
-                    above1,
-                    NavGraphEdge {
-                        inputs: [PlayerControl {
-                            jump_just_pressed: true,
-                            jump_pressed: true,
-                            ..default()
-                        }]
-                        .into(),
-                        distance: node.distance(&above1),
-                    },
-                );
-            }
-            if contains_above2 && contains_above1 {
-                // Jump staight up
-                graph.add_edge(
-                    node,
-                    above2,
-                    NavGraphEdge {
-                        inputs: [PlayerControl {
-                            jump_just_pressed: true,
-                            jump_pressed: true,
-                            ..default()
-                        }]
-                        .into(),
-                        distance: node.distance(&above2),
-                    },
-                );
-            }
-            if contains_above3 && contains_above2 && contains_above1 {
-                // Jump staight up
-                graph.add_edge(
-                    node,
-                    above2,
-                    NavGraphEdge {
-                        inputs: [PlayerControl {
-                            jump_just_pressed: true,
-                            jump_pressed: true,
-                            ..default()
-                        }]
-                        .into(),
-                        distance: node.distance(&above3),
-                    },
-                );
-            }
+        // Jump up and left
+        let above3l2 = above3.left().left();
+        let above2l = above2.left();
+        let contains_above2l = graph.contains_node(above2l);
+        let contains_above3l2 = graph.contains_node(above3l2);
+        if contains_above3l2 && contains_above2 && contains_above3 && contains_above2l {
+            graph.add_edge(
+                node,
+                above3l2,
+                NavGraphEdge {
+                    inputs: std::iter::repeat(PlayerControl {
+                        move_direction: vec2(-1.0, 0.0),
+                        jump_just_pressed: true,
+                        jump_pressed: true,
+                        ..default()
+                    })
+                    .take(20)
+                    .collect(),
+                    distance: node.distance(&above3l2),
+                },
+            );
+        }
+        let above3l3 = above3.left().left().left();
+        if graph.contains_node(above3l3)
+            && graph.contains_node(above3.left())
+            && contains_above3l2
+            && contains_above2
+            && contains_above3
+            && contains_above2l
+        {
+            graph.add_edge(
+                node,
+                above3l3,
+                NavGraphEdge {
+                    inputs: std::iter::repeat(PlayerControl {
+                        move_direction: vec2(-1.0, 0.0),
+                        jump_just_pressed: true,
+                        jump_pressed: true,
+                        ..default()
+                    })
+                    .take(20)
+                    .collect(),
+                    distance: node.distance(&above3l3),
+                },
+            );
+        }
 
-            // Jump up and left
-            let above3l2 = above3.left().left();
-            let above2l = above2.left();
-            let contains_above2l = graph.contains_node(above2l);
-            let contains_above3l2 = graph.contains_node(above3l2);
-            if contains_above3l2 && contains_above2 && contains_above3 && contains_above2l {
-                graph.add_edge(
-                    node,
-                    above3l2,
-                    NavGraphEdge {
-                        inputs: std::iter::repeat(PlayerControl {
-                            move_direction: vec2(-1.0, 0.0),
-                            jump_just_pressed: true,
-                            jump_pressed: true,
-                            ..default()
-                        })
-                        .take(20)
-                        .collect(),
-                        distance: node.distance(&above3l2),
-                    },
-                );
-            }
-            let above3l3 = above3.left().left().left();
-            if graph.contains_node(above3l3)
-                && graph.contains_node(above3.left())
-                && contains_above3l2
-                && contains_above2
-                && contains_above3
-                && contains_above2l
+        // Jump up and right
+        let above3r2 = above3.right().right();
+        let above2r = above2.right();
+        let contains_above2r = graph.contains_node(above2r);
+        let contains_above3r2 = graph.contains_node(above3r2);
+        if contains_above3r2 && contains_above2 && contains_above3 && contains_above2r {
+            graph.add_edge(
+                node,
+                above3r2,
+                NavGraphEdge {
+                    inputs: std::iter::repeat(PlayerControl {
+                        move_direction: vec2(1.0, 0.0),
+                        jump_just_pressed: true,
+                        jump_pressed: true,
+                        ..default()
+                    })
+                    .take(20)
+                    .collect(),
+                    distance: node.distance(&above3r2),
+                },
+            );
+        }
+        let above3r3 = above3.right().right().right();
+        if graph.contains_node(above3r3)
+            && graph.contains_node(above3.right())
+            && contains_above3r2
+            && contains_above2
+            && contains_above3
+            && contains_above2r
+        {
+            graph.add_edge(
+                node,
+                above3r3,
+                NavGraphEdge {
+                    inputs: std::iter::repeat(PlayerControl {
+                        move_direction: vec2(1.0, 0.0),
+                        jump_just_pressed: true,
+                        jump_pressed: true,
+                        ..default()
+                    })
+                    .take(20)
+                    .collect(),
+                    distance: node.distance(&above3r3),
+                },
+            );
+        }
+    }
+
+    /////////////////
+    // Wall Jumping
+    /////////////////
+
+    if !has_ground {
+        for direction in [-1.0, 1.0] {
+            let (wall, away): (fn(&NavNode) -> NavNode, fn(&NavNode) -> NavNode) =
+                if direction < 0.0 {
+                    (NavNode::left, NavNode::right)
+                } else {
+                    (NavNode::right, NavNode::left)
+                };
+            if !tiles.solids.contains(&wall(&node)) || !tiles.solids.contains(&wall(&node.above()))
             {
-                graph.add_edge(
-                    node,
-                    above3l3,
-                    NavGraphEdge {
-                        inputs: std::iter::repeat(PlayerControl {
-                            move_direction: vec2(-1.0, 0.0),
-                            jump_just_pressed: true,
-                            jump_pressed: true,
-                            ..default()
-                        })
-                        .take(20)
-                        .collect(),
-                        distance: node.distance(&above3l3),
-                    },
-                );
+                continue;
             }
 
-            // Jump up and right
-            let above3r2 = above3.right().right();
-            let above2r = above2.right();
-            let contains_above2r = graph.contains_node(above2r);
-            let contains_above3r2 = graph.contains_node(above3r2);
-            if contains_above3r2 && contains_above2 && contains_above3 && contains_above2r {
+            // Slide down the wall by moving into it, then jump up and away from it
+            let above1 = node.above();
+            let above2 = above1.above();
+            let target = away(&away(&above2));
+            if [above1, above2, away(&above1), away(&above2), target]
+                .iter()
+                .all(|x| graph.contains_node(*x))
+            {
                 graph.add_edge(
                     node,
-                    above3r2,
+                    target,
                     NavGraphEdge {
                         inputs: std::iter::repeat(PlayerControl {
-                            move_direction: vec2(1.0, 0.0),
-                            jump_just_pressed: true,
-                            jump_pressed: true,
+                            moving: true,
+                            move_direction: vec2(direction, 0.0),
                             ..default()
                         })
-                        .take(20)
-                        .collect(),
-                        distance: node.distance(&above3r2),
-                    },
-                );
-            }
-            let above3r3 = above3.right().right().right();
-            if graph.contains_node(above3r3)
-                && graph.contains_node(above3.right())
-                && contains_above3r2
-                && contains_above2
-                && contains_above3
-                && contains_above2r
-            {
-                graph.add_edge(
-                    node,
-                    above3r3,
-                    NavGraphEdge {
-                        inputs: std::iter::repeat(PlayerControl {
-                            move_direction: vec2(1.0, 0.0),
+                        .take(10)
+                        .chain(std::iter::once(PlayerControl {
                             jump_just_pressed: true,
                             jump_pressed: true,
                             ..default()
-                        })
-                        .take(20)
+                        }))
+                        .chain(
+                            std::iter::repeat(PlayerControl {
+                                jump_pressed: true,
+                                ..default()
+                            })
+                            .take(10),
+                        )
                         .collect(),
-                        distance: node.distance(&above3r3),
+                        distance: node.distance(&target),
                     },
                 );
             }
         }
+    }
 
-        /////////////////
-        // Falling Down
-        /////////////////
+    /////////////////
+    // Falling Down
+    /////////////////
 
-        // Fall straight down
-        let below = node.below();
-        if graph.contains_node(below) {
-            if semi_solids.contains(&below) {
-                graph.add_edge(
-                    node,
-                    below,
-                    NavGraphEdge {
-                        inputs: [
-                            PlayerControl {
-                                move_direction: vec2(0.0, -1.0),
-                                jump_just_pressed: true,
-                                jump_pressed: true,
-                                ..default()
-                            },
-                            default(),
-                            default(),
-                            default(),
-                            default(),
-                        ]
-                        .into(),
-                        distance: node.distance(&below),
-                    },
-                );
-            } else {
-                graph.add_edge(
-                    node,
-                    below,
-                    NavGraphEdge {
-                        inputs: [PlayerControl::default()].into(),
-                        distance: node.distance(&below),
-                    },
-                );
-            }
+    // Fall straight down
+    let below = node.below();
+    if graph.contains_node(below) {
+        if tiles.semi_solids.contains(&below) {
+            graph.add_edge(
+                node,
+                below,
+                NavGraphEdge {
+                    inputs: [
+                        PlayerControl {
+                            move_direction: vec2(0.0, -1.0),
+                            jump_just_pressed: true,
+                            jump_pressed: true,
+                            ..default()
+                        },
+                        default(),
+                        default(),
+                        default(),
+                        default(),
+                    ]
+                    .into(),
+                    distance: node.distance(&below),
+                },
+            );
+        } else {
+            graph.add_edge(
+                node,
+                below,
+                NavGraphEdge {
+                    inputs: [PlayerControl::default()].into(),
+                    distance: node.distance(&below),
+                },
+            );
         }
+    }
 
-        // Fall diagonally down right
-        let below_right = node.below().right();
-        if graph.contains_node(below_right) {
-            if semi_solids.contains(&below_right) {
-                graph.add_edge(
-                    node,
-                    below_right,
-                    NavGraphEdge {
-                        inputs: [
-                            PlayerControl {
-                                move_direction: vec2(1.0, -1.0),
-                                jump_just_pressed: true,
-                                jump_pressed: true,
-                                ..default()
-                            },
-                            PlayerControl {
-                                move_direction: vec2(1.0, -1.0),
-                                jump_pressed: true,
-                                ..default()
-                            },
-                            PlayerControl {
-                                move_direction: vec2(1.0, 0.0),
-                                ..default()
-                            },
-                            PlayerControl {
-                                move_direction: vec2(1.0, 0.0),
-                                ..default()
-                            },
-                        ]
-                        .into(),
-                        distance: node.distance(&below_right),
-                    },
-                );
-            } else {
-                graph.add_edge(
-                    node,
-                    below_right,
-                    NavGraphEdge {
-                        inputs: [PlayerControl {
+    // Fall diagonally down right
+    let below_right = node.below().right();
+    if graph.contains_node(below_right) {
+        if tiles.semi_solids.contains(&below_right) {
+            graph.add_edge(
+                node,
+                below_right,
+                NavGraphEdge {
+                    inputs: [
+                        PlayerControl {
+                            move_direction: vec2(1.0, -1.0),
+                            jump_just_pressed: true,
+                            jump_pressed: true,
+                            ..default()
+                        },
+                        PlayerControl {
+                            move_direction: vec2(1.0, -1.0),
+                            jump_pressed: true,
+                            ..default()
+                        },
+                        PlayerControl {
                             move_direction: vec2(1.0, 0.0),
                             ..default()
-                        }]
-                        .into(),
-                        distance: node.distance(&below_right),
-                    },
-                );
-            }
-        }
-        // Fall diagonally down left
-        let below_left = node.below().left();
-        if graph.contains_node(below_left) {
-            if semi_solids.contains(&below_left) {
-                graph.add_edge(
-                    node,
-                    below_left,
-                    NavGraphEdge {
-                        inputs: [
-                            PlayerControl {
-                                move_direction: vec2(-1.0, -1.0),
-                                jump_just_pressed: true,
-                                jump_pressed: true,
-                                ..default()
-                            },
-                            PlayerControl {
-                                move_direction: vec2(-1.0, -1.0),
-                                jump_pressed: true,
-                                ..default()
-                            },
-                            PlayerControl {
-                                move_direction: vec2(-1.0, 0.0),
-                                ..default()
-                            },
-                            PlayerControl {
-                                move_direction: vec2(-1.0, 0.0),
-                                ..default()
-                            },
-                        ]
-                        .into(),
-                        distance: node.distance(&below_left),
-                    },
-                );
-            } else {
-                graph.add_edge(
-                    node,
-                    below_left,
-                    NavGraphEdge {
-                        inputs: [PlayerControl {
-                            move_direction: vec2(-1.0, 0.0),
+                        },
+                        PlayerControl {
+                            move_direction: vec2(1.0, 0.0),
                             ..default()
-                        }]
-                        .into(),
-                        distance: node.distance(&below_left),
-                    },
-                );
-            }
-        }
-
-        // Slow fall right
-        let far_right_below = node.right().right().right().right().below();
-        let path = [
-            node.right(),
-            node.right().right(),
-            node.right().right().right(),
-            node.right().right().right().right(),
-            far_right_below,
-        ];
-        if path.iter().all(|x| graph.contains_node(*x)) {
+                        },
+                    ]
+                    .into(),
+                    distance: node.distance(&below_right),
+                },
+            );
+        } else {
             graph.add_edge(
                 node,
-                far_right_below,
+                below_right,
                 NavGraphEdge {
-                    inputs: std::iter::repeat(PlayerControl {
+                    inputs: [PlayerControl {
                         move_direction: vec2(1.0, 0.0),
-                        jump_pressed: true,
                         ..default()
-                    })
-                    .take(20)
-                    .collect(),
-                    // Bias against using this move because it doesn't always work, by adding an
-                    // extra distance.
-                    distance: node.distance(&far_right_below) + 1.0,
+                    }]
+                    .into(),
+                    distance: node.distance(&below_right),
                 },
             );
         }
-        // Slow fall left
-        let far_left_below = node.left().left().left().left().below();
-        let path = [
-            node.left(),
-            node.left().left(),
-            node.left().left().left(),
-            node.left().left().left().left(),
-            far_left_below,
-        ];
-        if path.iter().all(|x| graph.contains_node(*x)) {
+    }
+    // Fall diagonally down left
+    let below_left = node.below().left();
+    if graph.contains_node(below_left) {
+        if tiles.semi_solids.contains(&below_left) {
             graph.add_edge(
                 node,
-                far_left_below,
+                below_left,
                 NavGraphEdge {
-                    inputs: std::iter::repeat(PlayerControl {
+                    inputs: [
+                        PlayerControl {
+                            move_direction: vec2(-1.0, -1.0),
+                            jump_just_pressed: true,
+                            jump_pressed: true,
+                            ..default()
+                        },
+                        PlayerControl {
+                            move_direction: vec2(-1.0, -1.0),
+                            jump_pressed: true,
+                            ..default()
+                        },
+                        PlayerControl {
+                            move_direction: vec2(-1.0, 0.0),
+                            ..default()
+                        },
+                        PlayerControl {
+                            move_direction: vec2(-1.0, 0.0),
+                            ..default()
+                        },
+                    ]
+                    .into(),
+                    distance: node.distance(&below_left),
+                },
+            );
+        } else {
+            graph.add_edge(
+                node,
+                below_left,
+                NavGraphEdge {
+                    inputs: [PlayerControl {
                         move_direction: vec2(-1.0, 0.0),
-                        jump_pressed: true,
                         ..default()
-                    })
-                    .take(20)
-                    .collect(),
-                    // Bias against using this move because it doesn't always work, by adding an
-                    // extra distance.
-                    distance: node.distance(&far_left_below) + 1.0,
+                    }]
+                    .into(),
+                    distance: node.distance(&below_left),
                 },
             );
         }
     }
 
-    Arc::new(graph)
+    // Slow fall right
+    let far_right_below = node.right().right().right().right().below();
+    let path = [
+        node.right(),
+        node.right().right(),
+        node.right().right().right(),
+        node.right().right().right().right(),
+        far_right_below,
+    ];
+    if path.iter().all(|x| graph.contains_node(*x)) {
+        graph.add_edge(
+            node,
+            far_right_below,
+            NavGraphEdge {
+                inputs: std::iter::repeat(PlayerControl {
+                    move_direction: vec2(1.0, 0.0),
+                    jump_pressed: true,
+                    ..default()
+                })
+                .take(20)
+                .collect(),
+                // Bias against using this move because it doesn't always work, by adding an
+                // extra distance.
+                distance: node.distance(&far_right_below) + 1.0,
+            },
+        );
+    }
+    // Slow fall left
+    let far_left_below = node.left().left().left().left().below();
+    let path = [
+        node.left(),
+        node.left().left(),
+        node.left().left().left(),
+        node.left().left().left().left(),
+        far_left_below,
+    ];
+    if path.iter().all(|x| graph.contains_node(*x)) {
+        graph.add_edge(
+            node,
+            far_left_below,
+            NavGraphEdge {
+                inputs: std::iter::repeat(PlayerControl {
+                    move_direction: vec2(-1.0, 0.0),
+                    jump_pressed: true,
+                    ..default()
+                })
+                .take(20)
+                .collect(),
+                // Bias against using this move because it doesn't always work, by adding an
+                // extra distance.
+                distance: node.distance(&far_left_below) + 1.0,
+            },
+        );
+    }
 }
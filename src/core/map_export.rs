@@ -0,0 +1,286 @@
+//! Exporting the map in the world to map metadata YAML.
+//!
+//! The map is reconstructed from the [`SpawnedMapMeta`] resource, the tile layers, and the map
+//! elements, using the [`SpawnedMapLayerMeta`] of each of them to find the layer they belong to.
+//! The result has the same format as the `.map.yaml` files in the game assets, so it can be loaded
+//! like any other map.
+
+use std::path::{Path, PathBuf};
+
+use crate::prelude::*;
+
+/// The name of the asset pack that maps are exported to from the map editor.
+pub const EDITOR_MAPS_PACK: &str = "editor_maps";
+
+/// Errors that may occur when exporting a map.
+#[derive(thiserror::Error, Debug)]
+pub enum MapExportError {
+    #[error("The map has not been spawned")]
+    MapNotSpawned,
+    #[error("Could not find the asset path of {0}")]
+    UnknownAsset(&'static str),
+    #[error("Could not serialize map: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Could not write map: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Map metadata in the format of the `.map.yaml` files, with asset paths instead of handles.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MapExport {
+    pub name: String,
+    pub background: BackgroundExport,
+    /// The background color, formatted like `rgba(91, 87, 114, 255)`.
+    pub background_color: String,
+    pub grid_size: [u32; 2],
+    pub tile_size: [f32; 2],
+    pub layers: Vec<MapLayerExport>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackgroundExport {
+    pub speed: [f32; 2],
+    pub layers: Vec<ParallaxLayerExport>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ParallaxLayerExport {
+    pub image: String,
+    pub size: [f32; 2],
+    pub depth: f32,
+    pub scale: f32,
+    pub offset: [f32; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MapLayerExport {
+    pub id: String,
+    pub tilemap: Option<String>,
+    pub tiles: Vec<MapTileExport>,
+    pub elements: Vec<ElementSpawnExport>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MapTileExport {
+    pub pos: [u32; 2],
+    pub idx: u32,
+    pub collision: TileCollisionKind,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub destructible: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ElementSpawnExport {
+    pub pos: [f32; 2],
+    pub element: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<[u32; 2]>,
+}
+
+impl MapExport {
+    /// Reconstruct the map metadata from the world.
+    ///
+    /// Asset paths are written relative to the asset pack with the given name, or relative to the
+    /// core game assets if there is no pack.
+    pub fn from_world(world: &World, pack: Option<&str>) -> Result<Self, MapExportError> {
+        world.run_system(export_map, pack)
+    }
+
+    /// Serialize the map to YAML.
+    pub fn to_yaml(&self) -> Result<String, MapExportError> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Get a file name for the map, based on its name.
+    pub fn file_name(&self) -> String {
+        let name = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let name = name.trim_matches('_');
+        format!("{}.map.yaml", if name.is_empty() { "map" } else { name })
+    }
+
+    /// Save the map to the `maps` folder of the asset pack in the given directory, adding it to the
+    /// pack's maps.
+    ///
+    /// The pack is created if it doesn't exist yet. Returns the path of the saved map.
+    pub fn save_to_pack(&self, pack_dir: &Path) -> Result<PathBuf, MapExportError> {
+        std::fs::create_dir_all(pack_dir.join("maps"))?;
+
+        let pack_meta_path = pack_dir.join("pack.yaml");
+        if !pack_meta_path.exists() {
+            std::fs::write(
+                &pack_meta_path,
+                format!(
+                    "name: Editor Maps\n\
+                     id: editormaps_01hk8v1g3q7x3c0m5n2e4r6t8w\n\
+                     version: 0.1.0\n\
+                     game_version: {}\n\
+                     root: ./assets.yaml\n",
+                    env!("CARGO_PKG_VERSION")
+                ),
+            )?;
+        }
+
+        let file_name = self.file_name();
+        let map_path = pack_dir.join("maps").join(&file_name);
+        std::fs::write(&map_path, self.to_yaml()?)?;
+
+        // Add the map to the pack root, keeping anything else that is in there.
+        let root_path = pack_dir.join("assets.yaml");
+        let mut root: serde_yaml::Mapping = match std::fs::read_to_string(&root_path) {
+            Ok(contents) => serde_yaml::from_str(&contents)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => default(),
+            Err(e) => return Err(e.into()),
+        };
+        let maps = root
+            .entry("maps".into())
+            .or_insert_with(|| serde_yaml::Value::Sequence(default()));
+        if let serde_yaml::Value::Sequence(maps) = maps {
+            let entry = serde_yaml::Value::String(format!("./maps/{file_name}"));
+            if !maps.contains(&entry) {
+                maps.push(entry);
+            }
+        }
+        std::fs::write(&root_path, serde_yaml::to_string(&root)?)?;
+
+        Ok(map_path)
+    }
+}
+
+/// Get the asset path of a handle, relative to the given pack.
+fn asset_path<T>(
+    assets: &AssetServer,
+    handle: Handle<T>,
+    pack: Option<&str>,
+) -> Result<String, MapExportError> {
+    let handle = handle.untyped();
+    let loc = assets
+        .store
+        .path_handles
+        .iter()
+        .find(|entry| *entry.value() == handle)
+        .map(|entry| entry.key().clone())
+        .ok_or(MapExportError::UnknownAsset(std::any::type_name::<T>()))?;
+
+    let path = loc.path.to_string_lossy().replace('\\', "/");
+    let path = if path.starts_with('/') {
+        path
+    } else {
+        format!("/{path}")
+    };
+    Ok(match loc.pack.as_deref() {
+        asset_pack if asset_pack == pack => path,
+        Some(asset_pack) => format!("{asset_pack}:{path}"),
+        None => format!("core:{path}"),
+    })
+}
+
+fn export_map(
+    pack: In<Option<&str>>,
+    assets: Res<AssetServer>,
+    entities: Res<Entities>,
+    spawned_map_meta: Option<Res<SpawnedMapMeta>>,
+    spawned_map_layer_metas: Comp<SpawnedMapLayerMeta>,
+    tile_layers: Comp<TileLayer>,
+    tiles: Comp<Tile>,
+    tile_collisions: Comp<TileCollisionKind>,
+    destructible_tiles: Comp<DestructibleTile>,
+    element_handles: Comp<ElementHandle>,
+    element_sizes: Comp<ElementSize>,
+    transforms: Comp<Transform>,
+) -> Result<MapExport, MapExportError> {
+    let pack = *pack;
+    let Some(map) = spawned_map_meta else {
+        return Err(MapExportError::MapNotSpawned);
+    };
+
+    let background = BackgroundExport {
+        speed: map.background.speed.to_array(),
+        layers: map
+            .background
+            .layers
+            .iter()
+            .map(|layer| {
+                Ok(ParallaxLayerExport {
+                    image: asset_path(&assets, layer.image, pack)?,
+                    size: layer.size.to_array(),
+                    depth: layer.depth,
+                    scale: layer.scale,
+                    offset: layer.offset.to_array(),
+                })
+            })
+            .collect::<Result<_, MapExportError>>()?,
+    };
+
+    let mut layers = map
+        .layer_names
+        .iter()
+        .map(|id| MapLayerExport {
+            id: id.to_string(),
+            tilemap: None,
+            tiles: Vec::new(),
+            elements: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    for (_, (tile_layer, layer_meta)) in
+        entities.iter_with((&tile_layers, &spawned_map_layer_metas))
+    {
+        let Some(layer) = layers.get_mut(layer_meta.layer_idx as usize) else {
+            continue;
+        };
+        if tile_layer.atlas != default() {
+            layer.tilemap = Some(asset_path(&assets, tile_layer.atlas, pack)?);
+        }
+        for y in 0..map.grid_size.y {
+            for x in 0..map.grid_size.x {
+                let Some(tile_ent) = tile_layer.get(uvec2(x, y)) else {
+                    continue;
+                };
+                let Some(tile) = tiles.get(tile_ent) else {
+                    continue;
+                };
+                layer.tiles.push(MapTileExport {
+                    pos: [x, y],
+                    idx: tile.idx,
+                    collision: tile_collisions.get(tile_ent).copied().unwrap_or_default(),
+                    destructible: destructible_tiles.contains(tile_ent),
+                });
+            }
+        }
+    }
+
+    // Only the entities spawned for the map elements have element handles, so this doesn't include
+    // the items or other entities that the elements spawned.
+    for (element_ent, (element_handle, transform, layer_meta)) in
+        entities.iter_with((&element_handles, &transforms, &spawned_map_layer_metas))
+    {
+        let Some(layer) = layers.get_mut(layer_meta.layer_idx as usize) else {
+            continue;
+        };
+        layer.elements.push(ElementSpawnExport {
+            pos: transform.translation.truncate().to_array(),
+            element: asset_path(&assets, element_handle.0, pack)?,
+            size: element_sizes.get(element_ent).map(|size| size.to_array()),
+        });
+    }
+
+    let [r, g, b, a] = map.background_color.as_rgba_u8();
+    Ok(MapExport {
+        name: map.name.to_string(),
+        background,
+        background_color: format!("rgba({r}, {g}, {b}, {a})"),
+        grid_size: map.grid_size.to_array(),
+        tile_size: map.tile_size.to_array(),
+        layers,
+    })
+}
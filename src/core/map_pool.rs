@@ -57,7 +57,7 @@ impl MapPool {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct MapPoolNetwork {
     pub maps: Vec<NetworkHandle<MapMeta>>,
     pub current_map: NetworkHandle<MapMeta>,
@@ -0,0 +1,153 @@
+//! Checking maps for mistakes that make them unplayable or look broken.
+//!
+//! [`validate_map`] works on the map metadata alone, so maps can be checked without starting a
+//! match, for example by the `jumpy-headless validate` command on CI.
+
+use crate::prelude::*;
+
+/// A problem found in a map by [`validate_map`].
+///
+/// The messages describe where the problem is and how to fix it.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum MapProblem {
+    #[error(
+        "The map has {count} player spawners, but needs at least {required}. \
+         Add more player spawners so that every player has their own spawn point."
+    )]
+    TooFewSpawners { count: usize, required: usize },
+    #[error(
+        "The player spawner at {pos} on layer `{layer}` is inside the solid or slope tile at \
+         {tile}. Move the spawner into open space, or remove the tile."
+    )]
+    SpawnerInsideSolid { layer: Ustr, pos: Vec2, tile: UVec2 },
+    #[error(
+        "The element `{element}` at {pos} on layer `{layer}` is outside of the map, which is \
+         {size} units large. Move it onto the map, or delete it."
+    )]
+    ElementOutOfBounds {
+        layer: Ustr,
+        element: Ustr,
+        pos: Vec2,
+        size: Vec2,
+    },
+    #[error(
+        "The tile at {pos} on layer `{layer}` uses tile index {idx}, but the layer's tilemap \
+         only has {tile_count} tiles. Pick a tile from the tilemap for it."
+    )]
+    TileIndexOutOfRange {
+        layer: Ustr,
+        pos: UVec2,
+        idx: u32,
+        tile_count: u32,
+    },
+    #[error(
+        "Layer `{layer}` has {tile_count} tiles but no tilemap, so its tiles are not spawned. \
+         Set a tilemap for the layer, or remove its tiles."
+    )]
+    TilesWithoutTilemap { layer: Ustr, tile_count: usize },
+    #[error(
+        "There is no path between the player spawners at {from} and {to}. \
+         Make sure that every spawner can be reached by walking, jumping, or falling."
+    )]
+    UnreachableSpawner { from: Vec2, to: Vec2 },
+}
+
+/// Check the map for problems, returning all of the problems found.
+pub fn validate_map(map: &MapMeta, assets: &AssetServer) -> Vec<MapProblem> {
+    let mut problems = Vec::new();
+    let map_size = map.grid_size.as_vec2() * map.tile_size;
+
+    let solid_tiles = map
+        .layers
+        .iter()
+        .flat_map(|layer| layer.tiles.iter())
+        .filter(|tile| tile.collision.is_solid())
+        .map(|tile| tile.pos)
+        .collect::<HashSet<_>>();
+
+    let mut spawners = Vec::new();
+    for layer in &map.layers {
+        match layer.tilemap {
+            Set(tilemap) => {
+                let atlas = assets.get(tilemap);
+                let tile_count = atlas.columns * atlas.rows;
+                for tile in layer.tiles.iter().filter(|tile| tile.idx >= tile_count) {
+                    problems.push(MapProblem::TileIndexOutOfRange {
+                        layer: layer.id,
+                        pos: tile.pos,
+                        idx: tile.idx,
+                        tile_count,
+                    });
+                }
+            }
+            Unset if !layer.tiles.is_empty() => {
+                problems.push(MapProblem::TilesWithoutTilemap {
+                    layer: layer.id,
+                    tile_count: layer.tiles.len(),
+                });
+            }
+            Unset => (),
+        }
+
+        for element_spawn in &layer.elements {
+            let element = assets.get(element_spawn.element);
+            let pos = element_spawn.pos;
+            if pos.cmplt(Vec2::ZERO).any() || pos.cmpgt(map_size).any() {
+                problems.push(MapProblem::ElementOutOfBounds {
+                    layer: layer.id,
+                    element: element.name,
+                    pos,
+                    size: map_size,
+                });
+            }
+
+            if assets
+                .get(element.data)
+                .try_cast_ref::<PlayerSpawner>()
+                .is_ok()
+            {
+                let tile = (pos / map.tile_size).floor();
+                if tile.cmpge(Vec2::ZERO).all() && solid_tiles.contains(&tile.as_uvec2()) {
+                    problems.push(MapProblem::SpawnerInsideSolid {
+                        layer: layer.id,
+                        pos,
+                        tile: tile.as_uvec2(),
+                    });
+                }
+                spawners.push(pos);
+            }
+        }
+    }
+
+    if spawners.len() < MAX_PLAYERS as usize {
+        problems.push(MapProblem::TooFewSpawners {
+            count: spawners.len(),
+            required: MAX_PLAYERS as usize,
+        });
+    }
+
+    // Spawners inside of solid tiles have no node in the graph, and were reported above.
+    let nav_graph = create_nav_graph(map);
+    let nav_node = |pos: Vec2| NavNode((pos / map.tile_size).floor().as_ivec2());
+    let spawners = spawners
+        .into_iter()
+        .filter(|pos| nav_graph.contains_node(nav_node(*pos)))
+        .collect::<Vec<_>>();
+    let has_path = |from: Vec2, to: Vec2| {
+        petgraph::algo::has_path_connecting(&*nav_graph, nav_node(from), nav_node(to), None)
+    };
+    for (i, from) in spawners.iter().enumerate() {
+        for to in &spawners[i + 1..] {
+            // Some spawners may only be left by falling, so only report spawners that can't be
+            // reached from each other either way.
+            if !has_path(*from, *to) && !has_path(*to, *from) {
+                problems.push(MapProblem::UnreachableSpawner {
+                    from: *from,
+                    to: *to,
+                });
+            }
+        }
+    }
+
+    problems
+}
@@ -5,9 +5,11 @@ use std::time::Duration;
 
 use crate::prelude::*;
 
+mod autotile;
 mod map;
 mod player;
 
+pub use autotile::*;
 pub use map::*;
 pub use player::*;
 
@@ -23,7 +25,10 @@ impl MatchAssetServerExt for &mut AssetServer {
         ElementMeta::register_schema();
         BulletMeta::register_schema();
         MapMeta::register_schema();
+        AutotileMeta::register_schema();
+        TilesetMeta::register_schema();
         HatMeta::register_schema();
+        PhysicsMaterial::register_schema();
 
         self
     }
@@ -36,6 +41,10 @@ pub struct CoreMeta {
     pub physics: PhysicsMeta,
     pub config: CoreConfigMeta,
     pub map_tilesets: SVec<Handle<Atlas>>,
+    /// The metadata of the map tilesets, such as the physics materials of their tiles.
+    pub map_tileset_metas: SVec<Handle<TilesetMeta>>,
+    /// The terrains that can be painted with auto-tiling in the map editor.
+    pub map_autotiles: SVec<Handle<AutotileMeta>>,
     pub players: SVec<Handle<PlayerMeta>>,
     pub player_emotes: SMap<Ustr, Handle<EmoteMeta>>,
     pub player_hats: SVec<Handle<HatMeta>>,
@@ -45,6 +54,29 @@ pub struct CoreMeta {
     pub experimental_maps: SVec<Handle<MapMeta>>,
 }
 
+impl CoreMeta {
+    /// Get the physics material of the tile with the given index in the given tileset, if it has
+    /// one.
+    pub fn tile_material(
+        &self,
+        assets: &AssetServer,
+        tilemap: Handle<Atlas>,
+        idx: u32,
+    ) -> Option<PhysicsMaterial> {
+        self.map_tileset_metas
+            .iter()
+            .map(|handle| assets.get(*handle))
+            .filter(|tileset| tileset.tilemap == tilemap)
+            .find_map(|tileset| {
+                tileset
+                    .materials
+                    .iter()
+                    .find(|x| x.tiles.contains(&idx))
+                    .map(|x| *assets.get(x.material))
+            })
+    }
+}
+
 #[derive(HasSchema, Clone, Debug)]
 #[repr(C)]
 pub struct CameraMeta {
@@ -84,6 +116,9 @@ pub struct PhysicsMeta {
     pub terminal_velocity: f32,
     pub friction_lerp: f32,
     pub stop_threshold: f32,
+    /// Kinematic bodies moving faster than this are swept along their path every frame, so that
+    /// they don't pass through other actors without colliding with them.
+    pub ccd_speed_threshold: f32,
     pub player: PhysicsPlayerMeta,
 }
 
@@ -105,6 +140,21 @@ pub struct CoreConfigMeta {
     #[serde(with = "humantime_serde")]
     pub respawn_invincibility_time: Duration,
 
+    /// How long players can't be damaged again after surviving a hit, when one-hit kills are
+    /// turned off.
+    #[serde(default)]
+    #[serde(with = "humantime_serde")]
+    pub hit_invincibility_time: Duration,
+
+    /// How long killed players that have lives left wait before respawning.
+    #[serde(default)]
+    #[serde(with = "humantime_serde")]
+    pub respawn_delay: Duration,
+
+    /// The rules of the matches started from the menus.
+    #[serde(default)]
+    pub rules: MatchRules,
+
     /// After one or fewer players left, how long to watch before scoring
     #[serde(default)]
     #[serde(with = "humantime_serde")]
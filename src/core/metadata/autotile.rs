@@ -0,0 +1,136 @@
+use super::*;
+
+/// Auto-tiling rules for painting a terrain with a tileset.
+///
+/// When a terrain is painted, the tile index of every tile is picked from its neighbours using the
+/// first matching [`AutotileRule`], so edges and corners don't have to be placed by hand.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[type_data(metadata_asset("autotile"))]
+#[repr(C)]
+pub struct AutotileMeta {
+    pub name: Ustr,
+    /// The tileset that the tile indices refer to.
+    pub tilemap: Handle<Atlas>,
+    /// The collision given to the painted tiles.
+    pub collision: TileCollisionKind,
+    /// The tile index used when none of the rules match.
+    pub default_idx: u32,
+    /// The rules to pick tile indices with, in order of priority.
+    pub rules: SVec<AutotileRule>,
+}
+
+/// A rule picking a tile index for a terrain tile based on which of its neighbours are terrain.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[repr(C)]
+pub struct AutotileRule {
+    /// The tile index to use when the rule matches.
+    pub idx: u32,
+    /// Three rows of three characters describing the neighbourhood of the tile, from the top row
+    /// to the bottom row.
+    ///
+    /// `#` means the neighbour must be terrain, `.` means it must not be, and any other character
+    /// means it doesn't matter. The center character is ignored.
+    pub pattern: SVec<Ustr>,
+}
+
+/// Bit flags for the neighbours of a tile, used to describe which of them are terrain.
+///
+/// North is towards the top of the map, which has the higher tile positions.
+pub mod neighbours {
+    pub const N: u8 = 1 << 0;
+    pub const NE: u8 = 1 << 1;
+    pub const E: u8 = 1 << 2;
+    pub const SE: u8 = 1 << 3;
+    pub const S: u8 = 1 << 4;
+    pub const SW: u8 = 1 << 5;
+    pub const W: u8 = 1 << 6;
+    pub const NW: u8 = 1 << 7;
+
+    /// The offset to each of the neighbours, with the bit flag of the neighbour.
+    pub const OFFSETS: [(i32, i32, u8); 8] = [
+        (0, 1, N),
+        (1, 1, NE),
+        (1, 0, E),
+        (1, -1, SE),
+        (0, -1, S),
+        (-1, -1, SW),
+        (-1, 0, W),
+        (-1, 1, NW),
+    ];
+}
+
+impl AutotileMeta {
+    /// Get the tile index for a terrain tile, given the [`neighbours`] that are terrain.
+    pub fn tile_idx(&self, neighbours: u8) -> u32 {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(neighbours))
+            .map(|rule| rule.idx)
+            .unwrap_or(self.default_idx)
+    }
+
+    /// Whether the tile index is one of the tiles that this terrain paints with.
+    pub fn contains(&self, idx: u32) -> bool {
+        idx == self.default_idx || self.rules.iter().any(|rule| rule.idx == idx)
+    }
+}
+
+impl AutotileRule {
+    /// Get the neighbours that must be terrain, and the neighbours that must not be terrain.
+    pub fn masks(&self) -> (u8, u8) {
+        let mut required = 0;
+        let mut excluded = 0;
+        for (row, line) in self.pattern.iter().take(3).enumerate() {
+            for (column, c) in line.chars().take(3).enumerate() {
+                let offset = (column as i32 - 1, 1 - row as i32);
+                let Some((_, _, flag)) = neighbours::OFFSETS
+                    .iter()
+                    .find(|(x, y, _)| (*x, *y) == offset)
+                else {
+                    continue;
+                };
+                match c {
+                    '#' => required |= flag,
+                    '.' => excluded |= flag,
+                    _ => (),
+                }
+            }
+        }
+        (required, excluded)
+    }
+
+    /// Whether the rule matches a tile with the given [`neighbours`].
+    pub fn matches(&self, neighbours: u8) -> bool {
+        let (required, excluded) = self.masks();
+        neighbours & required == required && neighbours & excluded == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rules_match_in_order() {
+        let rule = |idx, pattern: [&str; 3]| AutotileRule {
+            idx,
+            pattern: pattern.into_iter().map(ustr).collect(),
+        };
+        let terrain = AutotileMeta {
+            default_idx: 18,
+            rules: [
+                rule(0, ["?.?", ".##", "?#?"]),
+                rule(1, ["?.?", "###", "?#?"]),
+            ]
+            .into_iter()
+            .collect(),
+            ..default()
+        };
+
+        use neighbours::*;
+        assert_eq!(terrain.tile_idx(E | S), 0);
+        assert_eq!(terrain.tile_idx(E | SE | S), 0);
+        assert_eq!(terrain.tile_idx(E | S | W), 1);
+        assert_eq!(terrain.tile_idx(N | E | S | W), 18);
+    }
+}
@@ -48,6 +48,9 @@ pub struct MapLayerMeta {
 pub struct ElementSpawn {
     pub pos: Vec2,
     pub element: Handle<ElementMeta>,
+    /// The size of the element in tiles, for elements that can be resized in the editor, such as
+    /// water. Zero uses the element's default size.
+    pub size: UVec2,
 }
 
 #[derive(HasSchema, Clone, Debug, Default)]
@@ -56,6 +59,28 @@ pub struct MapTileMeta {
     pub pos: UVec2,
     pub idx: u32,
     pub collision: TileCollisionKind,
+    /// Whether the tile is destroyed by explosions.
+    pub destructible: bool,
+}
+
+/// Metadata for a map tileset, which gives some of its tiles a physics material.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[type_data(metadata_asset("tileset"))]
+#[repr(C)]
+pub struct TilesetMeta {
+    /// The tileset that the tile indices refer to.
+    pub tilemap: Handle<Atlas>,
+    /// The physics materials of the tiles.
+    pub materials: SVec<TileMaterialMeta>,
+}
+
+/// Gives some of the tiles of a tileset a physics material.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[repr(C)]
+pub struct TileMaterialMeta {
+    /// The indices of the tiles in the tileset that have the material.
+    pub tiles: SVec<u32>,
+    pub material: Handle<PhysicsMaterial>,
 }
 
 impl MapMeta {
@@ -10,6 +10,8 @@ pub struct PlayerMeta {
     pub gravity: f32,
     pub sounds: PlayerSoundsMeta,
     pub stats: PlayerStatsMeta,
+    /// The abilities that the player has on top of the moveset from their stats.
+    pub abilities: PlayerAbilitiesMeta,
     pub layers: PlayerLayersMeta,
 }
 
@@ -77,6 +79,82 @@ pub struct PlayerStatsMeta {
     pub walk_speed: f32,
     pub slowdown: f32,
     pub accel_walk_speed: f32,
+    /// The maximum speed that the player swims at in water.
+    pub swim_speed: f32,
+    /// How fast the player speeds up while swimming in water.
+    pub accel_swim_speed: f32,
+    /// The health that the player spawns with, when one-hit kills are turned off.
+    pub health: f32,
+    /// The maximum speed that the player slides down a wall at.
+    pub wall_slide_speed: f32,
+    /// The velocity that the player jumps off of a wall with. The horizontal velocity is away from
+    /// the wall.
+    pub wall_jump_velocity: Vec2,
+    /// The number of frames after jumping off of a wall that the player can't steer for.
+    pub wall_jump_lockout_frames: u32,
+}
+
+/// The abilities of a player, see [`PlayerAbilities`].
+///
+/// An ability is disabled when it has no charges.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[repr(C)]
+pub struct PlayerAbilitiesMeta {
+    pub double_jump: DoubleJumpMeta,
+    pub air_dash: AirDashMeta,
+    pub ground_pound: GroundPoundMeta,
+}
+
+impl PlayerAbilitiesMeta {
+    /// Add the abilities that are granted by a hat or an item, which replace the player's own
+    /// version of those abilities.
+    pub fn with_granted(mut self, granted: &PlayerAbilitiesMeta) -> Self {
+        if granted.double_jump.charges > 0 {
+            self.double_jump = granted.double_jump.clone();
+        }
+        if granted.air_dash.charges > 0 {
+            self.air_dash = granted.air_dash.clone();
+        }
+        if granted.ground_pound.charges > 0 {
+            self.ground_pound = granted.ground_pound.clone();
+        }
+        self
+    }
+}
+
+/// Jumping again while in the air.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[repr(C)]
+pub struct DoubleJumpMeta {
+    /// The number of times that the player can jump in the air before landing again.
+    pub charges: u32,
+    /// The time after a jump before the player can jump in the air again, in seconds.
+    pub cooldown: f32,
+    pub jump_speed: f32,
+}
+
+/// Dashing sideways through the air, without falling.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[repr(C)]
+pub struct AirDashMeta {
+    /// The number of times that the player can dash before landing again.
+    pub charges: u32,
+    /// The time after a dash before the player can dash again, in seconds.
+    pub cooldown: f32,
+    pub speed: f32,
+    /// The number of frames that a dash lasts for.
+    pub frames: u32,
+}
+
+/// Slamming straight down to the ground from the air.
+#[derive(HasSchema, Clone, Debug, Default)]
+#[repr(C)]
+pub struct GroundPoundMeta {
+    /// The number of times that the player can ground pound before landing again.
+    pub charges: u32,
+    /// The time after a ground pound before the player can ground pound again, in seconds.
+    pub cooldown: f32,
+    pub speed: f32,
 }
 
 #[derive(HasSchema, Clone, Debug, Default)]
@@ -201,4 +279,6 @@ pub struct HatMeta {
     pub atlas: Handle<Atlas>,
     pub offset: Vec2,
     pub body_size: Vec2,
+    /// The abilities that the player wearing the hat is granted.
+    pub abilities: PlayerAbilitiesMeta,
 }
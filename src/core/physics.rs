@@ -3,8 +3,8 @@
 use crate::prelude::*;
 
 pub use collisions::{
-    Actor, Collider, ColliderShape, CollisionWorld, PhysicsParams, RapierContext, RapierUserData,
-    Solid, TileCollisionKind,
+    Actor, CastFilter, Collider, ColliderShape, CollisionCast, CollisionHit, CollisionWorld,
+    PhysicsParams, RapierContext, RapierUserData, Solid, TileCollisionKind,
 };
 
 use super::utils::Rect;
@@ -12,8 +12,12 @@ pub use rapier2d::prelude as rapier;
 
 pub mod collisions;
 pub mod dynamic_body;
+pub mod impulse;
+pub mod material;
 
 pub use dynamic_body::*;
+pub use impulse::*;
+pub use material::*;
 
 /// For now kinematic mode is globally position based.
 pub static KINEMATIC_MODE: rapier::RigidBodyType = rapier::RigidBodyType::KinematicPositionBased;
@@ -37,7 +41,11 @@ impl StageLabel for PhysicsStage {
 
 pub fn install(session: &mut Session) {
     KinematicBody::register_schema();
+    MirrorCollider::register_schema();
+    WaterVolume::register_schema();
+    RadialImpulse::register_schema();
     ColliderShape::register_schema();
+    CollisionCast::register_schema();
 
     session
         .stages
@@ -50,7 +58,10 @@ pub fn install(session: &mut Session) {
             SimpleSystemStage::new(PhysicsStage::Update),
         )
         .add_system_to_stage(PhysicsStage::Update, hydrate_physics_bodies)
-        .add_system_to_stage(PhysicsStage::Update, update_kinematic_bodies);
+        .add_system_to_stage(PhysicsStage::Update, mirror_colliders)
+        .add_system_to_stage(PhysicsStage::Update, apply_radial_impulses)
+        .add_system_to_stage(PhysicsStage::Update, update_kinematic_bodies)
+        .add_system_to_stage(PhysicsStage::Update, collisions::update_collision_casts);
 }
 
 /// A kinematic physics body
@@ -69,17 +80,17 @@ pub struct KinematicBody {
     pub angular_velocity: f32,
     pub gravity: f32,
     pub bounciness: f32,
-    /// Sets a 1 frame override for the body friction. It will be re-set to `None` every frame so if
-    /// you wish to apply a continuous friction change, you must re-set it every frame.
+    /// The [`PhysicsMaterial`] of what the body is standing on and touching, combined with its own.
     ///
-    /// This is useful for things like slippery blocks or other things that want to modify a body's
-    /// friction while it is on the block.
-    #[schema(opaque)]
-    pub frame_friction_override: Option<f32>,
+    /// This is updated every frame, and is the default material while the body is in the air.
+    pub material: PhysicsMaterial,
     pub is_on_ground: bool,
     pub was_on_ground: bool,
     /// Will be `true` if the body is currently on top of a platform/jumpthrough tile
     pub is_on_platform: bool,
+    /// The gradient of the slope that the body is currently standing on, or `0.0` if it isn't
+    /// standing on a slope. See [`TileCollisionKind::slope_gradient`].
+    pub slope_gradient: f32,
     /// If this is `true` the body will be affected by gravity
     pub has_mass: bool,
     pub has_friction: bool,
@@ -103,6 +114,10 @@ pub struct KinematicBody {
     pub last_update_position: Vec2,
     /// See comment for `last_update_position`, this tracks previous rotation to detect if object has moved.
     pub last_update_rotation: f32,
+
+    /// How much of the body is inside of a [`WaterVolume`], from `0.0` when it is out of the water
+    /// to `1.0` when it is fully under water.
+    pub submersion: f32,
 }
 
 impl KinematicBody {
@@ -122,6 +137,70 @@ impl KinematicBody {
     }
 }
 
+/// Component that mirrors the collider of an entity's [`KinematicBody`] when its [`AtlasSprite`]
+/// is flipped horizontally, for bodies with asymmetric [`ColliderShape::ConvexPolygon`]s.
+#[derive(Default, Debug, Clone, Copy, HasSchema)]
+#[repr(C)]
+pub struct MirrorCollider {
+    /// Whether the body's shape is currently mirrored.
+    pub mirrored: bool,
+}
+
+/// System that mirrors the colliders with a [`MirrorCollider`] to match their sprites.
+fn mirror_colliders(
+    entities: Res<Entities>,
+    atlas_sprites: Comp<AtlasSprite>,
+    mut mirror_colliders: CompMut<MirrorCollider>,
+    mut bodies: CompMut<KinematicBody>,
+) {
+    for (_ent, (mirror, body, sprite)) in
+        entities.iter_with((&mut mirror_colliders, &mut bodies, &atlas_sprites))
+    {
+        if mirror.mirrored != sprite.flip_x {
+            body.shape = body.shape.flip_x();
+            mirror.mirrored = sprite.flip_x;
+        }
+    }
+}
+
+/// A rectangular region of water, centered on the entity's [`Transform`].
+///
+/// Bodies in the water fall slower, and bodies that aren't being controlled float up to the
+/// surface.
+#[derive(Default, Debug, Clone, Copy, HasSchema)]
+#[repr(C)]
+pub struct WaterVolume {
+    /// The size of the water region.
+    pub size: Vec2,
+    /// What gravity is multiplied by for bodies that are fully under water.
+    pub gravity_scale: f32,
+    /// What the terminal velocity is multiplied by for bodies that are fully under water.
+    pub terminal_velocity_scale: f32,
+    /// How strongly bodies that aren't being controlled are pushed up, as a fraction of gravity.
+    ///
+    /// Values above `1.0` make those bodies float up to the surface.
+    pub buoyancy: f32,
+    /// The fraction of a body's velocity that is lost per second while it is fully under water.
+    pub drag: f32,
+}
+
+impl WaterVolume {
+    /// Get how much of the given rectangle is under the water, from `0.0` to `1.0`.
+    pub fn submersion(&self, transform: &Transform, rect: Rect) -> f32 {
+        let water = Rect::new(
+            transform.translation.x,
+            transform.translation.y,
+            self.size.x,
+            self.size.y,
+        );
+        if !water.overlaps(&rect) || rect.height() <= 0.0 {
+            return 0.0;
+        }
+        let depth = rect.max.y.min(water.max.y) - rect.min.y.max(water.min.y);
+        (depth / rect.height()).clamp(0.0, 1.0)
+    }
+}
+
 /// Hydrate newly added [`KinematicBody`]s.
 fn hydrate_physics_bodies(
     entities: Res<Entities>,
@@ -155,6 +234,7 @@ fn update_kinematic_bodies(
     mut dynamic_bodies: CompMut<DynamicBody>,
     mut collision_world: CollisionWorld,
     mut transforms: CompMut<Transform>,
+    water_volumes: Comp<WaterVolume>,
     time: Res<Time>,
 ) {
     puffin::profile_function!();
@@ -171,6 +251,22 @@ fn update_kinematic_bodies(
         &mut transforms,
         &mut dynamic_bodies,
     );
+    let water_volumes = entities
+        .iter_with((&water_volumes, &transforms))
+        .map(|(_, (water, transform))| (*water, *transform))
+        .collect::<Vec<_>>();
+    // Materials on actors, like slippery patches, apply to the bodies that touch them, and materials
+    // on tiles and solids to the bodies that stand on or bounce off of them. Most maps have neither,
+    // so looking for them is skipped when there are none.
+    let mut has_actor_materials = false;
+    let mut has_surface_materials = false;
+    for (ent, _) in entities.iter_with(&collision_world.physics_materials) {
+        if collision_world.actors.contains(ent) {
+            has_actor_materials = true;
+        } else {
+            has_surface_materials = true;
+        }
+    }
     for (entity, (body, dynamic_body)) in
         entities.iter_with((&mut bodies, &mut OptionalMut(&mut dynamic_bodies)))
     {
@@ -181,8 +277,35 @@ fn update_kinematic_bodies(
             collision_world.colliders.get_mut(entity).unwrap().disabled = false;
         }
 
+        // Find out how deep the body is in the water, using the deepest water volume it's in.
+        let mut water = None;
+        body.submersion = 0.0;
+        if !water_volumes.is_empty() {
+            let rect = body.bounding_box(*transforms.get(entity).unwrap());
+            for (volume, transform) in &water_volumes {
+                let submersion = volume.submersion(transform, rect);
+                if submersion > body.submersion {
+                    body.submersion = submersion;
+                    water = Some(*volume);
+                }
+            }
+        }
+
         if let Some(dynamic_body) = dynamic_body {
             if dynamic_body.is_dynamic {
+                // Rapier simulates the body, so float it up and slow it down there instead.
+                if let Some(water) = water {
+                    let submersion = body.submersion;
+                    let lift = global_gravity * water.buoyancy * submersion * time_factor;
+                    let damping = (1.0 - water.drag * submersion * time_factor).max(0.0);
+                    dynamic_body.push_simulation_command(Box::new(
+                        move |body: &mut rapier::RigidBody| {
+                            let lin_vel = *body.linvel() * damping;
+                            body.set_linvel(lin_vel, true);
+                            body.apply_impulse(rapier::vector!(0.0, lift * body.mass()), true);
+                        },
+                    ));
+                }
                 continue;
             }
         }
@@ -206,7 +329,9 @@ fn update_kinematic_bodies(
             loop {
                 let mut transform = transforms.get(entity).copied().unwrap();
 
-                if collision_world.tile_collision(transform, body.shape) != TileCollisionKind::Solid
+                if !collision_world
+                    .tile_collision(transform, body.shape)
+                    .is_solid()
                 {
                     break;
                 }
@@ -269,7 +394,15 @@ fn update_kinematic_bodies(
 
             if collision_world.move_vertical(&mut transforms, entity, body.velocity.y * time_factor)
             {
-                body.velocity.y *= -body.bounciness;
+                let bounciness = surface_bounciness(
+                    &collision_world,
+                    &transforms,
+                    entity,
+                    body,
+                    vec2(0.0, body.velocity.y.signum()),
+                    has_surface_materials,
+                );
+                body.velocity.y *= -bounciness;
             }
 
             // NOTE: It's important that we move horizontally after we move vertically, or else the
@@ -280,7 +413,31 @@ fn update_kinematic_bodies(
                 entity,
                 body.velocity.x * time_factor,
             ) {
-                body.velocity.x *= -body.bounciness;
+                let bounciness = surface_bounciness(
+                    &collision_world,
+                    &transforms,
+                    entity,
+                    body,
+                    vec2(body.velocity.x.signum(), 0.0),
+                    has_surface_materials,
+                );
+                body.velocity.x *= -bounciness;
+            }
+
+            // Keep bodies on the ground while they move down slopes, instead of having them fall
+            // down the slope in small steps.
+            if body.slope_gradient != 0.0 && body.velocity.y <= 0.0 {
+                collision_world.snap_to_ground(
+                    &mut transforms,
+                    entity,
+                    body.velocity.x.abs() * time_factor + 1.0,
+                );
+            }
+
+            // Fast bodies may move past other actors in a single frame, so check for collisions
+            // along the whole path that they moved.
+            if body.velocity.length() > meta.core.physics.ccd_speed_threshold {
+                collision_world.sweep_actor(&transforms, entity, body.last_update_position);
             }
         }
 
@@ -296,11 +453,7 @@ fn update_kinematic_bodies(
                 // Don't get stuck floating in fall-through platforms
                 if body.velocity == Vec2::ZERO
                     && collision_world.tile_collision_filtered(transform, body.shape, |ent| {
-                        collision_world
-                            .tile_collision_kinds
-                            .get(ent)
-                            .map(|x| *x == TileCollisionKind::JumpThrough)
-                            .unwrap_or(false)
+                        collision_world.collision_kind(ent) == Some(TileCollisionKind::JumpThrough)
                     }) == TileCollisionKind::JumpThrough
                 {
                     body.fall_through = true;
@@ -317,9 +470,8 @@ fn update_kinematic_bodies(
             let tile = collision_world.tile_collision_filtered(transform, body.shape, |ent| {
                 if collider.seen_wood {
                     collision_world
-                        .tile_collision_kinds
-                        .get(ent)
-                        .map(|x| *x != TileCollisionKind::JumpThrough)
+                        .collision_kind(ent)
+                        .map(|x| x != TileCollisionKind::JumpThrough)
                         .unwrap_or(false)
                 } else {
                     true
@@ -330,37 +482,81 @@ fn update_kinematic_bodies(
             body.is_on_ground =
                 tile != TileCollisionKind::Empty && !(on_jump_through_tile && body.fall_through);
             body.is_on_platform = body.is_on_ground && on_jump_through_tile;
+            body.slope_gradient = if body.is_on_ground {
+                tile.slope_gradient()
+            } else {
+                0.0
+            };
+
+            // Find the material of what the body is standing on and touching
+            body.material = if body.is_on_ground && has_surface_materials {
+                collision_world.physics_material_at(transform, body.shape)
+            } else {
+                default()
+            };
+            if has_actor_materials {
+                for other in collision_world.actor_collisions(entity) {
+                    if let Some(material) = collision_world.physics_materials.get(other) {
+                        body.material = body.material.combine(*material);
+                    }
+                }
+            }
+            if let Some(material) = collision_world.physics_materials.get(entity) {
+                body.material = body.material.combine(*material);
+            }
+        }
+
+        // Water makes bodies lighter, and pushes the ones that aren't swimming up towards the
+        // surface.
+        let mut gravity = body.gravity;
+        let mut terminal_velocity = meta.core.physics.terminal_velocity;
+        if let Some(water) = water {
+            gravity *= 1.0 + (water.gravity_scale - 1.0) * body.submersion;
+            terminal_velocity *= 1.0 + (water.terminal_velocity_scale - 1.0) * body.submersion;
+            if !body.is_controlled {
+                gravity -= body.gravity * water.buoyancy * body.submersion;
+            }
         }
+        let is_floating = body.has_mass && gravity < 0.0;
 
         if body.is_on_ground {
             if body.has_friction {
-                body.velocity.x *= if let Some(friction) = body.frame_friction_override {
-                    friction
-                } else {
-                    meta.core.physics.friction_lerp
-                };
-                body.frame_friction_override = None;
+                body.velocity.x *= body.material.friction_lerp(meta.core.physics.friction_lerp);
 
-                if body.velocity.x.abs() <= meta.core.physics.stop_threshold {
+                // Bodies that are sliding down a slope don't come to a stop.
+                if body.velocity.x.abs() <= meta.core.physics.stop_threshold
+                    && (body.slope_gradient == 0.0 || body.is_controlled)
+                {
                     body.velocity.x = 0.0;
                 }
 
                 body.velocity.y *= meta.core.physics.friction_lerp;
             }
 
-            if body.velocity.y <= body.gravity * time_factor {
+            if body.velocity.y <= body.gravity * time_factor && !is_floating {
                 body.velocity.y = 0.0;
             }
+
+            // Bodies that aren't being controlled slide down slopes, accelerated by the part of
+            // gravity that is along the slope.
+            if body.slope_gradient != 0.0 && body.has_mass && !body.is_controlled {
+                let gradient = body.slope_gradient;
+                body.velocity.x -= body.gravity * time_factor * gradient / (1.0 + gradient.powi(2));
+            }
         }
 
-        if !body.is_on_ground && body.has_mass {
-            body.velocity.y -= body.gravity * time_factor;
+        if (!body.is_on_ground || is_floating) && body.has_mass {
+            body.velocity.y -= gravity * time_factor;
 
-            if body.velocity.y < -meta.core.physics.terminal_velocity {
-                body.velocity.y = -meta.core.physics.terminal_velocity;
+            if body.velocity.y < -terminal_velocity {
+                body.velocity.y = -terminal_velocity;
             }
         }
 
+        if let Some(water) = water {
+            body.velocity *= (1.0 - water.drag * body.submersion * time_factor).max(0.0);
+        }
+
         if body.can_rotate {
             apply_rotation(
                 time_factor,
@@ -374,6 +570,27 @@ fn update_kinematic_bodies(
     }
 }
 
+/// Get how much of its speed a body keeps when it bounces off of the surface in the given direction
+/// from it, which depends on its own bounciness and on the [`PhysicsMaterial`] of the surface.
+///
+/// The surface is only looked for if any tiles or solids have a material.
+fn surface_bounciness(
+    collision_world: &CollisionWorld,
+    transforms: &CompMut<Transform>,
+    entity: Entity,
+    body: &KinematicBody,
+    direction: Vec2,
+    has_surface_materials: bool,
+) -> f32 {
+    if !has_surface_materials {
+        return body.bounciness;
+    }
+    let mut transform = *transforms.get(entity).unwrap();
+    transform.translation += (direction * 0.1).extend(0.0);
+    let material = collision_world.physics_material_at(transform, body.shape);
+    body.bounciness.max(material.bounciness)
+}
+
 /// Helper function to apply rotation to a kinematic body.
 fn apply_rotation(
     delta_time: f32,
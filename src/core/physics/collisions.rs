@@ -4,12 +4,14 @@ use std::hash::BuildHasherDefault;
 
 use indexmap::IndexMap;
 
+pub use query::*;
 use rapier::Vector;
 use rapier2d::geometry::InteractionGroups;
 pub use rapier2d::prelude as rapier;
 pub use shape::*;
 
 pub mod filtering;
+mod query;
 mod shape;
 
 use crate::collisions::filtering::CollisionGroup;
@@ -63,6 +65,31 @@ impl Clone for RapierContext {
     }
 }
 
+impl RapierContext {
+    /// Remove the rapier body of a map tile while its tile is still alive, notifying the collision
+    /// cache so that the collisions with it are ended.
+    fn remove_tile_body(&mut self, handle: rapier::RigidBodyHandle) {
+        let Some(body) = self.rigid_body_set.get(handle) else {
+            return;
+        };
+        for collider_handle in body.colliders() {
+            if let Some(collider) = self.collider_set.get(*collider_handle) {
+                let entity = RapierUserData::entity(collider.user_data);
+                self.collision_cache
+                    .collider_removed(entity, *collider_handle);
+            }
+        }
+        self.rigid_body_set.remove(
+            handle,
+            &mut self.islands,
+            &mut self.collider_set,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        );
+    }
+}
+
 /// A cache containing a map of entities, to the list of entities that each entity is currently
 /// intersecting with.
 pub struct CollisionCache {
@@ -78,6 +105,10 @@ pub struct CollisionCache {
     /// TODO: Consider a safer way to handle this that doesn't involve remembering to update this on
     /// removal.
     removed_colliders: IndexMap<rapier::ColliderHandle, Entity>,
+
+    /// The actors that fast moving entities have passed through since the last update, in addition
+    /// to the ones that they are intersecting. See [`CollisionWorld::sweep_actor`].
+    pub swept: IndexMap<Entity, Vec<Entity>, EntityBuildHasher>,
 }
 
 impl Default for CollisionCache {
@@ -85,6 +116,7 @@ impl Default for CollisionCache {
         Self {
             collisions: Arc::new(AtomicCell::new(IndexMap::default())),
             removed_colliders: Default::default(),
+            swept: Default::default(),
         }
     }
 }
@@ -119,6 +151,18 @@ impl CollisionCache {
         })
     }
 
+    /// Get the entities that the given `entity` is intersecting, or has been swept through since
+    /// the last update.
+    pub fn get_with_swept(&self, entity: Entity) -> Vec<Entity> {
+        let mut collisions = self.get(entity).clone();
+        for swept in self.swept.get(&entity).into_iter().flatten() {
+            if !collisions.contains(swept) {
+                collisions.push(*swept);
+            }
+        }
+        collisions
+    }
+
     /// Notify cache of removal of collider to correctly handle stop event on removed collider.
     /// see `Self::removed_colliders_userdata` field comment for details.
     pub fn collider_removed(&mut self, entity: Entity, collider_handle: rapier::ColliderHandle) {
@@ -136,6 +180,7 @@ impl Clone for CollisionCache {
         Self {
             collisions: Arc::new(AtomicCell::new((*self.collisions.borrow()).clone())),
             removed_colliders: self.removed_colliders.clone(),
+            swept: self.swept.clone(),
         }
     }
 }
@@ -293,11 +338,15 @@ impl_system_param! {
         colliders: CompMut<'a, Collider>,
         /// Contains the rapier collider handles for each map tile.
         tile_rapier_handles: CompMut<'a, TileRapierHandle>,
+        /// The merged tile colliders of each tile layer.
+        tile_collider_regions: CompMut<'a, TileColliderRegions>,
 
         tile_layers: Comp<'a, TileLayer>,
         tile_collision_kinds: Comp<'a, TileCollisionKind>,
         tile_dynamic_colliders: Comp<'a, TileDynamicCollider>,
         spawned_map_layer_metas: Comp<'a, SpawnedMapLayerMeta>,
+        /// The physics materials of map tiles, solids, and actors.
+        physics_materials: Comp<'a, PhysicsMaterial>,
     }
 }
 
@@ -457,10 +506,36 @@ pub struct Solid {
     pub disabled: bool,
     pub pos: Vec2,
     pub size: Vec2,
+    /// Whether bodies can jump up through the solid and drop down through it, like a
+    /// [`TileCollisionKind::JumpThrough`] tile, instead of it being solid from every side.
+    pub jump_through: bool,
     #[schema(opaque)]
     pub rapier_handle: Option<rapier::RigidBodyHandle>,
 }
 
+impl Solid {
+    /// The kind of tile that the solid collides like.
+    pub fn collision_kind(&self) -> TileCollisionKind {
+        if self.jump_through {
+            TileCollisionKind::JumpThrough
+        } else {
+            TileCollisionKind::Solid
+        }
+    }
+}
+
+/// Get the collision kind of a solid or a map tile, or `None` if the entity is neither.
+fn collision_kind_of(
+    solids: &ComponentStore<Solid>,
+    tile_collision_kinds: &ComponentStore<TileCollisionKind>,
+    entity: Entity,
+) -> Option<TileCollisionKind> {
+    match solids.get(entity) {
+        Some(solid) => Some(solid.collision_kind()),
+        None => tile_collision_kinds.get(entity).copied(),
+    }
+}
+
 /// A collider body in the physics simulation.
 ///
 /// This is only used for actors in the simulation, not for tiles or solids.
@@ -492,6 +567,108 @@ pub struct Collider {
 #[derive(Default, Clone, Debug, HasSchema, Deref, DerefMut)]
 pub struct TileRapierHandle(pub rapier::RigidBodyHandle);
 
+/// Component added to tile layers, listing the rectangles of tiles in the layer that share a single
+/// rapier body.
+///
+/// Neighbouring tiles that have the same collision kind and physics material are merged this way
+/// by [`CollisionWorld::update_tiles_with_filter`] and [`CollisionWorld::update_tiles_at`], so that
+/// large maps don't need a rapier body for each of their tiles. Every tile of a region has a
+/// [`TileRapierHandle`] to the shared body.
+#[derive(Default, Clone, Debug, HasSchema, Deref, DerefMut)]
+pub struct TileColliderRegions(pub Vec<TileColliderRegion>);
+
+/// A rectangle of tiles in a [`TileColliderRegions`].
+#[derive(Clone, Copy, Debug)]
+pub struct TileColliderRegion {
+    /// The grid position of the bottom-left tile of the region.
+    pub min: UVec2,
+    /// The number of tiles that the region spans on each axis.
+    pub size: UVec2,
+    /// The rapier body with the region's collider.
+    pub body: rapier::RigidBodyHandle,
+}
+
+impl TileColliderRegion {
+    /// Iterate over the grid positions of the tiles in the region.
+    pub fn tiles(&self) -> impl Iterator<Item = UVec2> {
+        let Self { min, size, .. } = *self;
+        (min.y..min.y + size.y).flat_map(move |y| (min.x..min.x + size.x).map(move |x| uvec2(x, y)))
+    }
+}
+
+/// The smallest rectangle of map grid positions containing a set of tiles.
+#[derive(Clone, Copy)]
+struct TileRect {
+    min: UVec2,
+    max: UVec2,
+}
+
+impl TileRect {
+    /// Get the rectangle around the given tiles, which must not be empty.
+    fn around(tiles: &[UVec2]) -> Self {
+        let min = tiles.iter().copied().fold(UVec2::MAX, UVec2::min);
+        let max = tiles.iter().copied().fold(UVec2::ZERO, UVec2::max);
+        Self { min, max }
+    }
+
+    fn size(&self) -> UVec2 {
+        self.max - self.min + UVec2::ONE
+    }
+
+    fn len(&self) -> usize {
+        let size = self.size();
+        (size.x * size.y) as usize
+    }
+
+    fn contains(&self, pos: UVec2) -> bool {
+        pos.cmpge(self.min).all() && pos.cmple(self.max).all()
+    }
+
+    /// Get the index of a tile in a grid covering the rectangle.
+    fn idx(&self, pos: UVec2) -> usize {
+        ((pos.y - self.min.y) * self.size().x + pos.x - self.min.x) as usize
+    }
+}
+
+/// Merge the tiles of a grid into rectangles, where each cell of the grid has the key that the tile
+/// is merged by, or [`None`] if it shouldn't be merged.
+///
+/// Rectangles are grown to the right first, and then upwards if `vertical` returns `true` for
+/// their key. Returns the grid position and size of each rectangle, along with its key.
+fn merge_tile_rects<K: PartialEq + Copy>(
+    grid_size: UVec2,
+    mut cells: Vec<Option<K>>,
+    vertical: impl Fn(&K) -> bool,
+) -> Vec<(UVec2, UVec2, K)> {
+    let idx = |x: u32, y: u32| (y * grid_size.x + x) as usize;
+    let mut rects = Vec::new();
+    for y in 0..grid_size.y {
+        for x in 0..grid_size.x {
+            let Some(key) = cells[idx(x, y)] else {
+                continue;
+            };
+            let mut size = uvec2(1, 1);
+            while x + size.x < grid_size.x && cells[idx(x + size.x, y)] == Some(key) {
+                size.x += 1;
+            }
+            if vertical(&key) {
+                while y + size.y < grid_size.y
+                    && (x..x + size.x).all(|x| cells[idx(x, y + size.y)] == Some(key))
+                {
+                    size.y += 1;
+                }
+            }
+            for cell_y in y..y + size.y {
+                for cell_x in x..x + size.x {
+                    cells[idx(cell_x, cell_y)] = None;
+                }
+            }
+            rects.push((uvec2(x, y), size, key));
+        }
+    }
+    rects
+}
+
 /// Component added to tiles that have an additional collider used for interaction with
 /// dynamic bodies that simulate physics.
 ///
@@ -508,6 +685,27 @@ pub struct TileDynamicCollider {
     pub offset: Vec2,
 }
 
+/// Helper function for configuring ColliderBuilder for map tiles.
+fn build_tile_rapier_collider(
+    entity: Entity,
+    shared_shape: rapier::SharedShape,
+    simulation_membership: SolverGroup,
+    material: PhysicsMaterial,
+) -> rapier::ColliderBuilder {
+    let simulation_filter = SolverGroup::ALL;
+
+    rapier::ColliderBuilder::new(shared_shape)
+        .active_events(rapier::ActiveEvents::COLLISION_EVENTS)
+        .active_collision_types(rapier::ActiveCollisionTypes::all())
+        .solver_groups(InteractionGroups::new(
+            simulation_membership.bits().into(),
+            simulation_filter.bits().into(),
+        ))
+        .friction(material.rapier_friction())
+        .restitution(material.bounciness)
+        .user_data(RapierUserData::from(entity))
+}
+
 /// Namespace struct for converting rapier collider user data to/from [`Entity`].
 pub struct RapierUserData;
 impl RapierUserData {
@@ -531,7 +729,11 @@ impl RapierUserData {
 }
 
 /// The kind of collision that a map tile has.
-#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, HasSchema, Serialize, Deserialize)]
+///
+/// Slopes are solid below their surface and empty above it. The 45° slopes rise a whole tile over
+/// the width of the tile, and the 22.5° slopes rise half a tile, so that two of them, a low one
+/// followed by a high one, make up a ramp one tile high.
+#[derive(Default, PartialEq, Eq, Hash, Clone, Copy, Debug, HasSchema, Serialize, Deserialize)]
 #[repr(u8)]
 #[derive_type_data(SchemaDeserialize)]
 pub enum TileCollisionKind {
@@ -539,6 +741,18 @@ pub enum TileCollisionKind {
     Empty,
     Solid,
     JumpThrough,
+    /// A 45° slope rising towards the right.
+    Slope45UpRight,
+    /// A 45° slope rising towards the left.
+    Slope45UpLeft,
+    /// The lower half of a 22.5° slope rising towards the right.
+    Slope22UpRightLow,
+    /// The upper half of a 22.5° slope rising towards the right.
+    Slope22UpRightHigh,
+    /// The upper half of a 22.5° slope rising towards the left.
+    Slope22UpLeftHigh,
+    /// The lower half of a 22.5° slope rising towards the left.
+    Slope22UpLeftLow,
 }
 
 impl TileCollisionKind {
@@ -548,8 +762,65 @@ impl TileCollisionKind {
     pub fn simulation_group_membership(&self) -> SolverGroup {
         match self {
             TileCollisionKind::Empty => SolverGroup::NONE,
-            TileCollisionKind::Solid => SolverGroup::SOLID_WORLD,
             TileCollisionKind::JumpThrough => SolverGroup::JUMP_THROUGH,
+            _ => SolverGroup::SOLID_WORLD,
+        }
+    }
+
+    /// Whether the tile blocks movement from every side, which is the case for solid tiles and
+    /// slopes.
+    pub fn is_solid(&self) -> bool {
+        *self == TileCollisionKind::Solid || self.is_slope()
+    }
+
+    /// Whether the tile is one of the slopes.
+    pub fn is_slope(&self) -> bool {
+        self.slope_heights().is_some()
+    }
+
+    /// Get the height of the surface of a slope at the left and the right edge of the tile, as a
+    /// fraction of the tile height, or `None` if the tile is not a slope.
+    pub fn slope_heights(&self) -> Option<(f32, f32)> {
+        match self {
+            TileCollisionKind::Slope45UpRight => Some((0.0, 1.0)),
+            TileCollisionKind::Slope45UpLeft => Some((1.0, 0.0)),
+            TileCollisionKind::Slope22UpRightLow => Some((0.0, 0.5)),
+            TileCollisionKind::Slope22UpRightHigh => Some((0.5, 1.0)),
+            TileCollisionKind::Slope22UpLeftHigh => Some((1.0, 0.5)),
+            TileCollisionKind::Slope22UpLeftLow => Some((0.5, 0.0)),
+            TileCollisionKind::Empty
+            | TileCollisionKind::Solid
+            | TileCollisionKind::JumpThrough => None,
+        }
+    }
+
+    /// Get how much the surface of a slope rises for every unit moved to the right, assuming square
+    /// tiles. This is negative for slopes rising towards the left, and zero for other tiles.
+    pub fn slope_gradient(&self) -> f32 {
+        self.slope_heights()
+            .map(|(left, right)| right - left)
+            .unwrap_or_default()
+    }
+
+    /// Get the rapier shape for a tile with this collision kind, centered on the tile.
+    pub fn shared_shape(&self, tile_size: Vec2) -> rapier::SharedShape {
+        let half = tile_size / 2.0;
+        match self.slope_heights() {
+            Some((left, right)) => {
+                let mut points = vec![
+                    rapier::Point::new(-half.x, -half.y),
+                    rapier::Point::new(half.x, -half.y),
+                ];
+                if right > 0.0 {
+                    points.push(rapier::Point::new(half.x, right * tile_size.y - half.y));
+                }
+                if left > 0.0 {
+                    points.push(rapier::Point::new(-half.x, left * tile_size.y - half.y));
+                }
+                rapier::SharedShape::convex_hull(&points)
+                    .expect("Slope tile shape should be a valid convex polygon")
+            }
+            None => rapier::SharedShape::cuboid(half.x, half.y),
         }
     }
 }
@@ -601,6 +872,9 @@ impl<'a> CollisionWorld<'a> {
             ..
         } = &mut *self.ctx;
 
+        // Swept collisions only last until the next update
+        collision_cache.swept.clear();
+
         // Delete any bodies that don't have alive entities
         let mut to_delete = Vec::new();
         for (handle, body) in rigid_body_set.iter() {
@@ -808,6 +1082,10 @@ impl<'a> CollisionWorld<'a> {
                 );
             }
             rapier_collider.set_enabled(!collider.disabled);
+            if let Some(material) = self.physics_materials.get(ent) {
+                rapier_collider.set_friction(material.rapier_friction());
+                rapier_collider.set_restitution(material.bounciness);
+            }
         }
 
         for (solid_ent, solid) in self.entities.iter_with(&mut self.solids) {
@@ -852,6 +1130,26 @@ impl<'a> CollisionWorld<'a> {
             rapier_collider.set_enabled(!solid.disabled);
             rapier_collider.set_position_wrt_parent(rapier::Isometry::new(default(), 0.0));
             rapier_collider.set_shape(shared_shape.clone());
+
+            // Jump-through solids only hold up dynamic bodies that are falling onto them, like
+            // jump-through tiles.
+            let simulation_membership = if solid.jump_through {
+                SolverGroup::JUMP_THROUGH
+            } else {
+                SolverGroup::SOLID_WORLD
+            };
+            rapier_collider.set_solver_groups(InteractionGroups::new(
+                simulation_membership.bits().into(),
+                SolverGroup::ALL.bits().into(),
+            ));
+
+            let material = self
+                .physics_materials
+                .get(solid_ent)
+                .copied()
+                .unwrap_or_default();
+            rapier_collider.set_friction(material.rapier_friction());
+            rapier_collider.set_restitution(material.bounciness);
         }
     }
 
@@ -890,121 +1188,268 @@ impl<'a> CollisionWorld<'a> {
 
     /// Update the collision for the tile with the given layer index and map grid position.
     pub fn update_tile(&mut self, layer_idx: u32, pos: UVec2) {
-        self.update_tiles_with_filter(|idx, p| layer_idx == idx && pos == p);
+        self.update_tiles_at(layer_idx, &[pos]);
+    }
+
+    /// Update the collisions for the tiles with the given layer index and map grid positions.
+    ///
+    /// Only the part of the layer around the given tiles is looked at, so this is much cheaper than
+    /// [`update_tiles_with_filter`][Self::update_tiles_with_filter] when a few tiles change, and
+    /// updating many tiles at once is cheaper than updating them one at a time.
+    pub fn update_tiles_at(&mut self, layer_idx: u32, positions: &[UVec2]) {
+        let Some(layer_ent) = self
+            .entities
+            .iter_with((&self.tile_layers, &self.spawned_map_layer_metas))
+            .find(|(_, (_, meta))| meta.layer_idx == layer_idx)
+            .map(|(layer_ent, _)| layer_ent)
+        else {
+            return;
+        };
+        self.rebuild_tile_colliders(layer_ent, positions);
     }
 
     /// Update the collisions for map tiles that pass the given filter.
     ///
     /// The filter is a function that takes the layer index and the tile position as an argument.
+    /// It is called for every tile of every layer, so prefer [`update_tiles_at`][Self::update_tiles_at]
+    /// when the positions of the tiles to update are known.
+    ///
+    /// Neighbouring solid tiles with the same physics material are given a single collider for the
+    /// rectangle that they make up, and so are rows of jump-through tiles. When one of the tiles in
+    /// such a [`TileColliderRegion`] passes the filter, the whole region is rebuilt. Collisions with
+    /// a merged collider are reported for the bottom-left tile of its region.
     pub fn update_tiles_with_filter<F>(&mut self, mut filter: F)
     where
         F: FnMut(u32, UVec2) -> bool,
     {
-        let RapierContext {
-            rigid_body_set,
-            collider_set,
-            collider_shape_cache,
-            ..
-        } = &mut *self.ctx;
-        for (_, (layer, meta)) in self
+        let layers = self
             .entities
             .iter_with((&self.tile_layers, &self.spawned_map_layer_metas))
-        {
-            let tile_shared_shape = collider_shape_cache
+            .map(|(layer_ent, (layer, meta))| {
+                let grid_size = layer.grid_size;
+                let positions = (0..grid_size.y)
+                    .flat_map(|y| (0..grid_size.x).map(move |x| uvec2(x, y)))
+                    .filter(|pos| filter(meta.layer_idx, *pos))
+                    .collect::<Vec<_>>();
+                (layer_ent, positions)
+            })
+            .collect::<Vec<_>>();
+        for (layer_ent, positions) in layers {
+            self.rebuild_tile_colliders(layer_ent, &positions);
+        }
+    }
+
+    /// Rebuild the colliders of the given tiles of a tile layer, and of the whole
+    /// [`TileColliderRegion`]s that they are a part of.
+    ///
+    /// Only the rectangle of the layer that contains those tiles is looked at.
+    fn rebuild_tile_colliders(&mut self, layer_ent: Entity, positions: &[UVec2]) {
+        let Some(layer) = self.tile_layers.get(layer_ent) else {
+            return;
+        };
+        let grid_size = layer.grid_size;
+        let mut positions = positions
+            .iter()
+            .copied()
+            .filter(|pos| pos.cmplt(grid_size).all())
+            .collect::<Vec<_>>();
+        if positions.is_empty() {
+            return;
+        }
+        let ctx = &mut *self.ctx;
+
+        // Remove the merged colliders of any regions with tiles that need updating, and update all
+        // of the tiles in them.
+        if !self.tile_collider_regions.contains(layer_ent) {
+            self.tile_collider_regions.insert(layer_ent, default());
+        }
+        let regions = self.tile_collider_regions.get_mut(layer_ent).unwrap();
+        let changed = TileRect::around(&positions);
+        let mut changed_tiles = vec![false; changed.len()];
+        for pos in &positions {
+            changed_tiles[changed.idx(*pos)] = true;
+        }
+        regions.retain(|region| {
+            if !region
+                .tiles()
+                .any(|pos| changed.contains(pos) && changed_tiles[changed.idx(pos)])
+            {
+                return true;
+            }
+            for pos in region.tiles() {
+                positions.push(pos);
+                if let Some(tile_ent) = layer.get(pos) {
+                    self.tile_rapier_handles.remove(tile_ent);
+                }
+            }
+            ctx.remove_tile_body(region.body);
+            false
+        });
+
+        // Only the rectangle containing the tiles to update is rebuilt.
+        let rect = TileRect::around(&positions);
+        let mut dirty = vec![false; rect.len()];
+        for pos in &positions {
+            dirty[rect.idx(*pos)] = true;
+        }
+
+        let tile_shared_shape = ctx
+            .collider_shape_cache
+            .shared_shape(ColliderShape::Rectangle {
+                size: layer.tile_size,
+            })
+            .clone();
+        // Slopes need their own shapes, which are shared by all the slopes of the same kind.
+        let mut slope_shared_shapes: HashMap<TileCollisionKind, rapier::SharedShape> = default();
+        // The solid and jump-through tiles to merge, along with their physics materials.
+        let mut mergeable = vec![None; dirty.len()];
+        for y in rect.min.y..=rect.max.y {
+            for x in rect.min.x..=rect.max.x {
+                let pos = uvec2(x, y);
+                if !dirty[rect.idx(pos)] {
+                    continue;
+                }
+                let Some(tile_ent) = layer.get(pos) else {
+                    continue;
+                };
+
+                // Remove the old collider of the tile
+                if let Some(handle) = self.tile_rapier_handles.remove(tile_ent) {
+                    ctx.remove_tile_body(*handle);
+                }
+
+                // Get dynamic collider if we have one
+                let dynamic_collider = self.tile_dynamic_colliders.get(tile_ent);
+
+                let collision_kind = self
+                    .tile_collision_kinds
+                    .get(tile_ent)
+                    .copied()
+                    .unwrap_or_default();
+                let material = self.physics_materials.get(tile_ent).copied();
+                if dynamic_collider.is_none()
+                    && matches!(
+                        collision_kind,
+                        TileCollisionKind::Solid | TileCollisionKind::JumpThrough
+                    )
+                {
+                    mergeable[rect.idx(pos)] = Some((collision_kind, material));
+                    continue;
+                }
+
+                let shared_shape = if collision_kind.is_slope() {
+                    slope_shared_shapes
+                        .entry(collision_kind)
+                        .or_insert_with(|| collision_kind.shared_shape(layer.tile_size))
+                        .clone()
+                } else {
+                    tile_shared_shape.clone()
+                };
+                let collider_pos = (pos.as_vec2() + 0.5) * layer.tile_size;
+                let body_handle = ctx.rigid_body_set.insert(
+                    rapier::RigidBodyBuilder::fixed()
+                        .translation(rapier::Vector::new(collider_pos.x, collider_pos.y))
+                        .user_data(RapierUserData::from(tile_ent)),
+                );
+
+                // Set SolverGroup based on collision kind so dynamic bodies
+                // know if they should generate contact forces with tile or not.
+                let simulation_membership = collision_kind.simulation_group_membership();
+
+                // Sim group for default tile collider. This is not used for collision
+                // (only used for events) if an additional "dynamic" collider is present
+                // to be used for collision response.
+                let mut default_collider_sim_membership = simulation_membership;
+                if dynamic_collider.is_some() {
+                    default_collider_sim_membership = SolverGroup::NONE;
+                }
+
+                // Give dynamic bodies that hit the tile the friction and bounciness of its
+                // material.
+                let material = material.unwrap_or_default();
+
+                // Insert default collider
+                ctx.collider_set.insert_with_parent(
+                    build_tile_rapier_collider(
+                        tile_ent,
+                        shared_shape,
+                        default_collider_sim_membership,
+                        material,
+                    ),
+                    body_handle,
+                    &mut ctx.rigid_body_set,
+                );
+
+                // Insert dynamic collider if we have one
+                if let Some(dynamic_collider) = dynamic_collider {
+                    let shared_shape = ctx
+                        .collider_shape_cache
+                        .shared_shape(dynamic_collider.shape);
+                    ctx.collider_set.insert_with_parent(
+                        build_tile_rapier_collider(
+                            tile_ent,
+                            shared_shape.clone(),
+                            simulation_membership,
+                            material,
+                        )
+                        // Don't generate events for this collider
+                        .active_events(rapier::ActiveEvents::empty())
+                        // Only needs to collide with dynamics
+                        .active_collision_types(rapier::ActiveCollisionTypes::DYNAMIC_FIXED)
+                        .position(dynamic_collider.offset.into()),
+                        body_handle,
+                        &mut ctx.rigid_body_set,
+                    );
+                }
+                self.tile_rapier_handles
+                    .insert(tile_ent, TileRapierHandle(body_handle));
+            }
+        }
+
+        // Give each rectangle of mergeable tiles one collider. Jump-through tiles are only merged
+        // along rows, so that every one of them keeps a top to land on.
+        let rects = merge_tile_rects(rect.size(), mergeable, |(kind, _)| {
+            *kind == TileCollisionKind::Solid
+        });
+        for (rect_min, rect_size, (collision_kind, material)) in rects {
+            let rect_min = rect.min + rect_min;
+            let tile_ent = layer.get(rect_min).unwrap();
+            let rect_world_size = rect_size.as_vec2() * layer.tile_size;
+            let collider_pos = rect_min.as_vec2() * layer.tile_size + rect_world_size / 2.0;
+            let body_handle = ctx.rigid_body_set.insert(
+                rapier::RigidBodyBuilder::fixed()
+                    .translation(rapier::Vector::new(collider_pos.x, collider_pos.y))
+                    .user_data(RapierUserData::from(tile_ent)),
+            );
+            let shared_shape = ctx
+                .collider_shape_cache
                 .shared_shape(ColliderShape::Rectangle {
-                    size: layer.tile_size,
+                    size: rect_world_size,
                 })
                 .clone();
-            for x in 0..layer.grid_size.x {
-                for y in 0..layer.grid_size.y {
-                    let pos = uvec2(x, y);
-                    if !filter(meta.layer_idx, pos) {
-                        continue;
-                    };
+            ctx.collider_set.insert_with_parent(
+                build_tile_rapier_collider(
+                    tile_ent,
+                    shared_shape,
+                    collision_kind.simulation_group_membership(),
+                    material.unwrap_or_default(),
+                ),
+                body_handle,
+                &mut ctx.rigid_body_set,
+            );
 
-                    let Some(tile_ent) = layer.get(pos) else {
-                        continue;
-                    };
-                    let collider_x = x as f32 * layer.tile_size.x + layer.tile_size.x / 2.0;
-                    let collider_y = y as f32 * layer.tile_size.y + layer.tile_size.y / 2.0;
-
-                    // Get dynamic collider if we have one
-                    let dynamic_collider = self.tile_dynamic_colliders.get(tile_ent);
-
-                    // Get or create a collider for the tile
-                    let handle = self
-                        .tile_rapier_handles
-                        .get(tile_ent)
-                        .map(|x| **x)
-                        .unwrap_or_else(|| {
-                            let body_handle = rigid_body_set.insert(
-                                rapier::RigidBodyBuilder::fixed()
-                                    .user_data(RapierUserData::from(tile_ent)),
-                            );
-
-                            // Set SolverGroup based on collision kind so dynamic bodies
-                            // know if they should generate contact forces with tile or not.
-                            let mut simulation_membership = SolverGroup::NONE;
-                            if let Some(collision_kind) = self.tile_collision_kinds.get(tile_ent) {
-                                simulation_membership =
-                                    collision_kind.simulation_group_membership();
-                            }
-                            let simulation_filter = SolverGroup::ALL;
-
-                            // Sim group for default tile collider. This is not used for collision
-                            // (only used for events) if an additional "dynamic" collider is present
-                            // to be used for collision response.
-                            let mut default_collider_sim_membership = simulation_membership;
-                            if dynamic_collider.is_some() {
-                                default_collider_sim_membership = SolverGroup::NONE;
-                            }
-
-                            // Insert default collider
-                            collider_set.insert_with_parent(
-                                rapier::ColliderBuilder::new(tile_shared_shape.clone())
-                                    .active_events(rapier::ActiveEvents::COLLISION_EVENTS)
-                                    .active_collision_types(rapier::ActiveCollisionTypes::all())
-                                    .solver_groups(InteractionGroups::new(
-                                        default_collider_sim_membership.bits().into(),
-                                        simulation_filter.bits().into(),
-                                    ))
-                                    .user_data(RapierUserData::from(tile_ent)),
-                                body_handle,
-                                rigid_body_set,
-                            );
-
-                            // Insert dynamic collider if we have one
-                            if let Some(dynamic_collider) = dynamic_collider {
-                                let shared_shape =
-                                    collider_shape_cache.shared_shape(dynamic_collider.shape);
-                                collider_set.insert_with_parent(
-                                    rapier::ColliderBuilder::new(shared_shape.clone())
-                                        // Don't generate events for this collider
-                                        .active_events(rapier::ActiveEvents::empty())
-                                        // Only needs to collide with dynamics
-                                        .active_collision_types(
-                                            rapier::ActiveCollisionTypes::DYNAMIC_FIXED,
-                                        )
-                                        .solver_groups(InteractionGroups::new(
-                                            simulation_membership.bits().into(),
-                                            simulation_filter.bits().into(),
-                                        ))
-                                        .position(dynamic_collider.offset.into())
-                                        .user_data(RapierUserData::from(tile_ent)),
-                                    body_handle,
-                                    rigid_body_set,
-                                );
-                            }
-                            self.tile_rapier_handles
-                                .insert(tile_ent, TileRapierHandle(body_handle));
-                            body_handle
-                        });
-                    let tile_body = rigid_body_set.get_mut(handle).unwrap();
-
-                    // Update the collider position
-                    tile_body.set_translation(rapier::Vector::new(collider_x, collider_y), false);
+            let region = TileColliderRegion {
+                min: rect_min,
+                size: rect_size,
+                body: body_handle,
+            };
+            for pos in region.tiles() {
+                if let Some(tile_ent) = layer.get(pos) {
+                    self.tile_rapier_handles
+                        .insert(tile_ent, TileRapierHandle(body_handle));
                 }
             }
+            regions.push(region);
         }
     }
 
@@ -1020,7 +1465,7 @@ impl<'a> CollisionWorld<'a> {
             .collision_cache
             .get(entity)
             .iter()
-            .any(|x| self.tile_collision_kinds.get(*x) == Some(&TileCollisionKind::JumpThrough))
+            .any(|x| self.collision_kind(*x) == Some(TileCollisionKind::JumpThrough))
         {
             let collider = self.colliders.get_mut(entity).unwrap();
             collider.descent = true;
@@ -1028,7 +1473,38 @@ impl<'a> CollisionWorld<'a> {
         }
     }
 
+    /// Move a solid, updating its collider right away so that bodies moved after it collide with
+    /// it in its new position, instead of where it was in the last physics update.
+    pub fn move_solid(&mut self, entity: Entity, pos: Vec2) {
+        let Some(solid) = self.solids.get_mut(entity) else {
+            return;
+        };
+        solid.pos = pos;
+        let Some(handle) = solid.rapier_handle else {
+            return;
+        };
+
+        let RapierContext {
+            query_pipeline,
+            collider_set,
+            rigid_body_set,
+            ..
+        } = &mut *self.ctx;
+        let Some(body) = rigid_body_set.get_mut(handle) else {
+            return;
+        };
+        body.set_translation(rapier::Vector::new(pos.x, pos.y), false);
+        for collider_handle in body.colliders() {
+            if let Some(collider) = collider_set.get_mut(*collider_handle) {
+                collider.set_position(rapier::Isometry::new(pos.to_array().into(), 0.0));
+            }
+        }
+        query_pipeline.update(rigid_body_set, collider_set);
+    }
+
     /// Returns the collisions that one actor has with any other actors.
+    ///
+    /// This includes the actors that it was swept through by [`sweep_actor`][Self::sweep_actor].
     pub fn actor_collisions(&self, entity: Entity) -> Vec<Entity> {
         if !self.actors.contains(entity) {
             return default();
@@ -1039,10 +1515,9 @@ impl<'a> CollisionWorld<'a> {
 
         self.ctx
             .collision_cache
-            .get(entity)
-            .iter()
-            .filter(|x| self.actors.contains(**x))
-            .copied()
+            .get_with_swept(entity)
+            .into_iter()
+            .filter(|x| self.actors.contains(*x))
             .collect()
     }
 
@@ -1061,10 +1536,9 @@ impl<'a> CollisionWorld<'a> {
 
         self.ctx
             .collision_cache
-            .get(entity)
-            .iter()
-            .filter(|x| self.actors.contains(**x) && filter(**x))
-            .copied()
+            .get_with_swept(entity)
+            .into_iter()
+            .filter(|x| self.actors.contains(*x) && filter(*x))
             .collect()
     }
 
@@ -1123,18 +1597,15 @@ impl<'a> CollisionWorld<'a> {
                 rapier::QueryFilter::new().predicate(&|_handle, rapier_collider| {
                     let ent = RapierUserData::entity(rapier_collider.user_data);
 
-                    if self.solids.contains(ent) {
-                        // Include all solid collisions
-                        return true;
-                    }
-
-                    let Some(tile_kind) = self.tile_collision_kinds.get(ent) else {
-                        // Ignore non-tile collisions
+                    let Some(tile_kind) =
+                        collision_kind_of(&self.solids, &self.tile_collision_kinds, ent)
+                    else {
+                        // Ignore collisions with anything other than tiles and solids
                         return false;
                     };
 
                     // Ignore jump-through tiles if we have already seen wood
-                    !(collider.seen_wood && *tile_kind == TileCollisionKind::JumpThrough)
+                    !(collider.seen_wood && tile_kind == TileCollisionKind::JumpThrough)
                 }),
             );
 
@@ -1149,11 +1620,8 @@ impl<'a> CollisionWorld<'a> {
                 // Subtract from the remaining attempted movement
                 dy -= diff;
 
-                if self.solids.contains(ent) {
-                    break true;
-                }
-
-                let tile_kind = *self.tile_collision_kinds.get(ent).unwrap();
+                let tile_kind =
+                    collision_kind_of(&self.solids, &self.tile_collision_kinds, ent).unwrap();
 
                 // collider wants to go down and collided with jumpthrough tile
                 if tile_kind == TileCollisionKind::JumpThrough && collider.descent {
@@ -1200,7 +1668,8 @@ impl<'a> CollisionWorld<'a> {
                     &**shape,
                     rapier::QueryFilter::new().predicate(&|_handle, collider| {
                         let ent = RapierUserData::entity(collider.user_data);
-                        self.tile_collision_kinds.get(ent) == Some(&TileCollisionKind::JumpThrough)
+                        collision_kind_of(&self.solids, &self.tile_collision_kinds, ent)
+                            == Some(TileCollisionKind::JumpThrough)
                     }),
                 )
                 .is_some();
@@ -1247,6 +1716,9 @@ impl<'a> CollisionWorld<'a> {
         let shape = collider_shape_cache.shared_shape(collider.shape);
 
         let mut movement = 0.0;
+        // How far the body has been lifted up while walking up slopes.
+        let mut rise = 0.0;
+        let mut slope_steps = 0;
         let collided = 'collision: loop {
             // Do a shape cast in the direction of movement
             let velocity = rapier::Vector::new(dx, 0.0);
@@ -1263,18 +1735,15 @@ impl<'a> CollisionWorld<'a> {
                     rapier::QueryFilter::new().predicate(&|_handle, rapier_collider| {
                         let ent = RapierUserData::entity(rapier_collider.user_data);
 
-                        if self.solids.contains(ent) {
-                            // Include all solid collisions
-                            return true;
-                        }
-
-                        let Some(tile_kind) = self.tile_collision_kinds.get(ent) else {
-                            // Ignore non-tile collisions
+                        let Some(tile_kind) =
+                            collision_kind_of(&self.solids, &self.tile_collision_kinds, ent)
+                        else {
+                            // Ignore collisions with anything other than tiles and solids
                             return false;
                         };
 
                         // Ignore jump-through tiles if we have already seen wood.
-                        !(collider.seen_wood && *tile_kind == TileCollisionKind::JumpThrough)
+                        !(collider.seen_wood && tile_kind == TileCollisionKind::JumpThrough)
                     }),
                 )
             };
@@ -1290,17 +1759,44 @@ impl<'a> CollisionWorld<'a> {
                 // Subtract from the remaining attempted movement
                 dx -= diff;
 
-                if self.solids.contains(ent) {
-                    break true;
-                }
-
-                let tile_kind = *self.tile_collision_kinds.get(ent).unwrap();
+                let tile_kind =
+                    collision_kind_of(&self.solids, &self.tile_collision_kinds, ent).unwrap();
 
                 // If we ran into a jump-through tile, go through it and continue casting
                 if tile_kind == TileCollisionKind::JumpThrough {
                     collider.seen_wood = true;
                     collider.descent = true;
 
+                // If we ran into a slope that rises in the direction we are moving, step up by as
+                // much as the slope rises over the rest of the movement, and continue casting.
+                } else if tile_kind.slope_gradient() * dx > 0.0 && slope_steps < 4 {
+                    slope_steps += 1;
+
+                    let step = (dx * tile_kind.slope_gradient()).abs() + 0.1;
+                    let blocked = query_pipeline
+                        .cast_shape(
+                            rigid_body_set,
+                            collider_set,
+                            &position,
+                            &rapier::Vector::new(0.0, step),
+                            &**shape,
+                            1.0,
+                            true,
+                            rapier::QueryFilter::new().predicate(&|_handle, rapier_collider| {
+                                let ent = RapierUserData::entity(rapier_collider.user_data);
+                                collision_kind_of(&self.solids, &self.tile_collision_kinds, ent)
+                                    .map(|kind| kind.is_solid())
+                                    .unwrap_or(false)
+                            }),
+                        )
+                        .is_some();
+                    if blocked {
+                        break 'collision true;
+                    }
+
+                    position.translation.y += step;
+                    rise += step;
+
                 // If we ran into any other kind of tile
                 } else {
                     // Indicate we ran into something and stop processing
@@ -1318,6 +1814,7 @@ impl<'a> CollisionWorld<'a> {
         // Move the entity
         let transform = transforms.get_mut(entity).unwrap();
         transform.translation.x += movement - if collided { 0.1 * dx.signum() } else { 0.0 };
+        transform.translation.y += rise;
 
         // Final check, if we are out of woods after the move - reset wood flags
         {
@@ -1334,7 +1831,8 @@ impl<'a> CollisionWorld<'a> {
                     &**shape,
                     rapier::QueryFilter::new().predicate(&|_handle, collider| {
                         let ent = RapierUserData::entity(collider.user_data);
-                        self.tile_collision_kinds.get(ent) == Some(&TileCollisionKind::JumpThrough)
+                        collision_kind_of(&self.solids, &self.tile_collision_kinds, ent)
+                            == Some(TileCollisionKind::JumpThrough)
                     }),
                 )
                 .is_some();
@@ -1348,6 +1846,72 @@ impl<'a> CollisionWorld<'a> {
         collided
     }
 
+    /// Move a body down onto the ground below it, if the ground is no further away than
+    /// `max_distance`, stopping just short of it like [`move_vertical`][Self::move_vertical]
+    /// does. Returns `true` if the body was moved.
+    ///
+    /// This is used to keep bodies on the ground while they walk down slopes.
+    pub fn snap_to_ground(
+        &mut self,
+        transforms: &mut CompMut<Transform>,
+        entity: Entity,
+        max_distance: f32,
+    ) -> bool {
+        puffin::profile_function!();
+
+        let RapierContext {
+            query_pipeline,
+            collider_set,
+            rigid_body_set,
+            collider_shape_cache,
+            ..
+        } = &mut *self.ctx;
+        assert!(self.actors.contains(entity));
+
+        let collider = self.colliders.get(entity).unwrap();
+        let transform = transforms.get_mut(entity).unwrap();
+        let position = (
+            transform.translation.truncate(),
+            transform.rotation.to_euler(EulerRot::XYZ).2,
+        )
+            .into();
+        let shape = collider_shape_cache.shared_shape(collider.shape);
+
+        let collision = query_pipeline.cast_shape(
+            rigid_body_set,
+            collider_set,
+            &position,
+            &rapier::Vector::new(0.0, -max_distance),
+            &**shape,
+            1.0,
+            true,
+            rapier::QueryFilter::new().predicate(&|_handle, rapier_collider| {
+                let ent = RapierUserData::entity(rapier_collider.user_data);
+
+                let Some(tile_kind) =
+                    collision_kind_of(&self.solids, &self.tile_collision_kinds, ent)
+                else {
+                    return false;
+                };
+
+                // Don't snap onto jump-through tiles that we are falling through
+                !(tile_kind == TileCollisionKind::JumpThrough
+                    && (collider.seen_wood || collider.descent))
+            }),
+        );
+
+        let Some((_, toi)) = collision else {
+            return false;
+        };
+        let distance = max_distance * toi.toi - 0.1;
+        if distance > 0.0 {
+            transform.translation.y -= distance;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Returns whether or not there is a tile or solid at the given position.
     ///
     /// > ⚠️ **Warning:** There is a slight difference to how `tile_collision_point` and
@@ -1357,16 +1921,16 @@ impl<'a> CollisionWorld<'a> {
     /// > perfectly lined up along the edge of a tile, but `tile_collision_point` won't.
     #[allow(unused)]
     pub fn solid_at(&self, pos: Vec2) -> bool {
-        self.solid_collision_point(pos)
-            || self.tile_collision_point(pos) == TileCollisionKind::Solid
+        self.solid_collision_point(pos) || self.tile_collision_point(pos).is_solid()
     }
 
     pub fn solid_collision_point(&self, pos: Vec2) -> bool {
-        for (_, (solid, collider)) in self.entities.iter_with((&self.solids, &self.colliders)) {
-            let bbox = collider
-                .shape
-                .bounding_box(Transform::from_translation(solid.pos.extend(0.0)));
-            if bbox.contains(pos) {
+        for (_, solid) in self.entities.iter_with(&self.solids) {
+            if solid.disabled || solid.jump_through {
+                continue;
+            }
+            let rect = Rect::new(solid.pos.x, solid.pos.y, solid.size.x, solid.size.y);
+            if rect.contains(pos) {
                 return true;
             }
         }
@@ -1376,6 +1940,8 @@ impl<'a> CollisionWorld<'a> {
 
     /// Returns the tile collision at the given point.
     ///
+    /// Points above the surface of a slope are reported as [`TileCollisionKind::Empty`].
+    ///
     /// > ⚠️ **Warning:** There is a slight difference to how `tile_collision_point` and
     /// > [`tile_collision`][Self::tile_collision] reports collisions.
     /// >
@@ -1390,11 +1956,20 @@ impl<'a> CollisionWorld<'a> {
             let y = (pos.y / tile_size.x).floor() as u32;
             let tile_entity = tile_layer.get(UVec2::new(x, y));
             if let Some(tile_entity) = tile_entity {
-                return self
+                let kind = self
                     .tile_collision_kinds
                     .get(tile_entity)
                     .copied()
                     .unwrap_or_default();
+
+                if let Some((left, right)) = kind.slope_heights() {
+                    let offset = pos / *tile_size - vec2(x as f32, y as f32);
+                    if offset.y > left + (right - left) * offset.x {
+                        return TileCollisionKind::Empty;
+                    }
+                }
+
+                return kind;
             }
         }
 
@@ -1431,13 +2006,54 @@ impl<'a> CollisionWorld<'a> {
                 }),
             )
             .map(|x| RapierUserData::entity(self.ctx.collider_set.get(x).unwrap().user_data))
-            .and_then(|ent| {
-                if self.solids.contains(ent) {
-                    return Some(TileCollisionKind::Solid);
+            .and_then(|ent| self.collision_kind(ent))
+            .unwrap_or_default()
+    }
+
+    /// Get the [`PhysicsMaterial`]s of the map tiles and [`Solid`]s that intersect the given shape,
+    /// combined with each other. This is the default material if none of them have one.
+    pub fn physics_material_at(
+        &self,
+        transform: Transform,
+        shape: ColliderShape,
+    ) -> PhysicsMaterial {
+        let mut touching = Vec::new();
+        self.ctx.query_pipeline.intersections_with_shape(
+            &self.ctx.rigid_body_set,
+            &self.ctx.collider_set,
+            &(
+                transform.translation.truncate(),
+                transform.rotation.to_euler(EulerRot::XYZ).2,
+            )
+                .into(),
+            &*shape.shared_shape(),
+            rapier::QueryFilter::new().predicate(&|_handle, collider| {
+                let ent = RapierUserData::entity(collider.user_data);
+                self.physics_materials.contains(ent)
+                    && (self.solids.contains(ent) || self.tile_collision_kinds.contains(ent))
+            }),
+            |handle| {
+                let ent =
+                    RapierUserData::entity(self.ctx.collider_set.get(handle).unwrap().user_data);
+                // Tiles may have more than one collider
+                if !touching.contains(&ent) {
+                    touching.push(ent);
                 }
-                self.tile_collision_kinds.get(ent).copied()
+                true
+            },
+        );
+
+        touching
+            .into_iter()
+            .filter_map(|ent| self.physics_materials.get(ent))
+            .fold(PhysicsMaterial::default(), |material, other| {
+                material.combine(*other)
             })
-            .unwrap_or_default()
+    }
+
+    /// Get the collision kind of a [`Solid`] or a map tile, or `None` if the entity is neither.
+    pub fn collision_kind(&self, entity: Entity) -> Option<TileCollisionKind> {
+        collision_kind_of(&self.solids, &self.tile_collision_kinds, entity)
     }
 
     /// Get the collider for the given entity.
@@ -1451,6 +2067,79 @@ impl<'a> CollisionWorld<'a> {
 mod test {
     use super::*;
 
+    /// The size of the tiles in the test worlds.
+    const TILE_SIZE: Vec2 = Vec2::splat(16.0);
+
+    /// Create a world with a single tile layer, with tiles of the given collision kinds at the given
+    /// grid positions, and update its collisions.
+    fn tile_world(grid_size: UVec2, tiles: &[(UVec2, TileCollisionKind)]) -> World {
+        let world = World::new();
+        let tiles = tiles.to_vec();
+        world.run_system(
+            move |mut entities: ResMutInit<Entities>,
+                  mut tile_layers: CompMut<TileLayer>,
+                  mut tile_collision_kinds: CompMut<TileCollisionKind>,
+                  mut spawned_map_layer_metas: CompMut<SpawnedMapLayerMeta>| {
+                let mut layer = TileLayer::new(grid_size, TILE_SIZE, default());
+                for (pos, kind) in &tiles {
+                    let tile_ent = entities.create();
+                    layer.set(*pos, Some(tile_ent));
+                    tile_collision_kinds.insert(tile_ent, *kind);
+                }
+                let layer_ent = entities.create();
+                tile_layers.insert(layer_ent, layer);
+                spawned_map_layer_metas.insert(layer_ent, SpawnedMapLayerMeta { layer_idx: 0 });
+            },
+            (),
+        );
+        world.run_system(
+            |mut collision_world: CollisionWorld| collision_world.update_tiles(),
+            (),
+        );
+        step(&world);
+        world
+    }
+
+    /// Update the collision world, like the physics update does every frame.
+    fn step(world: &World) {
+        world.run_system(
+            |mut collision_world: CollisionWorld,
+             mut transforms: CompMut<Transform>,
+             mut dynamic_bodies: CompMut<DynamicBody>| {
+                collision_world.update(
+                    1.0 / FPS,
+                    PhysicsParams {
+                        gravity: 0.0,
+                        terminal_velocity: None,
+                    },
+                    &mut transforms,
+                    &mut dynamic_bodies,
+                );
+            },
+            (),
+        );
+    }
+
+    /// Spawn an actor with the given shape, centered on `pos`.
+    fn spawn_actor(world: &World, shape: ColliderShape, pos: Vec2) -> Entity {
+        let entity = world.run_system(|mut entities: ResMutInit<Entities>| entities.create(), ());
+        world.run_system(
+            move |mut collision_world: CollisionWorld, mut transforms: CompMut<Transform>| {
+                transforms.insert(entity, Transform::from_translation(pos.extend(0.0)));
+                collision_world
+                    .colliders
+                    .insert(entity, Collider { shape, ..default() });
+                collision_world.actors.insert(entity, Actor);
+            },
+            (),
+        );
+        entity
+    }
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.2, "{a} is not close to {b}");
+    }
+
     #[test]
     fn convert_entity_to_from_user_data() {
         let e1 = Entity::new(102395950, 10394875);
@@ -1458,4 +2147,333 @@ mod test {
         let e2 = RapierUserData::entity(bits);
         assert_eq!(e1, e2);
     }
+
+    #[test]
+    fn merge_tile_rects_into_rectangles() {
+        // A 2x2 block of `1`s with a row of `2`s on top, which are only merged horizontally.
+        #[rustfmt::skip]
+        let cells = [
+            Some(1), Some(1), None,
+            Some(1), Some(1), Some(1),
+            Some(2), Some(2), Some(2),
+            Some(2), None,    Some(2),
+        ]
+        .to_vec();
+        let rects = merge_tile_rects(uvec2(3, 4), cells, |key| *key == 1);
+        assert_eq!(
+            rects,
+            [
+                (uvec2(0, 0), uvec2(2, 2), 1),
+                (uvec2(2, 1), uvec2(1, 1), 1),
+                (uvec2(0, 2), uvec2(3, 1), 2),
+                (uvec2(0, 3), uvec2(1, 1), 2),
+                (uvec2(2, 3), uvec2(1, 1), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn updating_tiles_only_rebuilds_their_regions() {
+        let solid = TileCollisionKind::Solid;
+        // A row of four solid tiles, with a tile on top of its right end that gets its own region.
+        let world = tile_world(
+            uvec2(4, 2),
+            &[
+                (uvec2(0, 0), solid),
+                (uvec2(1, 0), solid),
+                (uvec2(2, 0), solid),
+                (uvec2(3, 0), solid),
+                (uvec2(3, 1), solid),
+            ],
+        );
+        let regions = |world: &World| {
+            world.run_system(
+                |entities: Res<Entities>, collider_regions: Comp<TileColliderRegions>| {
+                    let (_, regions) = entities.iter_with(&collider_regions).next().unwrap();
+                    let mut regions = regions
+                        .iter()
+                        .map(|region| (region.min, region.size, region.body))
+                        .collect::<Vec<_>>();
+                    regions.sort_by_key(|(min, ..)| (min.y, min.x));
+                    regions
+                },
+                (),
+            )
+        };
+        let before = regions(&world);
+        assert_eq!(
+            before.iter().map(|r| (r.0, r.1)).collect::<Vec<_>>(),
+            [(uvec2(0, 0), uvec2(4, 1)), (uvec2(3, 1), uvec2(1, 1))]
+        );
+
+        // Remove the two middle tiles of the row.
+        world.run_system(
+            |mut entities: ResMutInit<Entities>, mut tile_layers: CompMut<TileLayer>| {
+                let layer_ent = entities.iter_with(&tile_layers).next().unwrap().0;
+                let layer = tile_layers.get_mut(layer_ent).unwrap();
+                for pos in [uvec2(1, 0), uvec2(2, 0)] {
+                    let tile_ent = layer.get(pos).unwrap();
+                    layer.set(pos, None);
+                    entities.kill(tile_ent);
+                }
+            },
+            (),
+        );
+        world.run_system(
+            |mut collision_world: CollisionWorld| {
+                collision_world.update_tiles_at(0, &[uvec2(1, 0), uvec2(2, 0)]);
+            },
+            (),
+        );
+        step(&world);
+
+        let after = regions(&world);
+        assert_eq!(
+            after.iter().map(|r| (r.0, r.1)).collect::<Vec<_>>(),
+            [
+                (uvec2(0, 0), uvec2(1, 1)),
+                (uvec2(3, 0), uvec2(1, 1)),
+                (uvec2(3, 1), uvec2(1, 1)),
+            ]
+        );
+        // The region that didn't have any of the tiles in it kept its body.
+        assert_eq!(after[2].2, before[1].2);
+        world.run_system(
+            |collision_world: CollisionWorld| {
+                let tile_center = |x: f32| (vec2(x, 0.0) + 0.5) * TILE_SIZE;
+                assert_eq!(
+                    collision_world.tile_collision_point(tile_center(0.0)),
+                    solid
+                );
+                for x in [1.0, 2.0] {
+                    assert_eq!(
+                        collision_world.tile_collision_point(tile_center(x)),
+                        TileCollisionKind::Empty
+                    );
+                }
+                assert_eq!(
+                    collision_world.tile_collision_point(tile_center(3.0)),
+                    solid
+                );
+            },
+            (),
+        );
+    }
+
+    #[test]
+    fn points_below_slope_surfaces_collide() {
+        for (kind, x, surface) in [
+            (TileCollisionKind::Slope45UpRight, 4.0, 4.0),
+            (TileCollisionKind::Slope45UpLeft, 4.0, 12.0),
+            (TileCollisionKind::Slope22UpRightLow, 12.0, 6.0),
+            (TileCollisionKind::Slope22UpRightHigh, 12.0, 14.0),
+        ] {
+            let world = tile_world(uvec2(1, 1), &[(uvec2(0, 0), kind)]);
+            world.run_system(
+                move |collision_world: CollisionWorld| {
+                    assert_eq!(
+                        collision_world.tile_collision_point(vec2(x, surface - 2.0)),
+                        kind,
+                        "{kind:?} below the surface"
+                    );
+                    assert_eq!(
+                        collision_world.tile_collision_point(vec2(x, surface + 2.0)),
+                        TileCollisionKind::Empty,
+                        "{kind:?} above the surface"
+                    );
+                },
+                (),
+            );
+        }
+    }
+
+    #[test]
+    fn cast_ray_hits_filtered_tiles() {
+        let world = tile_world(
+            uvec2(4, 4),
+            &[
+                (uvec2(1, 0), TileCollisionKind::Solid),
+                (uvec2(1, 2), TileCollisionKind::JumpThrough),
+            ],
+        );
+        world.run_system(
+            |collision_world: CollisionWorld| {
+                let origin = vec2(24.0, 60.0);
+                let down = vec2(0.0, -1.0);
+
+                // The jump-through tile is ignored by the default filter.
+                let hit = collision_world
+                    .cast_ray(origin, down, 100.0, &default())
+                    .unwrap();
+                assert_near(hit.distance, 44.0);
+                assert_near(hit.point.y, 16.0);
+                assert_eq!(hit.normal, vec2(0.0, 1.0));
+
+                let filter = CastFilter::default().with_tile_kind(TileCollisionKind::JumpThrough);
+                let hit = collision_world
+                    .cast_ray(origin, down, 100.0, &filter)
+                    .unwrap();
+                assert_near(hit.point.y, 48.0);
+
+                assert!(collision_world
+                    .cast_ray(origin, down, 40.0, &default())
+                    .is_none());
+                assert!(collision_world
+                    .cast_ray(origin, Vec2::ZERO, 100.0, &default())
+                    .is_none());
+            },
+            (),
+        );
+    }
+
+    #[test]
+    fn cast_shape_stops_at_first_hit() {
+        let world = tile_world(uvec2(4, 4), &[(uvec2(3, 1), TileCollisionKind::Solid)]);
+        let shape = ColliderShape::Rectangle {
+            size: vec2(8.0, 8.0),
+        };
+        let actor = spawn_actor(&world, shape, vec2(40.0, 24.0));
+        step(&world);
+
+        world.run_system(
+            move |collision_world: CollisionWorld| {
+                let start = Transform::from_translation(vec3(8.0, 24.0, 0.0));
+                let right = vec2(1.0, 0.0);
+
+                // Actors are only hit when the filter includes them.
+                let hit = collision_world
+                    .cast_shape(start, shape, right, 100.0, &default())
+                    .unwrap();
+                assert_near(hit.distance, 36.0);
+                assert_eq!(hit.normal, vec2(-1.0, 0.0));
+
+                let filter = CastFilter::default().with_actors(CollisionGroup::ALL);
+                let hit = collision_world
+                    .cast_shape(start, shape, right, 100.0, &filter)
+                    .unwrap();
+                assert_eq!(hit.entity, actor);
+                assert_near(hit.distance, 24.0);
+
+                let hit = collision_world.cast_shape(
+                    start,
+                    shape,
+                    right,
+                    100.0,
+                    &filter.excluding(actor),
+                );
+                assert_near(hit.unwrap().distance, 36.0);
+            },
+            (),
+        );
+    }
+
+    #[test]
+    fn fast_casts_do_not_tunnel_through_walls() {
+        // A wall one tile thick, and a bullet that moves more than a whole map width per frame.
+        let world = tile_world(uvec2(8, 3), &[(uvec2(4, 1), TileCollisionKind::Solid)]);
+        world.run_system(
+            |collision_world: CollisionWorld| {
+                let bullet = ColliderShape::Circle { diameter: 2.0 };
+                let start = Transform::from_translation(vec3(8.0, 24.0, 0.0));
+                let movement = vec2(200.0, 0.0);
+                let hit = collision_world
+                    .cast_shape(start, bullet, movement, movement.length(), &default())
+                    .unwrap();
+                assert_near(hit.distance, 55.0);
+            },
+            (),
+        );
+    }
+
+    #[test]
+    fn sweep_actor_finds_actors_passed_through() {
+        let world = tile_world(uvec2(16, 4), &[]);
+        let shape = ColliderShape::Rectangle {
+            size: vec2(8.0, 8.0),
+        };
+        let target = spawn_actor(&world, shape, vec2(100.0, 24.0));
+        // The body moved from one side of the target to the other in a single frame.
+        let body = spawn_actor(&world, shape, vec2(200.0, 24.0));
+        step(&world);
+
+        world.run_system(
+            move |mut collision_world: CollisionWorld, transforms: CompMut<Transform>| {
+                assert!(!collision_world.actor_collisions(body).contains(&target));
+
+                collision_world.sweep_actor(&transforms, body, vec2(20.0, 24.0));
+                assert!(collision_world.actor_collisions(body).contains(&target));
+                assert!(collision_world.actor_collisions(target).contains(&body));
+            },
+            (),
+        );
+
+        // Swept collisions only last until the next update.
+        step(&world);
+        world.run_system(
+            move |collision_world: CollisionWorld| {
+                assert!(!collision_world.actor_collisions(body).contains(&target));
+            },
+            (),
+        );
+    }
+
+    #[test]
+    fn snap_to_ground_follows_slope() {
+        // A slope rising to the right, which the actor is standing above.
+        let world = tile_world(
+            uvec2(3, 2),
+            &[(uvec2(1, 0), TileCollisionKind::Slope45UpRight)],
+        );
+        let shape = ColliderShape::Rectangle {
+            size: vec2(8.0, 8.0),
+        };
+        // The surface of the slope is at y = 12 below the right edge of the actor, 4 units below it.
+        let actor = spawn_actor(&world, shape, vec2(24.0, 20.0));
+        step(&world);
+
+        world.run_system(
+            move |mut collision_world: CollisionWorld, mut transforms: CompMut<Transform>| {
+                assert!(!collision_world.snap_to_ground(&mut transforms, actor, 2.0));
+                assert_near(transforms.get(actor).unwrap().translation.y, 20.0);
+
+                assert!(collision_world.snap_to_ground(&mut transforms, actor, 8.0));
+                assert_near(transforms.get(actor).unwrap().translation.y, 16.0);
+            },
+            (),
+        );
+    }
+
+    #[test]
+    fn moved_solids_collide_before_the_next_update() {
+        let world = tile_world(uvec2(4, 4), &[]);
+        let solid = world.run_system(|mut entities: ResMutInit<Entities>| entities.create(), ());
+        world.run_system(
+            move |mut collision_world: CollisionWorld| {
+                collision_world.solids.insert(
+                    solid,
+                    Solid {
+                        pos: vec2(32.0, 16.0),
+                        size: vec2(32.0, 16.0),
+                        ..default()
+                    },
+                );
+            },
+            (),
+        );
+        let shape = ColliderShape::Rectangle {
+            size: vec2(8.0, 8.0),
+        };
+        let actor = spawn_actor(&world, shape, vec2(32.0, 40.0));
+        step(&world);
+
+        // The top of the solid moves from y = 24 down to y = 8, which the actor falls onto.
+        world.run_system(
+            move |mut collision_world: CollisionWorld, mut transforms: CompMut<Transform>| {
+                collision_world.move_solid(solid, vec2(32.0, 0.0));
+                assert!(collision_world.move_vertical(&mut transforms, actor, -40.0));
+                assert_near(transforms.get(actor).unwrap().translation.y, 12.0);
+            },
+            (),
+        );
+    }
 }
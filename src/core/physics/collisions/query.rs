@@ -0,0 +1,297 @@
+use super::*;
+
+/// The first hit found by [`CollisionWorld::cast_ray`] or [`CollisionWorld::cast_shape`].
+#[derive(HasSchema, Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct CollisionHit {
+    /// The tile, solid, or actor that was hit.
+    pub entity: Entity,
+    /// The point where the ray or the shape hit.
+    pub point: Vec2,
+    /// The normal of the surface that was hit, pointing away from it.
+    pub normal: Vec2,
+    /// How far the ray or the shape travelled before it hit.
+    pub distance: f32,
+}
+
+/// Filter for what a [`CollisionWorld::cast_ray`] or [`CollisionWorld::cast_shape`] query can
+/// hit.
+///
+/// The default filter hits the solid parts of the world: solids, solid tiles, and slopes.
+#[derive(HasSchema, Clone, Debug)]
+#[repr(C)]
+pub struct CastFilter {
+    /// The [`CollisionGroup`] bits of the actors that can be hit. Actors aren't hit if this is `0`.
+    pub actor_groups: u32,
+    /// Whether or not [`Solid`]s can be hit.
+    pub solids: bool,
+    /// The kinds of tiles that can be hit.
+    pub tile_kinds: SVec<TileCollisionKind>,
+    /// An entity that is never hit, usually the one doing the query.
+    pub exclude: Maybe<Entity>,
+}
+
+impl Default for CastFilter {
+    fn default() -> Self {
+        Self {
+            actor_groups: 0,
+            solids: true,
+            tile_kinds: [
+                TileCollisionKind::Solid,
+                TileCollisionKind::Slope45UpRight,
+                TileCollisionKind::Slope45UpLeft,
+                TileCollisionKind::Slope22UpRightLow,
+                TileCollisionKind::Slope22UpRightHigh,
+                TileCollisionKind::Slope22UpLeftHigh,
+                TileCollisionKind::Slope22UpLeftLow,
+            ]
+            .into_iter()
+            .collect(),
+            exclude: Unset,
+        }
+    }
+}
+
+impl CastFilter {
+    /// Also hit the actors in the given collision groups.
+    pub fn with_actors(mut self, groups: CollisionGroup) -> Self {
+        self.actor_groups |= groups.bits();
+        self
+    }
+
+    /// Also hit tiles of the given kind.
+    pub fn with_tile_kind(mut self, kind: TileCollisionKind) -> Self {
+        if !self.tile_kinds.iter().any(|x| *x == kind) {
+            self.tile_kinds.push(kind);
+        }
+        self
+    }
+
+    /// Never hit the given entity.
+    pub fn excluding(mut self, entity: Entity) -> Self {
+        self.exclude = Set(entity);
+        self
+    }
+}
+
+/// Component for casting a ray or a shape from scripts, which can't use [`CollisionWorld`]
+/// directly.
+///
+/// The cast is done every frame after the physics update, and its first hit is written to
+/// [`hit`][Self::hit].
+#[derive(HasSchema, Clone, Debug, Default)]
+#[repr(C)]
+pub struct CollisionCast {
+    /// The shape to cast, or `Unset` to cast a ray.
+    pub shape: Maybe<ColliderShape>,
+    /// Where the cast starts.
+    pub origin: Vec2,
+    /// The direction of the cast. Doesn't need to be normalized.
+    pub direction: Vec2,
+    /// How far to cast.
+    pub max_distance: f32,
+    /// What the cast can hit.
+    pub filter: CastFilter,
+    /// The first hit of the cast, or `Unset` if nothing was hit.
+    pub hit: Maybe<CollisionHit>,
+}
+
+impl<'a> CollisionWorld<'a> {
+    /// Cast a ray, returning the first hit that passes the `filter`, if there is one within
+    /// `max_distance`.
+    ///
+    /// This uses the collider positions as of the last [`update()`][Self::update].
+    pub fn cast_ray(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        filter: &CastFilter,
+    ) -> Option<CollisionHit> {
+        puffin::profile_function!();
+
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return None;
+        }
+
+        let ray = rapier::Ray::new(origin.to_array().into(), direction.to_array().into());
+        let (handle, intersection) = self.ctx.query_pipeline.cast_ray_and_get_normal(
+            &self.ctx.rigid_body_set,
+            &self.ctx.collider_set,
+            &ray,
+            max_distance,
+            true,
+            rapier::QueryFilter::new()
+                .predicate(&|_handle, collider| self.cast_filter_matches(filter, collider)),
+        )?;
+
+        Some(CollisionHit {
+            entity: RapierUserData::entity(self.ctx.collider_set.get(handle).unwrap().user_data),
+            point: origin + direction * intersection.toi,
+            normal: vec2(intersection.normal.x, intersection.normal.y),
+            distance: intersection.toi,
+        })
+    }
+
+    /// Sweep the `shape` from the `transform` in the `direction`, returning the first hit that
+    /// passes the `filter`, if there is one within `max_distance`.
+    ///
+    /// Colliders that the shape is already touching at the start are hit at a distance of `0.0`.
+    /// This uses the collider positions as of the last [`update()`][Self::update].
+    pub fn cast_shape(
+        &self,
+        transform: Transform,
+        shape: ColliderShape,
+        direction: Vec2,
+        max_distance: f32,
+        filter: &CastFilter,
+    ) -> Option<CollisionHit> {
+        self.cast_shape_filtered(transform, shape, direction, max_distance, filter, |_| true)
+    }
+
+    /// Like [`cast_shape`][Self::cast_shape], but only hitting entities that also pass the
+    /// `predicate`.
+    pub fn cast_shape_filtered(
+        &self,
+        transform: Transform,
+        shape: ColliderShape,
+        direction: Vec2,
+        max_distance: f32,
+        filter: &CastFilter,
+        predicate: impl Fn(Entity) -> bool,
+    ) -> Option<CollisionHit> {
+        puffin::profile_function!();
+
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return None;
+        }
+
+        let (handle, toi) = self.ctx.query_pipeline.cast_shape(
+            &self.ctx.rigid_body_set,
+            &self.ctx.collider_set,
+            &(
+                transform.translation.truncate(),
+                transform.rotation.to_euler(EulerRot::XYZ).2,
+            )
+                .into(),
+            &direction.to_array().into(),
+            &*shape.shared_shape(),
+            max_distance,
+            true,
+            rapier::QueryFilter::new().predicate(&|_handle, collider| {
+                self.cast_filter_matches(filter, collider)
+                    && predicate(RapierUserData::entity(collider.user_data))
+            }),
+        )?;
+
+        // The witness point and the normal are in the local space of the collider that was hit.
+        let collider = self.ctx.collider_set.get(handle).unwrap();
+        let point = collider.position() * toi.witness2;
+        let normal = collider.position() * toi.normal2;
+        Some(CollisionHit {
+            entity: RapierUserData::entity(collider.user_data),
+            point: vec2(point.x, point.y),
+            normal: vec2(normal.x, normal.y),
+            distance: toi.toi,
+        })
+    }
+
+    /// Record the actors that the `entity` passed through while moving from `from` to its current
+    /// position, so that they are included in [`actor_collisions`][Self::actor_collisions] until
+    /// the next [`update()`][Self::update], even if the entity moved past them.
+    ///
+    /// This keeps fast bodies from tunneling through other actors between two frames.
+    pub fn sweep_actor(&mut self, transforms: &CompMut<Transform>, entity: Entity, from: Vec2) {
+        puffin::profile_function!();
+
+        let Some(shape) = self.colliders.get(entity).map(|collider| collider.shape) else {
+            return;
+        };
+        let transform = *transforms.get(entity).unwrap();
+        let path = transform.translation.truncate() - from;
+        let filter = CastFilter {
+            actor_groups: CollisionGroup::ALL.bits(),
+            solids: false,
+            tile_kinds: default(),
+            exclude: Set(entity),
+        };
+
+        let mut start = transform;
+        start.translation = from.extend(transform.translation.z);
+        let mut hits = Vec::new();
+        // Limit the amount of actors found, in case a lot of them are on the path.
+        while hits.len() < 8 {
+            let Some(hit) =
+                self.cast_shape_filtered(start, shape, path, path.length(), &filter, |e| {
+                    !hits.contains(&e)
+                })
+            else {
+                break;
+            };
+            hits.push(hit.entity);
+        }
+
+        let swept = &mut self.ctx.collision_cache.swept;
+        for hit in hits {
+            for (a, b) in [(entity, hit), (hit, entity)] {
+                let entry = swept.entry(a).or_default();
+                if !entry.contains(&b) {
+                    entry.push(b);
+                }
+            }
+        }
+    }
+
+    /// Whether or not the rapier collider passes the cast filter.
+    fn cast_filter_matches(&self, filter: &CastFilter, collider: &rapier::Collider) -> bool {
+        let ent = RapierUserData::entity(collider.user_data);
+        if let Set(exclude) = filter.exclude {
+            if exclude == ent {
+                return false;
+            }
+        }
+
+        if self.solids.contains(ent) {
+            filter.solids
+        } else if let Some(kind) = self.tile_collision_kinds.get(ent) {
+            filter.tile_kinds.iter().any(|x| x == kind)
+        } else if self.actors.contains(ent) {
+            collider.collision_groups().memberships.bits() & filter.actor_groups != 0
+        } else {
+            false
+        }
+    }
+}
+
+/// Run the casts of all of the [`CollisionCast`] components, updating their hits.
+pub fn update_collision_casts(
+    entities: Res<Entities>,
+    collision_world: CollisionWorld,
+    mut casts: CompMut<CollisionCast>,
+) {
+    puffin::profile_function!();
+
+    for (_ent, cast) in entities.iter_with(&mut casts) {
+        let hit = match cast.shape {
+            Set(shape) => collision_world.cast_shape(
+                Transform::from_translation(cast.origin.extend(0.0)),
+                shape,
+                cast.direction,
+                cast.max_distance,
+                &cast.filter,
+            ),
+            Unset => collision_world.cast_ray(
+                cast.origin,
+                cast.direction,
+                cast.max_distance,
+                &cast.filter,
+            ),
+        };
+        cast.hit = match hit {
+            Some(hit) => Set(hit),
+            None => Unset,
+        };
+    }
+}
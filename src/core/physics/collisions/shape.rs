@@ -10,17 +10,115 @@ impl ColliderShapeCache {
     }
 }
 
+/// The maximum number of points in a [`ColliderShape::ConvexPolygon`].
+pub const MAX_POLYGON_POINTS: usize = 8;
+
 /// The Jumpy collision shape type.
 #[derive(Clone, Copy, Debug, HasSchema)]
 #[repr(C, u8)]
 pub enum ColliderShape {
-    Circle { diameter: f32 },
-    Rectangle { size: Vec2 },
+    Circle {
+        diameter: f32,
+    },
+    Rectangle {
+        size: Vec2,
+    },
     // Capsule aligned with Y axis
-    CapsuleY { half_length: f32, radius: f32 },
+    CapsuleY {
+        half_length: f32,
+        radius: f32,
+    },
+    /// A convex polygon, with points relative to the center of the body.
+    ///
+    /// Use [`ColliderShape::convex_polygon`] to create one.
+    ConvexPolygon {
+        points: PolygonPoints,
+    },
+}
+
+/// The points of a [`ColliderShape::ConvexPolygon`].
+///
+/// The points are stored inline, instead of in a `Vec`, so that collider shapes can stay `Copy`.
+#[derive(Clone, Copy, Debug, Default, HasSchema)]
+pub struct PolygonPoints {
+    points: [Vec2; MAX_POLYGON_POINTS],
+    len: u8,
+}
+
+impl std::ops::Deref for PolygonPoints {
+    type Target = [Vec2];
+    fn deref(&self) -> &Self::Target {
+        &self.points[..self.len as usize]
+    }
 }
 
 impl ColliderShape {
+    /// Create a [`ColliderShape::ConvexPolygon`] from points relative to the center of the body.
+    ///
+    /// The points may be in any order, the polygon is the convex hull of the points. Returns `None`
+    /// if there are less than 3 or more than [`MAX_POLYGON_POINTS`] points.
+    pub fn convex_polygon(points: impl IntoIterator<Item = Vec2>) -> Option<Self> {
+        let mut polygon = PolygonPoints::default();
+        for point in points {
+            if polygon.len as usize == MAX_POLYGON_POINTS {
+                return None;
+            }
+            polygon.points[polygon.len as usize] = point;
+            polygon.len += 1;
+        }
+        (polygon.len >= 3).then_some(Self::ConvexPolygon { points: polygon })
+    }
+
+    /// Get the collider for an element's `body_polygon` metadata: a convex polygon with points
+    /// relative to the center of the body, used instead of the element's usual shape.
+    ///
+    /// Returns the `fallback` shape if the polygon is empty, and also logs a warning if the polygon
+    /// is invalid, so that mistakes in the metadata don't go unnoticed.
+    pub fn from_body_polygon(polygon: &SVec<Vec2>, fallback: Self) -> Self {
+        if polygon.is_empty() {
+            return fallback;
+        }
+        match Self::convex_polygon(polygon.iter().copied()) {
+            Some(shape) if shape.polygon_hull().is_some() => shape,
+            _ => {
+                warn!(
+                    "Invalid body polygon {polygon:?}: it must have between 3 and \
+                    {MAX_POLYGON_POINTS} points that aren't all on one line. Using {fallback:?} \
+                    instead."
+                );
+                fallback
+            }
+        }
+    }
+
+    /// Get the shape mirrored horizontally around the center of the body.
+    ///
+    /// Only polygons change, the other shapes are symmetric.
+    pub fn flip_x(self) -> Self {
+        match self {
+            Self::ConvexPolygon { mut points } => {
+                for point in &mut points.points[..points.len as usize] {
+                    point.x = -point.x;
+                }
+                Self::ConvexPolygon { points }
+            }
+            other => other,
+        }
+    }
+
+    /// Get the rapier convex hull of a [`ColliderShape::ConvexPolygon`], or `None` if it isn't a
+    /// polygon or all of its points are on one line.
+    fn polygon_hull(&self) -> Option<rapier::SharedShape> {
+        let Self::ConvexPolygon { points } = self else {
+            return None;
+        };
+        let points = points
+            .iter()
+            .map(|point| rapier::Point::new(point.x, point.y))
+            .collect::<Vec<_>>();
+        rapier::SharedShape::convex_hull(&points)
+    }
+
     pub fn compute_aabb(&self, transform: Transform) -> rapier::Aabb {
         match self {
             ColliderShape::Circle { diameter } => rapier::Ball {
@@ -44,6 +142,18 @@ impl ColliderShape {
                 transform.translation.truncate().to_array().into(),
                 transform.rotation.to_euler(EulerRot::XYZ).2,
             )),
+            ColliderShape::ConvexPolygon { points } => {
+                let rotation = Vec2::from_angle(transform.rotation.to_euler(EulerRot::XYZ).2);
+                let translation = transform.translation.truncate();
+                let (min, max) = points.iter().fold(
+                    (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+                    |(min, max), point| {
+                        let point = translation + rotation.rotate(*point);
+                        (min.min(point), max.max(point))
+                    },
+                );
+                rapier::Aabb::new(min.to_array().into(), max.to_array().into())
+            }
         }
     }
 
@@ -72,6 +182,14 @@ impl ColliderShape {
                 half_length,
                 radius,
             } => rapier::SharedShape::capsule_y(*half_length, *radius),
+            ColliderShape::ConvexPolygon { .. } => {
+                // The hull can only fail to be computed if all of the points are on one line, in
+                // which case we fall back to a tiny ball, so that the body still has a collider.
+                self.polygon_hull().unwrap_or_else(|| {
+                    warn!("Convex polygon {self:?} has all of its points on one line.");
+                    rapier::SharedShape::ball(0.5)
+                })
+            }
         }
     }
 }
@@ -99,6 +217,16 @@ impl PartialEq for ColliderShape {
             (Self::Rectangle { size: l_size }, Self::Rectangle { size: r_size }) => {
                 F(l_size.x) == F(r_size.x) && F(l_size.y) == F(r_size.y)
             }
+            (
+                Self::ConvexPolygon { points: l_points },
+                Self::ConvexPolygon { points: r_points },
+            ) => {
+                l_points.len() == r_points.len()
+                    && l_points
+                        .iter()
+                        .zip(r_points.iter())
+                        .all(|(l, r)| F(l.x) == F(r.x) && F(l.y) == F(r.y))
+            }
             _ => false,
         }
     }
@@ -123,6 +251,12 @@ impl std::hash::Hash for ColliderShape {
                 F(*half_length).hash(state);
                 F(*radius).hash(state);
             }
+            ColliderShape::ConvexPolygon { points } => {
+                for point in points.iter() {
+                    F(point.x).hash(state);
+                    F(point.y).hash(state);
+                }
+            }
         }
     }
 }
@@ -142,6 +276,7 @@ impl Ord for ColliderShape {
                 ColliderShape::Circle { diameter: r2 } => F(*r1).cmp(&F(*r2)),
                 ColliderShape::Rectangle { .. } => Less,
                 ColliderShape::CapsuleY { .. } => Less,
+                ColliderShape::ConvexPolygon { .. } => Less,
             },
             ColliderShape::Rectangle { size: s1 } => match other {
                 ColliderShape::Rectangle { size: s2 } => {
@@ -154,6 +289,7 @@ impl Ord for ColliderShape {
                 }
                 ColliderShape::Circle { .. } => Greater,
                 ColliderShape::CapsuleY { .. } => Less,
+                ColliderShape::ConvexPolygon { .. } => Less,
             },
             ColliderShape::CapsuleY {
                 half_length: l1,
@@ -172,6 +308,14 @@ impl Ord for ColliderShape {
                 }
                 ColliderShape::Circle { .. } => Greater,
                 ColliderShape::Rectangle { .. } => Greater,
+                ColliderShape::ConvexPolygon { .. } => Less,
+            },
+            ColliderShape::ConvexPolygon { points: p1 } => match other {
+                ColliderShape::ConvexPolygon { points: p2 } => p1
+                    .iter()
+                    .map(|point| (F(point.x), F(point.y)))
+                    .cmp(p2.iter().map(|point| (F(point.x), F(point.y)))),
+                _ => Greater,
             },
         }
     }
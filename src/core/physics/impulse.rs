@@ -0,0 +1,105 @@
+//! Radial impulses, which push bodies away from a point, like the blast of an explosion.
+
+use crate::prelude::*;
+
+/// Component that pushes the bodies around its entity's [`Transform`] away from it, like the blast
+/// of an explosion.
+///
+/// The impulse is applied once, in the physics update after it is added, and then the component
+/// is removed. It pushes [`KinematicBody`]s such as items, critters and players, and rapier
+/// simulated [`DynamicBody`]s such as ragdolls. Bodies that are deactivated or don't have mass
+/// aren't pushed.
+///
+/// Scripts can add this component to an entity with a [`Transform`] to make an explosion.
+#[derive(HasSchema, Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct RadialImpulse {
+    /// The distance from the center that bodies are pushed within.
+    pub radius: f32,
+    /// The speed that bodies at the center are pushed with, in pixels per second. It falls off
+    /// linearly to nothing at the radius.
+    pub strength: f32,
+    /// Extra upward speed that bodies are given, as a fraction of their push, so that bodies on
+    /// the ground are thrown into the air instead of sliding along it.
+    pub lift: f32,
+    /// Whether bodies that are behind solid walls from the center are protected from the push.
+    pub line_of_sight: bool,
+}
+
+impl RadialImpulse {
+    /// Get the velocity that a body at the given point is pushed with by the impulse at the given
+    /// center, or `None` if the point is out of its radius.
+    pub fn velocity_at(&self, center: Vec2, point: Vec2) -> Option<Vec2> {
+        let offset = point - center;
+        let distance = offset.length();
+        if distance > self.radius {
+            return None;
+        }
+
+        let speed = self.strength * (1.0 - distance / self.radius);
+        // Bodies right at the center are pushed straight up
+        let direction = offset.try_normalize().unwrap_or(Vec2::Y);
+        Some(direction * speed + Vec2::Y * speed * self.lift)
+    }
+}
+
+/// Apply the [`RadialImpulse`]s that have been added, and remove them.
+pub fn apply_radial_impulses(
+    entities: Res<Entities>,
+    mut impulses: CompMut<RadialImpulse>,
+    transforms: Comp<Transform>,
+    mut bodies: CompMut<KinematicBody>,
+    mut dynamic_bodies: CompMut<DynamicBody>,
+    collision_world: CollisionWorld,
+) {
+    puffin::profile_function!();
+
+    let blasts = entities
+        .iter_with((&impulses, &transforms))
+        .map(|(ent, (impulse, transform))| (ent, *impulse, transform.translation.truncate()))
+        .collect::<Vec<_>>();
+    for (impulse_ent, impulse, center) in blasts {
+        impulses.remove(impulse_ent);
+        if impulse.radius <= 0.0 {
+            continue;
+        }
+
+        for (ent, (body, transform, dynamic_body)) in entities.iter_with((
+            &mut bodies,
+            &transforms,
+            &mut OptionalMut(&mut dynamic_bodies),
+        )) {
+            if ent == impulse_ent || body.is_deactivated || !body.has_mass {
+                continue;
+            }
+
+            let target = body.bounding_box(*transform).center();
+            let Some(velocity) = impulse.velocity_at(center, target) else {
+                continue;
+            };
+
+            // Walls between the center and the body shield it from the blast. Walls that the
+            // center is inside of are ignored, so that explosions in the ground still push.
+            if impulse.line_of_sight
+                && collision_world
+                    .cast_ray(center, target - center, center.distance(target), &default())
+                    .filter(|hit| hit.distance > 0.0)
+                    .is_some()
+            {
+                continue;
+            }
+
+            match dynamic_body.filter(|x| x.is_dynamic) {
+                Some(dynamic_body) => {
+                    dynamic_body.push_simulation_command(Box::new(
+                        move |body: &mut rapier::RigidBody| {
+                            let lin_vel = *body.linvel() + rapier::vector!(velocity.x, velocity.y);
+                            body.set_linvel(lin_vel, true);
+                        },
+                    ));
+                }
+                None => body.velocity += velocity,
+            }
+        }
+    }
+}
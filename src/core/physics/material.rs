@@ -0,0 +1,56 @@
+//! Physics materials, which change how bodies move on the things that they touch.
+
+use crate::prelude::*;
+
+/// A physics material, which changes how bodies move while they touch the map tile, [`Solid`], or
+/// body that has it as a component.
+///
+/// Map tiles get their material from the [`TilesetMeta`] of their tileset, and elements from the
+/// [`material`][ElementMeta::material] of their [`ElementMeta`]. Both [`KinematicBody`]s and rapier
+/// simulated [`DynamicBody`]s are affected by it.
+#[derive(HasSchema, Clone, Copy, Debug, PartialEq)]
+#[type_data(metadata_asset("physics_material"))]
+#[repr(C)]
+pub struct PhysicsMaterial {
+    /// Multiplies how much speed bodies lose to friction while they are on the material.
+    ///
+    /// `1.0` is normal friction, lower values are slippery and higher values are sticky.
+    pub friction: f32,
+    /// How much of their speed bodies keep when they bounce off of the material, if it's more than
+    /// their own [`bounciness`][KinematicBody::bounciness].
+    pub bounciness: f32,
+    /// Multiplies how fast players walk on the material.
+    pub speed: f32,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self {
+            friction: 1.0,
+            bounciness: 0.0,
+            speed: 1.0,
+        }
+    }
+}
+
+impl PhysicsMaterial {
+    /// Combine this material with another one, for a body that touches both of them.
+    pub fn combine(self, other: Self) -> Self {
+        Self {
+            friction: self.friction * other.friction,
+            bounciness: self.bounciness.max(other.bounciness),
+            speed: self.speed * other.speed,
+        }
+    }
+
+    /// Get how much of its horizontal velocity a body on the material keeps every frame, given the
+    /// `friction_lerp` from the [`PhysicsMeta`] that is used for normal friction.
+    pub fn friction_lerp(&self, friction_lerp: f32) -> f32 {
+        (1.0 - (1.0 - friction_lerp) * self.friction).clamp(0.0, 1.0)
+    }
+
+    /// Get the friction coefficient for rapier colliders with the material.
+    pub fn rapier_friction(&self) -> f32 {
+        rapier::ColliderBuilder::default_friction() * self.friction
+    }
+}
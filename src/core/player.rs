@@ -4,7 +4,9 @@ use std::collections::VecDeque;
 
 use crate::prelude::*;
 
+mod abilities;
 mod state;
+pub use abilities::*;
 pub use state::*;
 use turborand::GenCore;
 
@@ -28,6 +30,9 @@ pub fn plugin(session: &mut Session) {
         .add_system_to_stage(CoreStage::PostUpdate, equip_hats)
         .add_system_to_stage(CoreStage::Last, delete_dead_ai_swords)
         .add_system_to_stage(CoreStage::Last, update_player_layers);
+
+    // Update the abilities after the players are hydrated
+    session.install_plugin(abilities::install);
 }
 
 /// The player index, for example Player 1, Player 2, and so on.
@@ -199,10 +204,15 @@ impl Emote {
 
 /// Marker component indicating that a player has been killed.
 ///
-/// This usually means their death animation is playing, and they are about to be de-spawned.
+/// This usually means their death animation is playing, and they are about to be de-spawned. See
+/// [`PlayerLives`] for whether they will respawn.
 #[derive(Clone, HasSchema, Default)]
+#[repr(C)]
 pub struct PlayerKilled {
-    pub hit_from: Option<Vec2>,
+    /// The position that the player was hit from.
+    pub hit_from: Maybe<Vec2>,
+    /// Who killed the player, and with what.
+    pub source: DamageSource,
 }
 
 /// Events that can be used to trigger player actions, such as killing, setting inventory, etc.
@@ -213,11 +223,16 @@ impl PlayerCommand {
     /// Kill a player.
     ///
     /// > **Note:** This doesn't despawn the player, it just puts the player into it's death animation.
-    pub fn kill(player: Entity, hit_from: Option<Vec2>) -> StaticSystem<(), ()> {
+    pub fn kill(
+        player: Entity,
+        hit_from: Option<Vec2>,
+        source: DamageSource,
+    ) -> StaticSystem<(), ()> {
         (move |entities: Res<Entities>,
                mut players_killed: CompMut<PlayerKilled>,
                mut items_dropped: CompMut<ItemDropped>,
                mut inventories: CompMut<Inventory>,
+               mut lives: ResMutInit<PlayerLives>,
                player_indexes: Comp<PlayerIdx>| {
             if players_killed.contains(player) {
                 // No need to kill him again
@@ -230,7 +245,9 @@ impl PlayerCommand {
                 return;
             };
 
-            debug!("Killing player: {}", idx.0);
+            let source = source.with_killer_idx(&player_indexes);
+            debug!("Killing player {} by {source:?}", idx.0);
+            lives.lose_life(*idx);
 
             // Drop any items the player was carrying
             let inventory = inventories.get(player).cloned().unwrap_or_default();
@@ -243,7 +260,81 @@ impl PlayerCommand {
             // Update the inventory
             inventories.insert(player, Inventory(None));
 
-            players_killed.insert(player, PlayerKilled { hit_from });
+            players_killed.insert(
+                player,
+                PlayerKilled {
+                    hit_from: hit_from.map_or(Unset, Set),
+                    source,
+                },
+            );
+        })
+        .system()
+    }
+
+    /// Damage a player, knocking them back with the given velocity.
+    ///
+    /// With the [`one_hit_kill`][MatchRules::one_hit_kill] rule this kills the player. Otherwise
+    /// the hit is absorbed by the player's armor if they have any, or the damage is taken from
+    /// their [`PlayerHealth`], killing them once it runs out. Players that survive the hit get a
+    /// [`PlayerDamaged`] event and are invincible for a moment.
+    ///
+    /// Players that are already killed or invincible aren't damaged.
+    pub fn damage(
+        player: Entity,
+        damage: f32,
+        knockback: Vec2,
+        hit_from: Option<Vec2>,
+        source: DamageSource,
+    ) -> StaticSystem<(), ()> {
+        (move |mut commands: Commands,
+               game_meta: Root<GameMeta>,
+               rules: Res<MatchRules>,
+               player_indexes: Comp<PlayerIdx>,
+               players_killed: Comp<PlayerKilled>,
+               mut players_damaged: CompMut<PlayerDamaged>,
+               mut healths: CompMut<PlayerHealth>,
+               mut invincibles: CompMut<Invincibility>,
+               mut bodies: CompMut<KinematicBody>| {
+            if players_killed.contains(player) || invincibles.contains(player) {
+                return;
+            }
+
+            if let Some(body) = bodies.get_mut(player) {
+                body.velocity += knockback;
+            }
+
+            let Some(health) = healths.get_mut(player).filter(|_| !rules.one_hit_kill) else {
+                commands.add(PlayerCommand::kill(player, hit_from, source));
+                return;
+            };
+            let absorbed = health.armor > 0;
+            if absorbed {
+                health.armor -= 1;
+            } else {
+                health.health -= damage;
+            }
+            if health.health <= 0.0 {
+                health.health = 0.0;
+                commands.add(PlayerCommand::kill(player, hit_from, source));
+                return;
+            }
+
+            if !players_damaged.contains(player) {
+                players_damaged.insert(player, default());
+            }
+            let damaged = players_damaged.get_mut(player).unwrap();
+            if absorbed {
+                damaged.absorbed += 1;
+            } else {
+                damaged.damage += damage;
+            }
+            damaged.hit_from = hit_from.map_or(Unset, Set);
+            damaged.source = source.with_killer_idx(&player_indexes);
+
+            invincibles.insert(
+                player,
+                Invincibility::new(game_meta.core.config.hit_invincibility_time),
+            );
         })
         .system()
     }
@@ -812,6 +903,7 @@ fn hydrate_players(
                       assets: Res<AssetServer>,
                       mut hydrated: CompMut<MapElementHydrated>,
                       mut bodies: CompMut<KinematicBody>,
+                      mut mirror_colliders: CompMut<MirrorCollider>,
                       mut atlas_sprites: CompMut<AtlasSprite>,
                       mut items: CompMut<Item>,
                       mut transforms: CompMut<Transform>,
@@ -833,6 +925,7 @@ fn hydrate_players(
                     if let Ok(SwordMeta {
                         atlas,
                         body_size,
+                        body_polygon,
                         can_rotate,
                         bounciness,
                         grab_offset,
@@ -847,10 +940,14 @@ fn hydrate_players(
                         transforms.insert(sword_ent, default());
                         element_handles.insert(sword_ent, ElementHandle(*element_handle));
                         hydrated.insert(sword_ent, MapElementHydrated);
+                        mirror_colliders.insert(sword_ent, MirrorCollider::default());
                         bodies.insert(
                             sword_ent,
                             KinematicBody {
-                                shape: ColliderShape::Rectangle { size: *body_size },
+                                shape: ColliderShape::from_body_polygon(
+                                    body_polygon,
+                                    ColliderShape::Rectangle { size: *body_size },
+                                ),
                                 has_mass: true,
                                 has_friction: true,
                                 can_rotate: *can_rotate,
@@ -0,0 +1,130 @@
+//! Player abilities, such as double jumping, that are used through their own player states.
+//!
+//! Players get the abilities from their [`PlayerMeta`], and may be granted more by the hat that
+//! they are wearing or by the item that they are holding with [`GrantAbilities`].
+
+use super::*;
+
+pub fn install(session: &mut Session) {
+    PlayerAbilities::register_schema();
+    AbilityCharges::register_schema();
+    GrantAbilities::register_schema();
+
+    session
+        .stages
+        .add_system_to_stage(CoreStage::First, update_player_abilities);
+}
+
+/// Component for the abilities that a player currently has, and how many more times they can use
+/// them.
+#[derive(Clone, Debug, HasSchema, Default)]
+#[repr(C)]
+pub struct PlayerAbilities {
+    /// The abilities of the player, including the ones granted by their hat and held item.
+    pub meta: PlayerAbilitiesMeta,
+    pub double_jump: AbilityCharges,
+    pub air_dash: AbilityCharges,
+    pub ground_pound: AbilityCharges,
+}
+
+/// The uses left of one of the player's abilities.
+///
+/// The charges are refilled whenever the player is on the ground or swimming.
+#[derive(Clone, Copy, Debug, HasSchema, Default)]
+#[repr(C)]
+pub struct AbilityCharges {
+    /// The number of times that the ability can be used before the player lands again.
+    pub charges: u32,
+    /// The time left until the ability can be used again, in seconds.
+    pub cooldown: f32,
+}
+
+impl AbilityCharges {
+    /// Whether the ability can be used right now.
+    pub fn is_ready(&self) -> bool {
+        self.charges > 0 && self.cooldown <= 0.0
+    }
+
+    /// Use up one of the charges and start the cooldown.
+    pub fn use_charge(&mut self, cooldown: f32) {
+        self.charges = self.charges.saturating_sub(1);
+        self.cooldown = cooldown;
+    }
+
+    /// Refill the charges and make sure there aren't more than the ability allows.
+    fn update(&mut self, max_charges: u32, refill: bool, delta: f32) {
+        if refill {
+            self.charges = max_charges;
+        }
+        self.charges = self.charges.min(max_charges);
+        self.cooldown = (self.cooldown - delta).max(0.0);
+    }
+}
+
+/// Component for items that grant abilities to the player holding them.
+#[derive(Clone, Debug, HasSchema, Default, Deref, DerefMut)]
+#[repr(C)]
+pub struct GrantAbilities(pub PlayerAbilitiesMeta);
+
+/// System that updates the abilities of players from their metadata, hat, and held item, and
+/// refills their charges.
+fn update_player_abilities(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    player_states: Comp<PlayerState>,
+    player_layers: Comp<PlayerLayers>,
+    player_body_attachments: Comp<PlayerBodyAttachment>,
+    inventories: Comp<Inventory>,
+    hats: Comp<Hat>,
+    grant_abilities: Comp<GrantAbilities>,
+    bodies: Comp<KinematicBody>,
+    assets: Res<AssetServer>,
+    time: Res<Time>,
+    mut player_abilities: CompMut<PlayerAbilities>,
+) {
+    for (player_ent, (player_idx, _state, layers, body)) in
+        entities.iter_with((&player_indexes, &player_states, &player_layers, &bodies))
+    {
+        let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let mut meta = assets.get(meta_handle).abilities.clone();
+
+        // Add the abilities of the hat that the player is wearing
+        let worn_hat = layers.hat_ent.filter(|hat_ent| {
+            player_body_attachments
+                .get(*hat_ent)
+                .is_some_and(|attachment| attachment.player == player_ent)
+        });
+        if let Some(hat) = worn_hat.and_then(|hat_ent| hats.get(hat_ent)) {
+            meta = meta.with_granted(&assets.get(hat.0).abilities);
+        }
+
+        // Add the abilities of the item that the player is holding
+        if let Some(granted) = inventories
+            .get(player_ent)
+            .and_then(|inventory| inventory.0)
+            .and_then(|item| grant_abilities.get(item))
+        {
+            meta = meta.with_granted(granted);
+        }
+
+        let is_new = !player_abilities.contains(player_ent);
+        if is_new {
+            player_abilities.insert(player_ent, default());
+        }
+        let abilities = player_abilities.get_mut(player_ent).unwrap();
+
+        let refill = is_new || body.is_on_ground || body.submersion >= swim::SWIM_SUBMERSION;
+        let delta = time.delta().as_secs_f32();
+        abilities
+            .double_jump
+            .update(meta.double_jump.charges, refill, delta);
+        abilities
+            .air_dash
+            .update(meta.air_dash.charges, refill, delta);
+        abilities
+            .ground_pound
+            .update(meta.ground_pound.charges, refill, delta);
+        abilities.meta = meta;
+    }
+}
@@ -46,15 +46,21 @@ pub fn plugin(session: &mut Session) {
         .stages
         .add_system_to_stage(CoreStage::Last, update_player_state_age);
 
+    air_dash::install(session);
     crouch::install(session);
     dead::install(session);
     default::install(session);
+    double_jump::install(session);
     drive_jellyfish::install(session);
+    ground_pound::install(session);
     idle::install(session);
     incapacitated::install(session);
     ragdoll::install(session);
     midair::install(session);
+    swim::install(session);
     walk::install(session);
+    wall_jump::install(session);
+    wall_slide::install(session);
 }
 
 fn update_player_state_age(entities: Res<Entities>, mut player_states: CompMut<PlayerState>) {
@@ -1,11 +1,17 @@
 use super::*;
 
+pub mod air_dash;
 pub mod crouch;
 pub mod dead;
 pub mod default;
+pub mod double_jump;
 pub mod drive_jellyfish;
+pub mod ground_pound;
 pub mod idle;
 pub mod incapacitated;
 pub mod midair;
 pub mod ragdoll;
+pub mod swim;
 pub mod walk;
+pub mod wall_jump;
+pub mod wall_slide;
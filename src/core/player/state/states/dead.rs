@@ -31,6 +31,8 @@ pub fn handle_player_state(
     mut dynamic_bodies: CompMut<DynamicBody>,
     mut animations: CompMut<AnimationBankSprite>,
     game_meta: Root<GameMeta>,
+    rules: Res<MatchRules>,
+    lives: ResInit<PlayerLives>,
     mut collision_world: CollisionWorld,
     mut audio_center: ResMut<AudioCenter>,
 ) {
@@ -97,9 +99,16 @@ pub fn handle_player_state(
             animation.current = "death_ragdoll".into();
         }
 
-        if state.age >= 80 {
+        if player_indices.bitset().bit_count() == 1 {
             // If only one player in match, we wont' score / transition rounds, so respawn player.
-            if player_indices.bitset().bit_count() == 1 {
+            if state.age >= 80 {
+                commands.add(PlayerCommand::despawn(player_ent));
+            }
+        } else if lives.remaining(*player_idx, &rules) > 0 {
+            // Respawn the player if they have lives left. The player spawner spawns them again
+            // once they are despawned.
+            let respawn_frames = game_meta.core.config.respawn_delay.as_secs_f32() * FPS;
+            if state.age as f32 >= respawn_frames {
                 commands.add(PlayerCommand::despawn(player_ent));
             }
         }
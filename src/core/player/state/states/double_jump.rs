@@ -0,0 +1,80 @@
+use super::*;
+
+pub static ID: Lazy<Ustr> = Lazy::new(|| ustr("core::double_jump"));
+
+pub fn install(session: &mut Session) {
+    PlayerState::add_player_state_transition_system(session, player_state_transition);
+    PlayerState::add_player_state_update_system(session, handle_player_state);
+    PlayerState::add_player_state_update_system(session, use_drop_or_grab_items_system(*ID));
+}
+
+pub fn player_state_transition(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    mut player_states: CompMut<PlayerState>,
+    bodies: Comp<KinematicBody>,
+) {
+    for (_ent, (player_idx, player_state, body)) in
+        entities.iter_with((&player_indexes, &mut player_states, &bodies))
+    {
+        if player_state.current != *ID {
+            continue;
+        }
+        let control = &player_inputs.players[player_idx.0 as usize].control;
+
+        // The jump is made in the first frame of the state, so the state always lasts at least
+        // one frame.
+        if player_state.age == 0 {
+            continue;
+        }
+
+        if body.is_on_ground {
+            player_state.current = *idle::ID;
+        } else if body.submersion >= swim::SWIM_SUBMERSION {
+            player_state.current = *swim::ID;
+        } else if control.ragdoll_just_pressed {
+            player_state.current = *ragdoll::ID;
+        } else {
+            player_state.current = *midair::ID;
+        }
+    }
+}
+
+pub fn handle_player_state(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    player_states: Comp<PlayerState>,
+    assets: Res<AssetServer>,
+    mut player_abilities: CompMut<PlayerAbilities>,
+    mut animations: CompMut<AnimationBankSprite>,
+    mut bodies: CompMut<KinematicBody>,
+    mut audio_center: ResMut<AudioCenter>,
+) {
+    let players = entities.iter_with((
+        &player_states,
+        &player_indexes,
+        &mut player_abilities,
+        &mut animations,
+        &mut bodies,
+    ));
+    for (_player_ent, (player_state, player_idx, abilities, animation, body)) in players {
+        if player_state.current != *ID {
+            continue;
+        }
+        let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let meta = assets.get(meta_handle);
+
+        // If this is the first frame of this state, jump
+        if player_state.age == 0 {
+            audio_center.play_sound(meta.sounds.jump, meta.sounds.jump_volume);
+
+            let double_jump = &abilities.meta.double_jump;
+            body.velocity.y = double_jump.jump_speed;
+            abilities.double_jump.use_charge(double_jump.cooldown);
+        }
+
+        animation.current = "rise".into();
+    }
+}
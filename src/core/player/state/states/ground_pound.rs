@@ -0,0 +1,76 @@
+use super::*;
+
+pub static ID: Lazy<Ustr> = Lazy::new(|| ustr("core::ground_pound"));
+
+pub fn install(session: &mut Session) {
+    PlayerState::add_player_state_transition_system(session, player_state_transition);
+    PlayerState::add_player_state_update_system(session, handle_player_state);
+    PlayerState::add_player_state_update_system(session, use_drop_or_grab_items_system(*ID));
+}
+
+pub fn player_state_transition(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    assets: Res<AssetServer>,
+    mut player_states: CompMut<PlayerState>,
+    bodies: Comp<KinematicBody>,
+    mut audio_center: ResMut<AudioCenter>,
+    mut trauma_events: ResMutInit<CameraTraumaEvents>,
+) {
+    for (_ent, (player_idx, player_state, body)) in
+        entities.iter_with((&player_indexes, &mut player_states, &bodies))
+    {
+        if player_state.current != *ID {
+            continue;
+        }
+        let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let meta = assets.get(meta_handle);
+
+        // The slam starts in the first frame of the state, so the state always lasts at least one
+        // frame.
+        if player_state.age == 0 {
+            continue;
+        }
+
+        if body.is_on_ground {
+            // Slam into the ground
+            audio_center.play_sound(meta.sounds.land, meta.sounds.land_volume);
+            trauma_events.send(3.0);
+            player_state.current = *idle::ID;
+        } else if body.submersion >= swim::SWIM_SUBMERSION {
+            player_state.current = *swim::ID;
+        }
+    }
+}
+
+pub fn handle_player_state(
+    entities: Res<Entities>,
+    player_states: Comp<PlayerState>,
+    mut player_abilities: CompMut<PlayerAbilities>,
+    mut animations: CompMut<AnimationBankSprite>,
+    mut bodies: CompMut<KinematicBody>,
+) {
+    let players = entities.iter_with((
+        &player_states,
+        &mut player_abilities,
+        &mut animations,
+        &mut bodies,
+    ));
+    for (_player_ent, (player_state, abilities, animation, body)) in players {
+        if player_state.current != *ID {
+            continue;
+        }
+        let ground_pound = &abilities.meta.ground_pound;
+
+        if player_state.age == 0 {
+            abilities.ground_pound.use_charge(ground_pound.cooldown);
+        }
+
+        animation.current = "fall".into();
+
+        // Slam straight down, without falling through platforms
+        body.velocity = vec2(0.0, -ground_pound.speed);
+        body.fall_through = false;
+    }
+}
@@ -26,6 +26,8 @@ pub fn player_state_transition(
 
         if control.ragdoll_just_pressed {
             player_state.current = *ragdoll::ID;
+        } else if body.submersion >= swim::SWIM_SUBMERSION {
+            player_state.current = *swim::ID;
         } else if !body.is_on_ground {
             player_state.current = *midair::ID;
         } else if control.move_direction.y < -0.5 {
@@ -45,11 +47,9 @@ pub fn handle_player_state(
     mut sprites: CompMut<AnimationBankSprite>,
     mut bodies: CompMut<KinematicBody>,
     mut audio_center: ResMut<AudioCenter>,
-    collision_world: CollisionWorld,
-    slippery: CompMut<Slippery>,
 ) {
     let players = entities.iter_with((&player_states, &player_indexes, &mut sprites, &mut bodies));
-    for (player_ent, (player_state, player_idx, animation, body)) in players {
+    for (_player_ent, (player_state, player_idx, animation, body)) in players {
         if player_state.current != *ID {
             continue;
         }
@@ -73,15 +73,8 @@ pub fn handle_player_state(
             body.velocity.y = meta.stats.jump_speed;
         }
 
-        let mut slide_factor = 1.;
-        for (slippery_ent, slippery_meta) in entities.iter_with(&slippery) {
-            if collision_world
-                .actor_collisions(player_ent)
-                .contains(&slippery_ent)
-            {
-                slide_factor = 1. / slippery_meta.player_slide;
-            }
-        }
+        // Slide further on slippery materials
+        let slide_factor = body.material.friction;
 
         // Since we are idling, slide
         if body.velocity.x != 0.0 {
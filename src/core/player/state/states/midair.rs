@@ -15,10 +15,13 @@ pub fn player_state_transition(
     assets: Res<AssetServer>,
     mut player_states: CompMut<PlayerState>,
     bodies: Comp<KinematicBody>,
+    player_abilities: Comp<PlayerAbilities>,
+    transforms: Comp<Transform>,
+    collision_world: CollisionWorld,
     mut audio_center: ResMut<AudioCenter>,
 ) {
-    for (_ent, (player_idx, player_state, body)) in
-        entities.iter_with((&player_indexes, &mut player_states, &bodies))
+    for (ent, (player_idx, player_state, body, transform)) in
+        entities.iter_with((&player_indexes, &mut player_states, &bodies, &transforms))
     {
         let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
         let meta = assets.get(meta_handle);
@@ -26,15 +29,36 @@ pub fn player_state_transition(
         if player_state.current != *ID {
             continue;
         }
+        let abilities = player_abilities.get(ent);
+        let double_jump_ready = abilities.is_some_and(|x| x.double_jump.is_ready());
+        let air_dash_ready = abilities.is_some_and(|x| x.air_dash.is_ready());
+        let ground_pound_ready = abilities.is_some_and(|x| x.ground_pound.is_ready());
 
         if body.is_on_ground {
             // Play land sound
             audio_center.play_sound(meta.sounds.land, meta.sounds.land_volume);
             // Switch to idle state
             player_state.current = *idle::ID;
+        } else if body.submersion >= swim::SWIM_SUBMERSION {
+            player_state.current = *swim::ID;
         } else if control.ragdoll_just_pressed {
             // TODO audio
             player_state.current = *ragdoll::ID;
+        } else if body.velocity.y <= 0.0
+            && wall_slide::wall_direction(&collision_world, body, *transform)
+                .is_some_and(|wall| wall * control.move_direction.x > 0.0)
+        {
+            // Grab onto the wall that we are falling along and moving into
+            player_state.current = *wall_slide::ID;
+        } else if control.jump_just_pressed && double_jump_ready {
+            player_state.current = *double_jump::ID;
+        } else if control.slide_just_pressed
+            && control.move_direction.y < -0.5
+            && ground_pound_ready
+        {
+            player_state.current = *ground_pound::ID;
+        } else if control.slide_just_pressed && air_dash_ready {
+            player_state.current = *air_dash::ID;
         }
     }
 }
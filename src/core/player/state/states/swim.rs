@@ -0,0 +1,98 @@
+use super::*;
+
+pub static ID: Lazy<Ustr> = Lazy::new(|| ustr("core::swim"));
+
+/// How much of the player has to be under water for them to start swimming. See
+/// [`KinematicBody::submersion`].
+pub const SWIM_SUBMERSION: f32 = 0.5;
+
+pub fn install(session: &mut Session) {
+    PlayerState::add_player_state_transition_system(session, player_state_transition);
+    PlayerState::add_player_state_update_system(session, handle_player_state);
+    PlayerState::add_player_state_update_system(session, use_drop_or_grab_items_system(*ID));
+}
+
+pub fn player_state_transition(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    mut player_states: CompMut<PlayerState>,
+    bodies: Comp<KinematicBody>,
+) {
+    for (_ent, (player_idx, player_state, body)) in
+        entities.iter_with((&player_indexes, &mut player_states, &bodies))
+    {
+        if player_state.current != *ID {
+            continue;
+        }
+        let control = &player_inputs.players[player_idx.0 as usize].control;
+
+        // Keep swimming while standing on the bottom of the water, until the player gets out of it.
+        if body.submersion < SWIM_SUBMERSION {
+            player_state.current = if body.is_on_ground {
+                *idle::ID
+            } else {
+                *midair::ID
+            };
+        } else if control.ragdoll_just_pressed {
+            player_state.current = *ragdoll::ID;
+        }
+    }
+}
+
+pub fn handle_player_state(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    player_states: Comp<PlayerState>,
+    assets: Res<AssetServer>,
+    mut sprites: CompMut<AtlasSprite>,
+    mut animations: CompMut<AnimationBankSprite>,
+    mut bodies: CompMut<KinematicBody>,
+    mut audio_center: ResMut<AudioCenter>,
+) {
+    let players = entities.iter_with((
+        &player_states,
+        &player_indexes,
+        &mut animations,
+        &mut sprites,
+        &mut bodies,
+    ));
+    for (_player_ent, (player_state, player_idx, animation, sprite, body)) in players {
+        if player_state.current != *ID {
+            continue;
+        }
+        let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let meta = assets.get(meta_handle);
+        let control = &player_inputs.players[player_idx.0 as usize].control;
+
+        animation.current = "swim".into();
+
+        // Swim in the movement direction. The water's drag slows the player down when they stop.
+        if body.velocity.length() < meta.stats.swim_speed {
+            body.velocity += meta.stats.accel_swim_speed * control.move_direction;
+            body.velocity = body.velocity.clamp_length_max(meta.stats.swim_speed);
+        }
+
+        // Kick upwards, or leap out of the water if we are at the surface
+        if control.jump_just_pressed {
+            audio_center.play_sound(meta.sounds.jump, meta.sounds.jump_volume);
+
+            body.velocity.y = if body.submersion < 1.0 {
+                meta.stats.jump_speed
+            } else {
+                body.velocity.y.max(meta.stats.swim_speed)
+            };
+        }
+
+        // Swim down through platforms
+        body.fall_through = control.move_direction.y < -0.5;
+
+        // Point in movement direction
+        if control.move_direction.x > 0.0 {
+            sprite.flip_x = false;
+        } else if control.move_direction.x < 0.0 {
+            sprite.flip_x = true;
+        }
+    }
+}
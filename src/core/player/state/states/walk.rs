@@ -26,6 +26,8 @@ pub fn player_state_transition(
 
         if control.ragdoll_just_pressed {
             player_state.current = *ragdoll::ID;
+        } else if body.submersion >= swim::SWIM_SUBMERSION {
+            player_state.current = *swim::ID;
         } else if !body.is_on_ground {
             player_state.current = *midair::ID;
         } else if control.move_direction.y < -0.5 {
@@ -76,18 +78,19 @@ pub fn handle_player_state(
             body.velocity.y = meta.stats.jump_speed;
         }
 
-        // Walk in movement direction
-        body.velocity.x += meta.stats.accel_walk_speed * control.move_direction.x;
+        // Walk in movement direction, as fast as the ground's material lets us
+        let speed = body.material.speed;
+        body.velocity.x += meta.stats.accel_walk_speed * speed * control.move_direction.x;
         if control.move_direction.x.is_sign_positive() {
             body.velocity.x = body
                 .velocity
                 .x
-                .min(meta.stats.walk_speed * control.move_direction.x);
+                .min(meta.stats.walk_speed * speed * control.move_direction.x);
         } else {
             body.velocity.x = body
                 .velocity
                 .x
-                .max(meta.stats.walk_speed * control.move_direction.x);
+                .max(meta.stats.walk_speed * speed * control.move_direction.x);
         }
 
         // Point in movement direction
@@ -0,0 +1,96 @@
+use super::*;
+
+pub static ID: Lazy<Ustr> = Lazy::new(|| ustr("core::wall_jump"));
+
+pub fn install(session: &mut Session) {
+    PlayerState::add_player_state_transition_system(session, player_state_transition);
+    PlayerState::add_player_state_update_system(session, handle_player_state);
+    PlayerState::add_player_state_update_system(session, use_drop_or_grab_items_system(*ID));
+}
+
+pub fn player_state_transition(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    assets: Res<AssetServer>,
+    mut player_states: CompMut<PlayerState>,
+    bodies: Comp<KinematicBody>,
+) {
+    for (_ent, (player_idx, player_state, body)) in
+        entities.iter_with((&player_indexes, &mut player_states, &bodies))
+    {
+        if player_state.current != *ID {
+            continue;
+        }
+        let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let meta = assets.get(meta_handle);
+        let control = &player_inputs.players[player_idx.0 as usize].control;
+
+        // The jump off of the wall is made in the first frame of the state, so the state always
+        // lasts at least one frame.
+        if player_state.age == 0 {
+            continue;
+        }
+
+        if body.is_on_ground {
+            player_state.current = *idle::ID;
+        } else if body.submersion >= swim::SWIM_SUBMERSION {
+            player_state.current = *swim::ID;
+        } else if control.ragdoll_just_pressed {
+            player_state.current = *ragdoll::ID;
+        } else if player_state.age >= meta.stats.wall_jump_lockout_frames as u64 {
+            // Movement input is ignored until the lockout is over, so that the player doesn't
+            // steer straight back into the wall.
+            player_state.current = *midair::ID;
+        }
+    }
+}
+
+pub fn handle_player_state(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    player_states: Comp<PlayerState>,
+    assets: Res<AssetServer>,
+    transforms: Comp<Transform>,
+    collision_world: CollisionWorld,
+    mut sprites: CompMut<AtlasSprite>,
+    mut animations: CompMut<AnimationBankSprite>,
+    mut bodies: CompMut<KinematicBody>,
+    mut audio_center: ResMut<AudioCenter>,
+) {
+    let players = entities.iter_with((
+        &player_states,
+        &player_indexes,
+        &transforms,
+        &mut animations,
+        &mut sprites,
+        &mut bodies,
+    ));
+    for (_player_ent, (player_state, player_idx, transform, animation, sprite, body)) in players {
+        if player_state.current != *ID {
+            continue;
+        }
+        let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let meta = assets.get(meta_handle);
+
+        // If this is the first frame of this state, jump away from the wall
+        if player_state.age == 0 {
+            audio_center.play_sound(meta.sounds.jump, meta.sounds.jump_volume);
+
+            let wall = wall_slide::wall_direction(&collision_world, body, *transform)
+                .unwrap_or(if sprite.flip_x { -1.0 } else { 1.0 });
+            body.velocity = vec2(
+                -wall * meta.stats.wall_jump_velocity.x,
+                meta.stats.wall_jump_velocity.y,
+            );
+            sprite.flip_x = wall > 0.0;
+        }
+
+        if body.velocity.y > 0.0 {
+            animation.current = "rise".into();
+        } else {
+            animation.current = "fall".into();
+        }
+    }
+}
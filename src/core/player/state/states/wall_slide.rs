@@ -0,0 +1,111 @@
+use super::*;
+
+pub static ID: Lazy<Ustr> = Lazy::new(|| ustr("core::wall_slide"));
+
+/// How far past the side of the player's body a wall is looked for.
+const WALL_DISTANCE: f32 = 1.0;
+
+pub fn install(session: &mut Session) {
+    PlayerState::add_player_state_transition_system(session, player_state_transition);
+    PlayerState::add_player_state_update_system(session, handle_player_state);
+    PlayerState::add_player_state_update_system(session, use_drop_or_grab_items_system(*ID));
+}
+
+/// Get the side of the player's body that is against a wall, `-1.0` for the left and `1.0` for
+/// the right, or `None` if the player isn't against a wall.
+///
+/// Walls are solid map tiles and [`Solid`]s that are beside both the middle and the top of the
+/// body, so that the player doesn't catch on the corners of ledges.
+pub fn wall_direction(
+    collision_world: &CollisionWorld,
+    body: &KinematicBody,
+    transform: Transform,
+) -> Option<f32> {
+    let rect = body.bounding_box(transform);
+    let heights = [rect.center().y, rect.top() - 1.0];
+    [-1.0, 1.0].into_iter().find(|&direction| {
+        let x = if direction < 0.0 {
+            rect.left() - WALL_DISTANCE
+        } else {
+            rect.right() + WALL_DISTANCE
+        };
+        heights
+            .iter()
+            .all(|&y| collision_world.solid_at(vec2(x, y)))
+    })
+}
+
+pub fn player_state_transition(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    mut player_states: CompMut<PlayerState>,
+    bodies: Comp<KinematicBody>,
+    transforms: Comp<Transform>,
+    collision_world: CollisionWorld,
+) {
+    for (_ent, (player_idx, player_state, body, transform)) in
+        entities.iter_with((&player_indexes, &mut player_states, &bodies, &transforms))
+    {
+        if player_state.current != *ID {
+            continue;
+        }
+        let control = &player_inputs.players[player_idx.0 as usize].control;
+        let wall = wall_direction(&collision_world, body, *transform);
+
+        if body.is_on_ground {
+            player_state.current = *idle::ID;
+        } else if body.submersion >= swim::SWIM_SUBMERSION {
+            player_state.current = *swim::ID;
+        } else if control.ragdoll_just_pressed {
+            player_state.current = *ragdoll::ID;
+        } else if control.jump_just_pressed && wall.is_some() {
+            player_state.current = *wall_jump::ID;
+        } else if !wall.is_some_and(|wall| wall * control.move_direction.x > 0.0) {
+            // Let go of the wall when it ends, or when the player stops moving into it
+            player_state.current = *midair::ID;
+        }
+    }
+}
+
+pub fn handle_player_state(
+    entities: Res<Entities>,
+    player_inputs: Res<MatchInputs>,
+    player_indexes: Comp<PlayerIdx>,
+    player_states: Comp<PlayerState>,
+    assets: Res<AssetServer>,
+    mut sprites: CompMut<AtlasSprite>,
+    mut animations: CompMut<AnimationBankSprite>,
+    mut bodies: CompMut<KinematicBody>,
+) {
+    let players = entities.iter_with((
+        &player_states,
+        &player_indexes,
+        &mut animations,
+        &mut sprites,
+        &mut bodies,
+    ));
+    for (_player_ent, (player_state, player_idx, animation, sprite, body)) in players {
+        if player_state.current != *ID {
+            continue;
+        }
+        let meta_handle = player_inputs.players[player_idx.0 as usize].selected_player;
+        let meta = assets.get(meta_handle);
+        let control = &player_inputs.players[player_idx.0 as usize].control;
+
+        if body.velocity.y > 0.0 {
+            animation.current = "rise".into();
+        } else {
+            animation.current = "fall".into();
+        }
+
+        // Slide down the wall slowly
+        body.velocity.y = body.velocity.y.max(-meta.stats.wall_slide_speed);
+
+        // Keep pressing against the wall
+        body.velocity.x = control.move_direction.x * meta.stats.accel_air_speed;
+
+        // Face the wall
+        sprite.flip_x = control.move_direction.x < 0.0;
+    }
+}
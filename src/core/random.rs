@@ -24,9 +24,16 @@ pub struct GlobalRng(AtomicRng);
 
 pub const DEFAULT_RANDOM_SEED: u32 = 7;
 
+impl GlobalRng {
+    /// Create a new random number generator with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self(AtomicRng::with_seed(seed))
+    }
+}
+
 impl Default for GlobalRng {
     fn default() -> Self {
-        Self(AtomicRng::with_seed(DEFAULT_RANDOM_SEED as u64))
+        Self::with_seed(DEFAULT_RANDOM_SEED as u64)
     }
 }
 
@@ -0,0 +1,560 @@
+//! Recording and playback of match inputs.
+//!
+//! The match simulation runs at a fixed [`FPS`], uses deterministic rapier physics, and gets all of
+//! its randomness from the seeded [`GlobalRng`]. That means a match can be reproduced exactly from
+//! its setup and the player controls of every frame, which is all that a [`Replay`] contains.
+//!
+//! Replays are recorded by a [`ReplayRecorder`] and played back with a [`ReplayPlayback`], both of
+//! which are driven by a [`SessionRunner`]. [`ReplayRunner`] plays a replay back in real time.
+//!
+//! > **Note:** Editor inputs are not recorded, so matches where the map was edited can't be
+//! > replayed.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::prelude::*;
+
+/// The version of the replay file format. Increased every time the format changes.
+pub const REPLAY_FORMAT_VERSION: u32 = 3;
+
+/// Environment variable that may be set to a file path to record local matches to.
+pub const RECORD_REPLAY_ENV_VAR: &str = "JUMPY_RECORD_REPLAY";
+
+/// Errors that may occur when saving or loading a [`Replay`].
+#[derive(thiserror::Error, Debug)]
+pub enum ReplayError {
+    #[error("Could not access replay file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not encode or decode replay: {0}")]
+    Encoding(postcard::Error),
+    #[error("Replay format version {0} is not supported")]
+    UnsupportedVersion(u32),
+}
+
+/// A recording of a match, containing everything needed to simulate it again.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Replay {
+    /// The [`REPLAY_FORMAT_VERSION`] the replay was saved with.
+    pub version: u32,
+    /// The version of the game the replay was recorded with.
+    pub game_version: String,
+    /// The maps the match was started with.
+    pub maps: MapPoolNetwork,
+    /// The setup of each player slot.
+    pub players: [ReplayPlayer; MAX_PLAYERS as usize],
+    /// The lua plugins that were enabled for the match.
+    pub plugins: Vec<NetworkHandle<LuaPlugin>>,
+    /// The seed that the [`GlobalRng`] is reset to at the start of every round.
+    pub seed: u64,
+    /// The rules the match was played with.
+    pub rules: MatchRules,
+    /// The recorded player controls, run-length encoded.
+    pub frames: Vec<ReplayFrames>,
+}
+
+/// The setup of a player slot in a [`Replay`].
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReplayPlayer {
+    pub active: bool,
+    pub is_ai: bool,
+    pub selected_player: NetworkHandle<PlayerMeta>,
+    pub selected_hat: Option<NetworkHandle<HatMeta>>,
+}
+
+/// A run of consecutive frames in a [`Replay`] that all have the same player controls.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ReplayFrames {
+    /// The number of frames in the run.
+    pub count: u32,
+    /// The controls for every player during the run.
+    pub controls: [ReplayControl; MAX_PLAYERS as usize],
+}
+
+bitflags::bitflags! {
+    /// The buttons of a [`PlayerControl`], packed for storage in a [`ReplayControl`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct ReplayButtons: u32 {
+        const JUMP = 1 << 0;
+        const JUMP_JUST = 1 << 1;
+        const SHOOT = 1 << 2;
+        const SHOOT_JUST = 1 << 3;
+        const GRAB = 1 << 4;
+        const GRAB_JUST = 1 << 5;
+        const SLIDE = 1 << 6;
+        const SLIDE_JUST = 1 << 7;
+        const RAGDOLL = 1 << 8;
+        const RAGDOLL_JUST = 1 << 9;
+        const PAUSE = 1 << 10;
+        const PAUSE_JUST = 1 << 11;
+        const MENU_BACK = 1 << 12;
+        const MENU_BACK_JUST = 1 << 13;
+        const MENU_CONFIRM = 1 << 14;
+        const MENU_CONFIRM_JUST = 1 << 15;
+        const MENU_START = 1 << 16;
+        const MENU_START_JUST = 1 << 17;
+        const MOVING = 1 << 18;
+        const JUST_MOVED = 1 << 19;
+    }
+}
+
+/// A [`PlayerControl`] as it is stored in a [`Replay`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReplayControl {
+    buttons: u32,
+    /// The `left`, `right`, `up`, and `down` axes.
+    axes: [f32; 4],
+    move_direction: [f32; 2],
+}
+
+impl From<&PlayerControl> for ReplayControl {
+    fn from(control: &PlayerControl) -> Self {
+        let mut buttons = ReplayButtons::empty();
+        for (flag, pressed) in [
+            (ReplayButtons::JUMP, control.jump_pressed),
+            (ReplayButtons::JUMP_JUST, control.jump_just_pressed),
+            (ReplayButtons::SHOOT, control.shoot_pressed),
+            (ReplayButtons::SHOOT_JUST, control.shoot_just_pressed),
+            (ReplayButtons::GRAB, control.grab_pressed),
+            (ReplayButtons::GRAB_JUST, control.grab_just_pressed),
+            (ReplayButtons::SLIDE, control.slide_pressed),
+            (ReplayButtons::SLIDE_JUST, control.slide_just_pressed),
+            (ReplayButtons::RAGDOLL, control.ragdoll_pressed),
+            (ReplayButtons::RAGDOLL_JUST, control.ragdoll_just_pressed),
+            (ReplayButtons::PAUSE, control.pause_pressed),
+            (ReplayButtons::PAUSE_JUST, control.pause_just_pressed),
+            (ReplayButtons::MENU_BACK, control.menu_back_pressed),
+            (
+                ReplayButtons::MENU_BACK_JUST,
+                control.menu_back_just_pressed,
+            ),
+            (ReplayButtons::MENU_CONFIRM, control.menu_confirm_pressed),
+            (
+                ReplayButtons::MENU_CONFIRM_JUST,
+                control.menu_confirm_just_pressed,
+            ),
+            (ReplayButtons::MENU_START, control.menu_start_pressed),
+            (
+                ReplayButtons::MENU_START_JUST,
+                control.menu_start_just_pressed,
+            ),
+            (ReplayButtons::MOVING, control.moving),
+            (ReplayButtons::JUST_MOVED, control.just_moved),
+        ] {
+            buttons.set(flag, pressed);
+        }
+
+        Self {
+            buttons: buttons.bits(),
+            axes: [control.left, control.right, control.up, control.down],
+            move_direction: control.move_direction.to_array(),
+        }
+    }
+}
+
+impl From<&ReplayControl> for PlayerControl {
+    fn from(control: &ReplayControl) -> Self {
+        let buttons = ReplayButtons::from_bits_truncate(control.buttons);
+        let [left, right, up, down] = control.axes;
+        PlayerControl {
+            left,
+            right,
+            up,
+            down,
+            move_direction: Vec2::from_array(control.move_direction),
+            just_moved: buttons.contains(ReplayButtons::JUST_MOVED),
+            moving: buttons.contains(ReplayButtons::MOVING),
+            menu_back_pressed: buttons.contains(ReplayButtons::MENU_BACK),
+            menu_back_just_pressed: buttons.contains(ReplayButtons::MENU_BACK_JUST),
+            menu_confirm_pressed: buttons.contains(ReplayButtons::MENU_CONFIRM),
+            menu_confirm_just_pressed: buttons.contains(ReplayButtons::MENU_CONFIRM_JUST),
+            menu_start_pressed: buttons.contains(ReplayButtons::MENU_START),
+            menu_start_just_pressed: buttons.contains(ReplayButtons::MENU_START_JUST),
+            pause_pressed: buttons.contains(ReplayButtons::PAUSE),
+            pause_just_pressed: buttons.contains(ReplayButtons::PAUSE_JUST),
+            jump_pressed: buttons.contains(ReplayButtons::JUMP),
+            jump_just_pressed: buttons.contains(ReplayButtons::JUMP_JUST),
+            shoot_pressed: buttons.contains(ReplayButtons::SHOOT),
+            shoot_just_pressed: buttons.contains(ReplayButtons::SHOOT_JUST),
+            grab_pressed: buttons.contains(ReplayButtons::GRAB),
+            grab_just_pressed: buttons.contains(ReplayButtons::GRAB_JUST),
+            slide_pressed: buttons.contains(ReplayButtons::SLIDE),
+            slide_just_pressed: buttons.contains(ReplayButtons::SLIDE_JUST),
+            ragdoll_pressed: buttons.contains(ReplayButtons::RAGDOLL),
+            ragdoll_just_pressed: buttons.contains(ReplayButtons::RAGDOLL_JUST),
+        }
+    }
+}
+
+impl Replay {
+    /// Create an empty replay for the match running in the given world.
+    ///
+    /// The world must contain the resources inserted by the [`MatchPlugin`].
+    pub fn new(world: &World, seed: u64) -> Self {
+        let assets = world.resource::<AssetServer>();
+        let match_inputs = world.resource::<MatchInputs>();
+
+        Self {
+            version: REPLAY_FORMAT_VERSION,
+            game_version: env!("CARGO_PKG_VERSION").into(),
+            maps: world.resource::<MapPool>().into_network(&assets),
+            players: std::array::from_fn(|i| {
+                let player = &match_inputs.players[i];
+                ReplayPlayer {
+                    active: player.active,
+                    is_ai: player.is_ai,
+                    selected_player: player.selected_player.network_handle(&assets),
+                    selected_hat: player.selected_hat.map(|h| h.network_handle(&assets)),
+                }
+            }),
+            plugins: world
+                .resource::<LuaPlugins>()
+                .0
+                .iter()
+                .map(|h| h.network_handle(&assets))
+                .collect(),
+            seed,
+            rules: world.resource::<MatchRules>().clone(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Load a replay from a file.
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let bytes = std::fs::read(path)?;
+        let replay: Replay = postcard::from_bytes(&bytes).map_err(ReplayError::Encoding)?;
+
+        if replay.version != REPLAY_FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedVersion(replay.version));
+        }
+        if replay.game_version != env!("CARGO_PKG_VERSION") {
+            warn!(
+                "Replay was recorded with game version {}, it may not play back correctly.",
+                replay.game_version
+            );
+        }
+
+        Ok(replay)
+    }
+
+    /// Save the replay to a file.
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let bytes = postcard::to_allocvec(self).map_err(ReplayError::Encoding)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Append a frame with the current player controls to the replay.
+    pub fn push_frame(&mut self, inputs: &MatchInputs) {
+        let controls = std::array::from_fn(|i| (&inputs.players[i].control).into());
+
+        match self.frames.last_mut() {
+            Some(run) if run.controls == controls => run.count += 1,
+            _ => self.frames.push(ReplayFrames { count: 1, controls }),
+        }
+    }
+
+    /// Get the total number of frames in the replay.
+    pub fn frame_count(&self) -> u32 {
+        self.frames.iter().map(|run| run.count).sum()
+    }
+
+    /// Get the player controls for the given frame.
+    pub fn get_frame(&self, frame: u32) -> Option<[PlayerControl; MAX_PLAYERS as usize]> {
+        let mut start = 0;
+        for run in &self.frames {
+            if frame < start + run.count {
+                return Some(std::array::from_fn(|i| (&run.controls[i]).into()));
+            }
+            start += run.count;
+        }
+        None
+    }
+
+    /// Create a [`MatchPlugin`] that will start the recorded match with the given session runner.
+    ///
+    /// The runner is responsible for feeding the replay frames into the match, see
+    /// [`ReplayPlayback`].
+    pub fn match_plugin(
+        &self,
+        assets: &AssetServer,
+        session_runner: Box<dyn SessionRunner>,
+    ) -> MatchPlugin {
+        MatchPlugin {
+            maps: MapPool::from_network(self.maps.clone(), assets),
+            player_info: std::array::from_fn(|i| {
+                let player = &self.players[i];
+                PlayerInput {
+                    active: player.active,
+                    selected_player: player.selected_player.into_handle(assets),
+                    selected_hat: player.selected_hat.as_ref().map(|h| h.into_handle(assets)),
+                    control: default(),
+                    editor_input: default(),
+                    control_source: None,
+                    is_ai: player.is_ai,
+                }
+            }),
+            plugins: Arc::new(self.plugins.iter().map(|h| h.into_handle(assets)).collect()),
+            score: default(),
+            rules: self.rules.clone(),
+            session_runner,
+        }
+    }
+}
+
+/// Records the inputs of a match into a [`Replay`], saving it to a file when dropped if it has a
+/// [`path`][Self::path].
+///
+/// Session runners should call [`record_frame`][Self::record_frame] before every simulation step,
+/// and [`restart_round`][Self::restart_round] when their session is restarted.
+pub struct ReplayRecorder {
+    /// The file that the replay is saved to.
+    pub path: Option<PathBuf>,
+    /// The seed to reset the [`GlobalRng`] to at the start of every round.
+    pub seed: u64,
+    replay: Option<Arc<Mutex<Replay>>>,
+    round_frame: u64,
+}
+
+/// Resource containing the replay being recorded for the current match, if any.
+///
+/// This allows the replay to be watched while the match is still in progress.
+#[derive(HasSchema, Clone)]
+#[schema(no_default)]
+pub struct ReplayRecording(pub Arc<Mutex<Replay>>);
+
+impl ReplayRecorder {
+    /// Create a recorder that will save the replay to the given path, or only keep it in memory if
+    /// there is no path.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            seed: DEFAULT_RANDOM_SEED as u64,
+            replay: None,
+            round_frame: 0,
+        }
+    }
+
+    /// Create a recorder that will save the replay to the path in the [`RECORD_REPLAY_ENV_VAR`]
+    /// environment variable, if it is set.
+    pub fn from_env() -> Self {
+        Self::new(std::env::var_os(RECORD_REPLAY_ENV_VAR).map(PathBuf::from))
+    }
+
+    /// Get a copy of the replay recorded so far.
+    pub fn replay(&self) -> Option<Replay> {
+        self.replay
+            .as_ref()
+            .map(|replay| replay.lock().unwrap().clone())
+    }
+
+    /// Record the current [`MatchInputs`] for the frame that is about to be simulated.
+    pub fn record_frame(&mut self, world: &mut World) {
+        if is_round_over(world) {
+            return;
+        }
+        if self.round_frame == 0 {
+            world.insert_resource(GlobalRng::with_seed(self.seed));
+        }
+        let replay = self
+            .replay
+            .get_or_insert_with(|| Arc::new(Mutex::new(Replay::new(world, self.seed))))
+            .clone();
+        if self.round_frame == 0 {
+            world.insert_resource(ReplayRecording(replay.clone()));
+        }
+        replay
+            .lock()
+            .unwrap()
+            .push_frame(&world.resource::<MatchInputs>());
+        self.round_frame += 1;
+    }
+
+    /// Notify the recorder that the round has been restarted.
+    pub fn restart_round(&mut self) {
+        self.round_frame = 0;
+    }
+
+    /// Save the replay recorded so far, if the recorder has a path.
+    pub fn save(&self) -> Result<(), ReplayError> {
+        if let (Some(path), Some(replay)) = (&self.path, &self.replay) {
+            replay.lock().unwrap().save(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ReplayRecorder {
+    fn drop(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match self.save() {
+            Ok(()) => info!("Saved replay to {}", path.display()),
+            Err(e) => error!("Could not save replay to {}: {e}", path.display()),
+        }
+    }
+}
+
+/// Resource containing the progress of the replay being played back in the current session, if
+/// any.
+#[derive(HasSchema, Clone, Copy, Default, Debug)]
+pub struct ReplayProgress {
+    /// The number of frames that have been played back.
+    pub frame: u32,
+    /// Whether all of the frames of the replay have been played back.
+    pub finished: bool,
+}
+
+/// Plays back the inputs of a [`Replay`].
+///
+/// Session runners should call [`apply_frame`][Self::apply_frame] before every simulation step,
+/// and [`restart_round`][Self::restart_round] when their session is restarted.
+#[derive(Clone)]
+pub struct ReplayPlayback {
+    pub replay: Arc<Replay>,
+    /// The index of the current run in [`Replay::frames`].
+    run: usize,
+    /// The number of frames already played from the current run.
+    run_frame: u32,
+    /// The number of frames played in total.
+    frame: u32,
+    round_frame: u64,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Arc<Replay>) -> Self {
+        Self {
+            replay,
+            run: 0,
+            run_frame: 0,
+            frame: 0,
+            round_frame: 0,
+        }
+    }
+
+    /// Get the number of frames that have been played back.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Get the number of frames that have been played back since the start of the round.
+    pub fn round_frame(&self) -> u64 {
+        self.round_frame
+    }
+
+    /// Whether all of the frames of the replay have been played back.
+    pub fn is_finished(&self) -> bool {
+        self.run >= self.replay.frames.len()
+    }
+
+    /// Write the recorded controls of the next frame into the [`MatchInputs`], and update the
+    /// [`ReplayProgress`].
+    ///
+    /// Returns `false` without modifying the world if the replay or the round is finished.
+    pub fn apply_frame(&mut self, world: &mut World) -> bool {
+        if is_round_over(world) {
+            return false;
+        }
+        let Some(run) = self.replay.frames.get(self.run) else {
+            world.insert_resource(ReplayProgress {
+                frame: self.frame,
+                finished: true,
+            });
+            return false;
+        };
+
+        if self.round_frame == 0 {
+            world.insert_resource(GlobalRng::with_seed(self.replay.seed));
+        }
+
+        {
+            let mut match_inputs = world.resource_mut::<MatchInputs>();
+            for (player, control) in match_inputs.players.iter_mut().zip(&run.controls) {
+                // AI players will come up with the same controls on their own.
+                if !player.is_ai {
+                    player.control = control.into();
+                }
+            }
+        }
+
+        self.run_frame += 1;
+        if self.run_frame >= run.count {
+            self.run += 1;
+            self.run_frame = 0;
+        }
+        self.frame += 1;
+        self.round_frame += 1;
+        world.insert_resource(ReplayProgress {
+            frame: self.frame,
+            finished: self.is_finished(),
+        });
+
+        true
+    }
+
+    /// Notify the playback that the round has been restarted.
+    pub fn restart_round(&mut self) {
+        self.round_frame = 0;
+    }
+}
+
+/// Whether the round has ended and the session is about to be restarted.
+///
+/// Session runners may still simulate a few frames before the restart happens. Those frames are
+/// thrown away with the session, so they are not recorded.
+fn is_round_over(world: &World) -> bool {
+    world
+        .get_resource::<RoundScoringState>()
+        .map_or(false, |state| state.next_maps.is_some())
+}
+
+/// Session runner that plays a [`Replay`] back in real time.
+pub struct ReplayRunner {
+    pub playback: ReplayPlayback,
+    pub accumulator: f64,
+    pub last_run: Option<Instant>,
+}
+
+impl ReplayRunner {
+    pub fn new(replay: Arc<Replay>) -> Self {
+        Self {
+            playback: ReplayPlayback::new(replay),
+            accumulator: 0.0,
+            last_run: None,
+        }
+    }
+}
+
+impl SessionRunner for ReplayRunner {
+    fn step(&mut self, frame_start: Instant, world: &mut World, stages: &mut SystemStages) {
+        pub const STEP: f64 = 1.0 / FPS as f64;
+        let last_run = self.last_run.unwrap_or(frame_start);
+        self.accumulator += (frame_start - last_run).as_secs_f64();
+        self.last_run = Some(frame_start);
+
+        while self.accumulator >= STEP {
+            self.accumulator -= STEP;
+
+            if !self.playback.apply_frame(world) {
+                self.accumulator = 0.0;
+                break;
+            }
+
+            world
+                .resource_mut::<Time>()
+                .advance_exact(Duration::from_secs_f64(STEP));
+            stages.run(world);
+        }
+    }
+
+    fn restart_session(&mut self) {
+        self.playback.restart_round();
+        self.accumulator = 0.0;
+        self.last_run = None;
+    }
+
+    fn disable_local_input(&mut self, _disable_input: bool) {}
+}
@@ -0,0 +1,31 @@
+//! Rules that may be changed for each match.
+
+use crate::prelude::*;
+
+/// Resource containing the rules of the current match.
+///
+/// The rules are chosen when the match is started with the [`MatchPlugin`][super::MatchPlugin],
+/// and are kept when the match moves on to the next round.
+#[derive(HasSchema, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct MatchRules {
+    /// Whether players are killed by the first hit that they take.
+    ///
+    /// When this is turned off, players have [`PlayerHealth`] and are only killed once it runs
+    /// out, and armor such as the stomp boots can absorb hits.
+    pub one_hit_kill: bool,
+    /// The number of lives that each player has in a round.
+    ///
+    /// Killed players respawn until they run out of lives, and the round ends when one or fewer
+    /// players have lives left, see [`PlayerLives`].
+    pub lives: u32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            one_hit_kill: true,
+            lives: 1,
+        }
+    }
+}
@@ -38,6 +38,32 @@ impl RoundScoringState {
     }
 }
 
+/// Resource tracking the lives that the players have lost in the current round.
+///
+/// Players are killed for good once they have lost all of the [`lives`][MatchRules::lives] from
+/// the match rules, otherwise they respawn.
+#[derive(HasSchema, Clone, Default, Debug)]
+pub struct PlayerLives {
+    /// The number of lives that each player has lost.
+    lost: [u32; MAX_PLAYERS as usize],
+}
+
+impl PlayerLives {
+    /// Get the number of lives that the player has left.
+    pub fn remaining(&self, player: PlayerIdx, rules: &MatchRules) -> u32 {
+        rules
+            .lives
+            .max(1)
+            .saturating_sub(self.lost[player.0 as usize])
+    }
+
+    /// Take one of the player's lives.
+    pub fn lose_life(&mut self, player: PlayerIdx) {
+        let lost = &mut self.lost[player.0 as usize];
+        *lost = lost.saturating_add(1);
+    }
+}
+
 /// Store player's match score's (rounds won)
 #[derive(HasSchema, Clone, Default, Debug)]
 pub struct MatchScore {
@@ -95,6 +121,9 @@ pub fn round_end(
     mut scoring_menu: ResMut<ScoringMenuState>,
     killed_players: Comp<PlayerKilled>,
     player_indices: Comp<PlayerIdx>,
+    player_inputs: Res<MatchInputs>,
+    rules: Res<MatchRules>,
+    lives: ResInit<PlayerLives>,
     #[cfg(not(target_arch = "wasm32"))] syncing_info: Option<Res<SyncingInfo>>,
 ) {
     // Count players so we can avoid ending round if it's a one player match
@@ -103,19 +132,35 @@ pub fn round_end(
     // Is Some if one player left, or none if all players dead.
     // Exits function if >= 2 players left: otherwise we handle continue to handle
     // round scoring.
-    let last_player_or_draw: Option<(PlayerIdx, Entity)> = {
-        let mut last_player: Option<(PlayerIdx, Entity)> = None;
-        for (ent, (player_idx, killed)) in
-            entities.iter_with((&player_indices, &Optional(&killed_players)))
+    //
+    // Killed players are still in the round while they have lives left to respawn with. The
+    // entity is `None` for players that are respawning, which don't have a player entity for a
+    // moment.
+    let last_player_or_draw: Option<(PlayerIdx, Option<Entity>)> = {
+        let mut last_player: Option<(PlayerIdx, Option<Entity>)> = None;
+        for (i, _) in player_inputs
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, player)| player.active)
         {
-            player_count += 1;
-            if killed.is_none() {
+            let player_idx = PlayerIdx(i as u32);
+            let player_ent = entities
+                .iter_with(&player_indices)
+                .find(|(_, idx)| **idx == player_idx)
+                .map(|(ent, _)| ent);
+            if player_ent.is_some() {
+                player_count += 1;
+            }
+
+            let is_alive = player_ent.is_some_and(|ent| !killed_players.contains(ent));
+            if is_alive || lives.remaining(player_idx, &rules) > 0 {
                 if last_player.is_some() {
                     // At least two players alive, not the round end.
                     return;
                 }
 
-                last_player = Some((*player_idx, ent));
+                last_player = Some((player_idx, player_ent));
             }
         }
 
@@ -123,8 +168,8 @@ pub fn round_end(
         last_player
     };
 
-    if player_count == 1 {
-        // Single player match - don't end round.
+    if player_count <= 1 {
+        // Single player match or map editor - don't end round.
         return;
     }
 
@@ -140,7 +185,7 @@ pub fn round_end(
         state.round_scored = true;
         score.complete_round(last_player_or_draw.map(|x| x.0));
 
-        if let Some((_, winner_ent)) = last_player_or_draw {
+        if let Some((_, Some(winner_ent))) = last_player_or_draw {
             // commands.add(PlayerCommand::won_round(winner));
             commands.add(spawn_win_indicator(winner_ent));
         }
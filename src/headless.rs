@@ -0,0 +1,216 @@
+//! Running matches without a window, renderer, or input devices.
+//!
+//! This is used by the `jumpy-headless` binary to simulate matches on machines without a GPU, for
+//! example to catch panics in element systems or to balance items on CI. The game is set up with
+//! only the plugins needed by the [`MatchPlugin`][crate::core::MatchPlugin], and every call to
+//! [`step`] advances the match by exactly one fixed frame.
+
+use std::path::Path;
+
+use crate::{prelude::*, ui::scoring::ScoringMenuState, PackMeta};
+
+/// Callback that may modify the [`MatchInputs`] before each simulated frame. It receives the
+/// number of frames simulated since the start of the current round.
+///
+/// This is how non-AI players are controlled in a headless match.
+pub type InputScript = Box<dyn FnMut(u64, &mut MatchInputs) + Sync + Send>;
+
+/// Create a bones [`Game`] that is ready to run headless matches, loading the game assets and asset
+/// packs from the given directories.
+pub fn new_game(asset_dir: &Path, packs_dir: &Path) -> Game {
+    // Initialize the Bevy task pool manually so that we can use it to load assets.
+    bevy_tasks::IoTaskPool::init(bevy_tasks::TaskPool::new);
+
+    // Register our game and pack meta types
+    GameMeta::register_schema();
+    PackMeta::register_schema();
+
+    let mut game = Game::new();
+    game
+        // Only install the plugins the match needs, the rest depend on a window or input devices.
+        .install_plugin(DefaultGamePlugin)
+        .install_plugin(crate::core::game_plugin)
+        .install_plugin(crate::ui::scoring::game_plugin)
+        .init_shared_resource::<AssetServer>()
+        .init_shared_resource::<AudioCenter>()
+        .register_default_assets();
+
+    // The camera controller needs a window size, so we pretend we have a 1080p window.
+    game.insert_shared_resource(Window {
+        size: vec2(1920.0, 1080.0),
+        fullscreen: false,
+    });
+
+    // Load all of the assets before we start so that matches can be started right away.
+    let asset_server = {
+        let mut asset_server = game.shared_resource_mut::<AssetServer>().unwrap();
+        asset_server.set_game_version(Version::new(
+            env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+            env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        ));
+        asset_server.set_io(FileAssetIo::new(asset_dir, packs_dir));
+        asset_server.clone()
+    };
+    bevy_tasks::block_on(asset_server.load_assets()).expect("Could not load game assets");
+
+    game
+}
+
+/// Get the handles of all the stable maps and all maps from the loaded asset packs.
+pub fn all_maps(asset_server: &AssetServer) -> Vec<Handle<MapMeta>> {
+    let game_meta = asset_server.root::<GameMeta>();
+
+    let mut map_handles = Vec::new();
+    map_handles.extend(game_meta.core.stable_maps.iter().copied());
+    for pack in asset_server.packs() {
+        let pack_meta = asset_server.get(pack.root.typed::<PackMeta>());
+        map_handles.extend(pack_meta.maps.iter().copied());
+    }
+    map_handles
+}
+
+/// Get player inputs for a match with `count` AI players, cycling through the available player
+/// skins.
+pub fn ai_players(game_meta: &GameMeta, count: usize) -> [PlayerInput; MAX_PLAYERS as usize] {
+    let players = &game_meta.core.players;
+    std::array::from_fn(|i| PlayerInput {
+        active: i < count,
+        selected_player: players[i % players.len()],
+        selected_hat: None,
+        control: default(),
+        editor_input: default(),
+        control_source: None,
+        is_ai: true,
+    })
+}
+
+/// Start a new headless match with the given rules, replacing the current one if there is any.
+///
+/// Players that are not AI players may be controlled with the runner's
+/// [`input_script`][HeadlessMatchRunner::input_script].
+pub fn start_match(
+    game: &mut Game,
+    maps: MapPool,
+    player_info: [PlayerInput; MAX_PLAYERS as usize],
+    rules: MatchRules,
+    runner: HeadlessMatchRunner,
+) {
+    let plugins = {
+        let asset_server = game.shared_resource::<AssetServer>().unwrap();
+        asset_server.root::<GameMeta>().get_plugins(&asset_server)
+    };
+
+    game.sessions.end_game();
+    game.sessions.start_game(crate::core::MatchPlugin {
+        maps,
+        player_info,
+        plugins,
+        score: default(),
+        rules,
+        session_runner: Box::new(runner),
+    });
+}
+
+/// Start playing back a replay, replacing the current match if there is any.
+///
+/// Rounds may be simulated for a few frames after they end, and those frames aren't part of the
+/// replay, so use [`replay_finished`] to tell when the whole replay has been simulated.
+pub fn start_replay(game: &mut Game, replay: Arc<Replay>) {
+    let match_plugin = {
+        let asset_server = game.shared_resource::<AssetServer>().unwrap();
+        replay.match_plugin(
+            &asset_server,
+            Box::new(HeadlessMatchRunner {
+                playback: Some(ReplayPlayback::new(replay.clone())),
+                ..default()
+            }),
+        )
+    };
+
+    game.sessions.end_game();
+    game.sessions.start_game(match_plugin);
+}
+
+/// Simulate a single frame of the headless match.
+pub fn step(game: &mut Game) {
+    game.step(Instant::now());
+
+    // There is no scoring menu to wait for, so we move on to the next round immediately when the
+    // match reaches an intermission.
+    let next_maps = {
+        let mut scoring_menu = game.shared_resource_mut::<ScoringMenuState>().unwrap();
+        if !scoring_menu.active {
+            return;
+        }
+        let next_maps = scoring_menu.next_maps.take();
+        scoring_menu.reset();
+        next_maps
+    };
+    game.sessions.restart_game(next_maps, false);
+}
+
+/// Whether all of the frames of the replay started with [`start_replay`] have been simulated.
+pub fn replay_finished(game: &Game) -> bool {
+    game.sessions
+        .get(SessionNames::GAME)
+        .and_then(|session| session.world.get_resource::<ReplayProgress>())
+        .map_or(false, |progress| progress.finished)
+}
+
+/// Get the checksum of the last frame simulated in the current match, if a match is running.
+///
+/// Comparing this between two runs of the same match is a quick way to check that they were
+/// simulated identically.
+pub fn world_checksum(game: &Game) -> Option<WorldChecksum> {
+    let session = game.sessions.get(SessionNames::GAME)?;
+    let checksums = session.world.get_resource::<WorldChecksums>()?;
+    checksums.history.back().map(|(_, checksum)| *checksum)
+}
+
+/// Session runner that advances the match by one fixed frame every time it is stepped, regardless
+/// of how much real time has passed.
+#[derive(Default)]
+pub struct HeadlessMatchRunner {
+    /// The number of frames simulated since the start of the round.
+    pub frame: u64,
+    /// Optional script used to control the non-AI players.
+    pub input_script: Option<InputScript>,
+    /// Replay to take the non-AI player controls from.
+    pub playback: Option<ReplayPlayback>,
+    /// Records the match to a replay file if set.
+    pub recorder: Option<ReplayRecorder>,
+}
+
+impl SessionRunner for HeadlessMatchRunner {
+    fn step(&mut self, _frame_start: Instant, world: &mut World, stages: &mut SystemStages) {
+        world
+            .resource_mut::<Time>()
+            .advance_exact(Duration::from_secs_f64(1.0 / FPS as f64));
+
+        if let Some(playback) = &mut self.playback {
+            playback.apply_frame(world);
+        }
+        if let Some(input_script) = &mut self.input_script {
+            input_script(self.frame, &mut world.resource_mut::<MatchInputs>());
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_frame(world);
+        }
+
+        stages.run(world);
+        self.frame += 1;
+    }
+
+    fn restart_session(&mut self) {
+        self.frame = 0;
+        if let Some(playback) = &mut self.playback {
+            playback.restart_round();
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.restart_round();
+        }
+    }
+
+    fn disable_local_input(&mut self, _disable_input: bool) {}
+}
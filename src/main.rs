@@ -1,39 +1,5 @@
-#![doc(html_logo_url = "https://avatars.githubusercontent.com/u/87333478?s=200&v=4")]
-// This cfg_attr is needed because `rustdoc::all` includes lints not supported on stable
-#![cfg_attr(doc, allow(unknown_lints))]
-#![deny(rustdoc::all)]
-#![allow(clippy::too_many_arguments)]
-// TODO: Warn on dead code.
-// This is temporarily disabled while migrating to the new bones.
-#![allow(dead_code)]
-#![allow(ambiguous_glob_reexports)]
-#![doc = include_str!("./README.md")]
-
 use bones_bevy_renderer::BonesBevyRenderer;
-use bones_framework::prelude::*;
-
-pub mod audio;
-pub mod core;
-pub mod debug;
-pub mod fullscreen;
-pub mod input;
-pub mod profiler;
-pub mod sessions;
-pub mod settings;
-pub mod ui;
-
-mod prelude {
-    pub use crate::{
-        audio::*, core::prelude::*, impl_system_param, input::*, sessions::*, settings::*, GameMeta,
-    };
-    pub use bones_framework::prelude::*;
-    pub use once_cell::sync::Lazy;
-    pub use serde::{Deserialize, Serialize};
-    pub use std::{sync::Arc, time::Duration};
-    #[allow(unused)]
-    pub use tracing::{debug, error, info, trace, warn};
-}
-use crate::prelude::*;
+use jumpy::{audio, debug, fullscreen, input, prelude::*, profiler, settings, ui, PackMeta};
 
 // This will cause Bevy to be dynamically linked during development,
 // which can greatly reduce re-compile times in some circumstances.
@@ -42,99 +8,6 @@ use crate::prelude::*;
 #[allow(clippy::single_component_path_imports)]
 use bevy_dylib;
 
-#[derive(HasSchema, Clone, Debug, Default)]
-#[type_data(metadata_asset("game"))]
-#[repr(C)]
-pub struct GameMeta {
-    pub plugins: SVec<Handle<LuaPlugin>>,
-    pub core: CoreMeta,
-    pub default_settings: settings::Settings,
-    pub localization: Handle<LocalizationAsset>,
-    pub theme: ui::UiTheme,
-    pub main_menu: ui::main_menu::MainMenuMeta,
-    pub music: GameMusic,
-    pub network: NetworkMeta,
-}
-
-#[derive(HasSchema, Copy, Clone, Debug)]
-#[repr(C)]
-pub struct NetworkMeta {
-    pub max_prediction_window: usize,
-    pub local_input_delay: usize,
-}
-
-// In wasm build get derivable_impls clippy warning which breaks CI
-#[allow(clippy::derivable_impls)]
-impl Default for NetworkMeta {
-    fn default() -> Self {
-        #[cfg(target_arch = "wasm32")]
-        {
-            Self {
-                local_input_delay: 0,
-                max_prediction_window: 0,
-            }
-        }
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            Self {
-                local_input_delay: bones_framework::networking::NETWORK_LOCAL_INPUT_DELAY_DEFAULT,
-                max_prediction_window:
-                    bones_framework::networking::NETWORK_MAX_PREDICTION_WINDOW_DEFAULT,
-            }
-        }
-    }
-}
-
-#[derive(HasSchema, Clone, Debug, Default)]
-#[type_data(metadata_asset("assets"))]
-#[repr(C)]
-pub struct PackMeta {
-    pub plugins: SVec<Handle<LuaPlugin>>,
-    pub map_tilesets: SVec<Handle<Atlas>>,
-    pub players: SVec<Handle<PlayerMeta>>,
-    pub player_hats: SVec<Handle<HatMeta>>,
-    pub maps: SVec<Handle<MapMeta>>,
-    pub map_elements: SVec<Handle<ElementMeta>>,
-}
-
-impl GameMeta {
-    /// Get the lua plugins loaded by the game.
-    pub fn get_plugins(&self, asset_server: &AssetServer) -> Arc<Vec<Handle<LuaPlugin>>> {
-        let mut plugins = Vec::new();
-        plugins.extend(self.plugins.iter().copied());
-        plugins.extend(
-            self.core
-                .map_elements
-                .iter()
-                .map(|eh| asset_server.get(*eh).plugin)
-                .filter(|plugin_handle| plugin_handle != &Handle::default()),
-        );
-
-        for pack in asset_server.packs() {
-            let pack_meta = asset_server.get(pack.root.typed::<PackMeta>());
-            plugins.extend(pack_meta.plugins.iter().copied());
-            plugins.extend(
-                pack_meta
-                    .map_elements
-                    .iter()
-                    .map(|eh| asset_server.get(*eh).plugin)
-                    .filter(|plugin_handle| plugin_handle != &Handle::default()),
-            );
-        }
-        Arc::new(plugins)
-    }
-}
-
-#[derive(HasSchema, Clone, Debug, Default)]
-#[repr(C)]
-pub struct GameMusic {
-    pub title_screen: Handle<AudioSource>,
-    pub fight: SVec<Handle<AudioSource>>,
-    pub character_screen: Handle<AudioSource>,
-    pub results_screen: Handle<AudioSource>,
-    pub credits: Handle<AudioSource>,
-}
-
 fn main() {
     // Init logging
     setup_logs!("org", "fishfolk", "jumpy");
@@ -159,7 +32,7 @@ fn main() {
         .install_plugin(settings::game_plugin)
         .install_plugin(fullscreen::game_plugin)
         .install_plugin(input::game_plugin)
-        .install_plugin(core::game_plugin)
+        .install_plugin(jumpy::core::game_plugin)
         .install_plugin(debug::game_plugin)
         .install_plugin(profiler::game_plugin)
         .install_plugin(ui::scoring::game_plugin)
@@ -188,6 +61,16 @@ fn main() {
         .create(SessionNames::SCORING)
         .install_plugin(ui::scoring::session_plugin);
 
+    // Replay viewer controls, only shown while watching a replay
+    game.sessions
+        .create(SessionNames::REPLAY_VIEWER)
+        .install_plugin(ui::replay_viewer::session_plugin);
+
+    // Heads-up display shown on top of the game
+    game.sessions
+        .create(SessionNames::HUD)
+        .install_plugin(ui::hud::session_plugin);
+
     // session for pop-ups / nofication UI
     game.sessions
         .create(SessionNames::NOTIFICATION)
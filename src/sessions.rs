@@ -5,9 +5,12 @@ impl SessionNames {
     pub const AUDIO: &'static str = DEFAULT_BONES_AUDIO_SESSION;
     pub const DEBUG: &'static str = "debug";
     pub const GAME: &'static str = "game";
+    pub const HUD: &'static str = "hud";
     pub const MAIN_MENU: &'static str = "main_menu";
+    pub const MAP_EDITOR: &'static str = "map_editor";
     pub const PAUSE_MENU: &'static str = "pause_menu";
     pub const PROFILER: &'static str = "profiler";
+    pub const REPLAY_VIEWER: &'static str = "replay_viewer";
     pub const SCORING: &'static str = "scoring";
     pub const NOTIFICATION: &'static str = "notification";
 }
@@ -40,11 +43,12 @@ impl SessionExt for Sessions {
 
     #[track_caller]
     fn restart_game(&mut self, map_pool: Option<MapPool>, reset_score: bool) {
-        if let Some((existing_map_pool, player_info, plugins, mut session_runner, score)) =
+        if let Some((existing_map_pool, player_info, plugins, mut session_runner, score, rules)) =
             self.get_mut(SessionNames::GAME).map(|session| {
                 let existing_map_pool = (*session.world.resource::<MapPool>()).clone();
                 let match_inputs = session.world.resource::<MatchInputs>();
                 let score = (*session.world.resource::<MatchScore>()).clone();
+                let rules = (*session.world.resource::<MatchRules>()).clone();
 
                 // Take ownership of session runner (we want to preserve socket and such for network runner)
                 // by swapping a dummy one with session.
@@ -58,6 +62,7 @@ impl SessionExt for Sessions {
                     session.world.resource::<LuaPlugins>().0.clone(),
                     session_runner,
                     score,
+                    rules,
                 )
             })
         {
@@ -80,6 +85,7 @@ impl SessionExt for Sessions {
                     plugins,
                     session_runner,
                     score,
+                    rules,
                 });
         } else {
             panic!("Cannot restart game when game is not running");
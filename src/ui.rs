@@ -1,10 +1,13 @@
 use crate::prelude::*;
 
+pub mod hud;
 pub mod main_menu;
+pub mod map_editor;
 pub mod map_select;
 pub mod notification;
 pub mod pause_menu;
 pub mod player_image;
+pub mod replay_viewer;
 pub mod scoring;
 
 #[cfg(not(target_arch = "wasm32"))]
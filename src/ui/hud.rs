@@ -0,0 +1,64 @@
+//! Heads-up display shown on top of the game while a match is being played.
+
+use crate::prelude::*;
+
+pub fn session_plugin(session: &mut Session) {
+    session.add_system_to_stage(Update, hud_system);
+}
+
+/// The space between the lives of each player.
+const PLAYER_LIVES_SPACING: f32 = 30.0;
+
+/// System that shows the lives that each player has left, if the match has more than one life per
+/// player.
+fn hud_system(
+    meta: Root<GameMeta>,
+    ctx: Res<EguiCtx>,
+    localization: Localization<GameMeta>,
+    sessions: Res<Sessions>,
+) {
+    let Some(session) = sessions.get(SessionNames::GAME) else {
+        return;
+    };
+    let (Some(rules), Some(match_inputs)) = (
+        session.world.get_resource::<MatchRules>(),
+        session.world.get_resource::<MatchInputs>(),
+    ) else {
+        return;
+    };
+    if rules.lives <= 1 {
+        return;
+    }
+    let lives = session
+        .world
+        .get_resource::<PlayerLives>()
+        .map(|lives| (*lives).clone())
+        .unwrap_or_default();
+
+    egui::Area::new("hud")
+        .anchor(
+            egui::Align2::CENTER_TOP,
+            [0.0, meta.theme.font_styles.normal.size],
+        )
+        .interactable(false)
+        .show(&ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = PLAYER_LIVES_SPACING;
+                for (i, _) in match_inputs
+                    .players
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, player)| player.active)
+                {
+                    let remaining = lives.remaining(PlayerIdx(i as u32), &rules);
+                    ui.label(meta.theme.font_styles.normal.rich(localization.get_with(
+                        "player-lives",
+                        &fluent_args! {
+                            "player" => i + 1,
+                            "lives" => remaining,
+                        },
+                    )));
+                }
+            });
+        });
+}
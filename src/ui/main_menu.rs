@@ -3,6 +3,7 @@ use crate::prelude::*;
 use super::ImageMeta;
 
 mod credits;
+mod map_editor;
 mod map_select;
 pub mod player_select;
 pub(super) mod settings;
@@ -80,6 +81,8 @@ pub enum MenuPage {
     },
     Credits,
     NetworkGame,
+    /// Selecting the map to open in the map editor.
+    MapEditor,
 }
 
 #[allow(clippy::const_is_empty)]
@@ -140,6 +143,7 @@ fn main_menu_system(world: &World) {
             MenuPage::PlayerSelect => world.run_system(player_select::widget, ui),
             MenuPage::MapSelect { .. } => world.run_system(map_select::widget, ui),
             MenuPage::Credits => world.run_system(credits::widget, ui),
+            MenuPage::MapEditor => world.run_system(map_editor::widget, ui),
             MenuPage::NetworkGame =>
             {
                 #[cfg(not(target_arch = "wasm32"))]
@@ -217,6 +221,18 @@ fn home_menu(
                     ui.ctx().set_state(MenuPage::NetworkGame);
                 }
 
+                // Map editor
+                if BorderedButton::themed(
+                    &meta.theme.buttons.normal,
+                    localization.get("map-editor"),
+                )
+                .min_size(vec2(ui.available_width(), 0.0))
+                .show(ui)
+                .clicked()
+                {
+                    ui.ctx().set_state(MenuPage::MapEditor);
+                }
+
                 // Settings
                 if BorderedButton::themed(&meta.theme.buttons.normal, localization.get("settings"))
                     .min_size(vec2(ui.available_width(), 0.0))
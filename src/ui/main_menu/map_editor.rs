@@ -0,0 +1,25 @@
+use crate::prelude::*;
+
+use crate::ui::map_select::{map_select_menu, MapSelectAction};
+
+use super::MenuPage;
+
+/// Select the map to open in the map editor.
+pub fn widget(
+    ui: In<&mut egui::Ui>,
+    world: &World,
+    meta: Root<GameMeta>,
+    mut sessions: ResMut<Sessions>,
+    mut session_options: ResMut<SessionOptions>,
+    assets: Res<AssetServer>,
+) {
+    match world.run_system(map_select_menu, ()) {
+        MapSelectAction::None => (),
+        MapSelectAction::SelectMap(maps) => {
+            session_options.delete = true;
+            ui.ctx().set_state(MenuPage::Home);
+            crate::ui::map_editor::start_map_editor(&mut sessions, &meta, &assets, maps);
+        }
+        MapSelectAction::GoBack => ui.ctx().set_state(MenuPage::Home),
+    }
+}
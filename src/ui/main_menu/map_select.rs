@@ -107,20 +107,24 @@ pub fn widget(
                 Some(socket) => {
                     let random_seed = ui.ctx().get_state::<NetworkGameState>().random_seed();
 
-                    Box::new(GgrsSessionRunner::<NetworkInputConfig>::new(
-                        Some(FPS),
-                        GgrsSessionRunnerInfo::new(
-                            socket.ggrs_socket(),
-                            Some(meta.network.max_prediction_window),
-                            Some(meta.network.local_input_delay),
-                            random_seed,
-                        ),
+                    Box::new(ChecksumSyncRunner::new(
+                        Box::new(GgrsSessionRunner::<NetworkInputConfig>::new(
+                            Some(FPS),
+                            GgrsSessionRunnerInfo::new(
+                                socket.ggrs_socket(),
+                                Some(meta.network.max_prediction_window),
+                                Some(meta.network.local_input_delay),
+                                random_seed,
+                            ),
+                        )),
+                        (*socket).clone(),
+                        meta.network.max_prediction_window,
                     ))
                 }
-                None => Box::<JumpyDefaultMatchRunner>::default(),
+                None => Box::new(JumpyDefaultMatchRunner::new()),
             };
             #[cfg(target_arch = "wasm32")]
-            let session_runner = Box::<JumpyDefaultMatchRunner>::default();
+            let session_runner = Box::new(JumpyDefaultMatchRunner::new());
 
             let player_select_state = ui.ctx().get_state::<PlayerSelectState>();
             sessions.start_game(MatchPlugin {
@@ -143,6 +147,7 @@ pub fn widget(
                 plugins: meta.get_plugins(&assets),
                 session_runner,
                 score: default(),
+                rules: meta.core.config.rules.clone(),
             });
             ui.ctx().set_state(PlayerSelectState::default());
         }
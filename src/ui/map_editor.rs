@@ -0,0 +1,1066 @@
+//! In-game map editor.
+//!
+//! The editor runs in its own session next to the game session of the map that is being edited.
+//! It draws the editor panels on top of the running match and makes its changes by sending
+//! [`EditorInput`]s through the [`MatchInputs`] of the game session, where they are applied by the
+//! [`MapManager`][crate::core::editor::MapManager].
+
+use std::collections::VecDeque;
+
+use crate::{core::JumpyDefaultMatchRunner, prelude::*};
+
+use super::ImageMeta;
+
+/// The index of the player that the editor inputs are sent for.
+const EDITOR_PLAYER: usize = 0;
+
+/// The range of camera heights that the editor camera can be zoomed between.
+const CAMERA_HEIGHT_RANGE: std::ops::RangeInclusive<f32> = 100.0..=3000.0;
+
+/// How much the camera zooms for every point scrolled.
+const ZOOM_SPEED: f32 = 0.002;
+
+/// How fast the camera pans with the arrow keys, in screen points per second.
+const PAN_SPEED: f32 = 600.0;
+
+/// The size of the icons and tiles in the editor palettes.
+const PALETTE_ITEM_SIZE: f32 = 32.0;
+
+pub fn session_plugin(session: &mut Session) {
+    session.add_system_to_stage(Update, map_editor_system);
+}
+
+/// Start editing the current map of the map pool, replacing the current match if there is any.
+pub fn start_map_editor(
+    sessions: &mut Sessions,
+    meta: &GameMeta,
+    assets: &AssetServer,
+    maps: MapPool,
+) {
+    sessions.end_game();
+    sessions.start_game(crate::core::MatchPlugin {
+        maps,
+        // Nobody plays while editing, the players are only used to send the editor inputs.
+        player_info: default(),
+        plugins: meta.get_plugins(assets),
+        session_runner: Box::new(JumpyDefaultMatchRunner::new()),
+        score: default(),
+        rules: meta.core.config.rules.clone(),
+    });
+    sessions
+        .create(SessionNames::MAP_EDITOR)
+        .install_plugin(session_plugin);
+}
+
+/// The tools of the map editor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EditorTool {
+    /// Select, move, and delete elements.
+    #[default]
+    Select,
+    /// Place elements from the element palette.
+    Elements,
+    /// Paint tiles from the tile palette.
+    Tiles,
+    /// Change the collision of existing tiles.
+    Collisions,
+}
+
+/// The state of the map editor UI.
+#[derive(HasSchema, Clone, Debug)]
+pub struct MapEditorState {
+    pub tool: EditorTool,
+    /// The index of the layer being edited.
+    pub layer: u32,
+    /// The tile index to paint with, or [`None`] to erase tiles.
+    pub tile: Option<u32>,
+    /// The terrain to paint with auto-tiling instead of the tile, if any.
+    pub terrain: Option<Handle<AutotileMeta>>,
+    /// The collision kind to give painted tiles.
+    pub collision: TileCollisionKind,
+    /// Whether painted tiles can be destroyed by explosions.
+    pub destructible: bool,
+    /// The element to place.
+    pub element: Option<Handle<ElementMeta>>,
+    /// The element that is selected with the select tool.
+    pub selected_entity: Option<Entity>,
+    /// The offset from the pointer to the element that is being dragged.
+    pub drag_offset: Option<Vec2>,
+    /// The last tile that was painted, so that we don't paint it every frame while dragging.
+    pub last_painted: Option<UVec2>,
+    pub show_grid: bool,
+    pub map_name: String,
+    pub layer_name: String,
+    pub new_layer_name: String,
+    /// The result of the last map export, to show to the user.
+    pub export_message: Option<String>,
+    /// Editor inputs waiting to be sent to the game session, one per simulated frame.
+    pub pending_inputs: VecDeque<EditorInput>,
+}
+
+impl Default for MapEditorState {
+    fn default() -> Self {
+        Self {
+            tool: default(),
+            layer: 0,
+            tile: Some(0),
+            terrain: None,
+            collision: TileCollisionKind::Solid,
+            destructible: false,
+            element: None,
+            selected_entity: None,
+            drag_offset: None,
+            last_painted: None,
+            show_grid: true,
+            map_name: String::new(),
+            layer_name: String::new(),
+            new_layer_name: String::new(),
+            export_message: None,
+            pending_inputs: default(),
+        }
+    }
+}
+
+impl MapEditorState {
+    /// Queue an editor input to be sent to the game session.
+    fn send(&mut self, input: EditorInput) {
+        // Dragging an element moves it every frame, but only the latest position matters.
+        if let EditorInput::MoveEntity { entity, .. } = &input {
+            if let Some(EditorInput::MoveEntity { entity: last, .. }) = self.pending_inputs.back() {
+                if last == entity {
+                    self.pending_inputs.pop_back();
+                }
+            }
+        }
+        // The same goes for dragging the size of an element.
+        if let EditorInput::ResizeElement { entity, .. } = &input {
+            if let Some(EditorInput::ResizeElement { entity: last, .. }) =
+                self.pending_inputs.back()
+            {
+                if last == entity {
+                    self.pending_inputs.pop_back();
+                }
+            }
+        }
+        self.pending_inputs.push_back(input);
+    }
+}
+
+/// An element on the map being edited.
+struct MapViewElement {
+    entity: Entity,
+    handle: Handle<ElementMeta>,
+    pos: Vec2,
+    /// The size of the element in tiles, if it can be resized.
+    size: Option<UVec2>,
+    /// The size of the element in the world, if it can be resized, which it can be grabbed by
+    /// instead of the grab size of its editor metadata.
+    grab_size: Option<Vec2>,
+}
+
+/// The parts of the game world that the editor shows, collected at the start of every frame.
+struct MapView {
+    map: SpawnedMapMeta,
+    camera_pos: Vec2,
+    camera_height: f32,
+    /// The tilemap of every layer, if it has one.
+    layer_tilemaps: Vec<Option<Handle<Atlas>>>,
+    elements: Vec<MapViewElement>,
+    can_undo: bool,
+    can_redo: bool,
+}
+
+impl MapView {
+    /// Collect the view from the game world. Returns [`None`] if the map has not been spawned yet.
+    fn new(world: &World, default_camera_height: f32) -> Option<Self> {
+        let map = world.get_resource::<SpawnedMapMeta>()?.clone();
+        let entities = world.resource::<Entities>();
+        let transforms = world.components.get::<Transform>().borrow();
+
+        let (camera_pos, camera_height) = {
+            let cameras = world.components.get::<Camera>().borrow();
+            let camera_shakes = world.components.get::<CameraShake>().borrow();
+            let (_, (camera, camera_shake)) =
+                entities.iter_with((&*cameras, &*camera_shakes)).next()?;
+            let height = match camera.size {
+                CameraSize::FixedHeight(height) => height,
+                _ => default_camera_height,
+            };
+            (camera_shake.center.truncate(), height)
+        };
+
+        let layer_metas = world.components.get::<SpawnedMapLayerMeta>().borrow();
+
+        let mut layer_tilemaps = vec![None; map.layer_names.len()];
+        let tile_layers = world.components.get::<TileLayer>().borrow();
+        for (_, (tile_layer, layer_meta)) in entities.iter_with((&*tile_layers, &*layer_metas)) {
+            if let Some(tilemap) = layer_tilemaps.get_mut(layer_meta.layer_idx as usize) {
+                if tile_layer.atlas != default() {
+                    *tilemap = Some(tile_layer.atlas);
+                }
+            }
+        }
+
+        let element_handles = world.components.get::<ElementHandle>().borrow();
+        let element_sizes = world.components.get::<ElementSize>().borrow();
+        let water_volumes = world.components.get::<WaterVolume>().borrow();
+        let elements = entities
+            .iter_with((&*element_handles, &*transforms))
+            .map(|(entity, (handle, transform))| MapViewElement {
+                entity,
+                handle: handle.0,
+                pos: transform.translation.truncate(),
+                size: element_sizes.get(entity).map(|size| size.0),
+                grab_size: water_volumes.get(entity).map(|water| water.size),
+            })
+            .collect();
+
+        let history = world.get_resource::<EditorHistory>();
+        let can_undo = history.as_ref().is_some_and(|history| history.can_undo());
+        let can_redo = history.as_ref().is_some_and(|history| history.can_redo());
+
+        Some(Self {
+            map,
+            camera_pos,
+            camera_height,
+            layer_tilemaps,
+            elements,
+            can_undo,
+            can_redo,
+        })
+    }
+
+    /// Get the size of the map in world units.
+    fn size(&self) -> Vec2 {
+        self.map.grid_size.as_vec2() * self.map.tile_size
+    }
+
+    /// Get the position of the tile at the given world position, if it is on the map.
+    fn tile_pos(&self, pos: Vec2) -> Option<UVec2> {
+        let tile_pos = (pos / self.map.tile_size).floor();
+        (tile_pos.cmpge(Vec2::ZERO).all() && tile_pos.cmplt(self.map.grid_size.as_vec2()).all())
+            .then_some(tile_pos.as_uvec2())
+    }
+}
+
+/// Converts between screen and world positions.
+struct ScreenTransform {
+    screen_center: egui::Pos2,
+    camera_pos: Vec2,
+    /// The number of world units per screen point.
+    scale: f32,
+}
+
+impl ScreenTransform {
+    fn to_world(&self, pos: egui::Pos2) -> Vec2 {
+        let offset = pos - self.screen_center;
+        self.camera_pos + vec2(offset.x, -offset.y) * self.scale
+    }
+
+    fn to_screen(&self, pos: Vec2) -> egui::Pos2 {
+        let offset = (pos - self.camera_pos) / self.scale;
+        self.screen_center + egui::vec2(offset.x, -offset.y)
+    }
+
+    fn rect_to_screen(&self, rect: Rect) -> egui::Rect {
+        egui::Rect::from_two_pos(self.to_screen(rect.min), self.to_screen(rect.max))
+    }
+}
+
+fn map_editor_system(
+    ctx: Res<EguiCtx>,
+    meta: Root<GameMeta>,
+    localization: Localization<GameMeta>,
+    assets: Res<AssetServer>,
+    egui_textures: Res<EguiTextures>,
+    mut sessions: ResMut<Sessions>,
+    mut session_options: ResMut<SessionOptions>,
+    mut state: ResMutInit<MapEditorState>,
+) {
+    let mut close_editor = false;
+    {
+        let Some(session) = sessions.get_mut(SessionNames::GAME) else {
+            // The match was ended from somewhere else, like the pause menu.
+            session_options.delete = true;
+            return;
+        };
+        let world = &session.world;
+        let Some(mut view) = MapView::new(world, meta.core.camera.default_height) else {
+            return;
+        };
+
+        // Keep the selected layer valid when layers are deleted.
+        let layer_count = view.map.layer_names.len() as u32;
+        if state.layer >= layer_count {
+            state.layer = layer_count.saturating_sub(1);
+        }
+        if let Some(entity) = state.selected_entity {
+            if !view.elements.iter().any(|element| element.entity == entity) {
+                state.selected_entity = None;
+            }
+        }
+
+        // Undo and redo shortcuts, unless they are meant for a text field.
+        if !ctx.wants_keyboard_input() {
+            let (undo, redo) = ctx.input(|input| {
+                let command = input.modifiers.command;
+                let z_pressed = command && input.key_pressed(egui::Key::Z);
+                let y_pressed = command && input.key_pressed(egui::Key::Y);
+                (
+                    z_pressed && !input.modifiers.shift,
+                    (z_pressed && input.modifiers.shift) || y_pressed,
+                )
+            });
+            if undo {
+                state.send(EditorInput::Undo);
+            } else if redo {
+                state.send(EditorInput::Redo);
+            }
+        }
+
+        egui::SidePanel::left("map_editor_tools").show(&ctx, |ui| {
+            tools_panel(
+                ui,
+                &mut state,
+                &view,
+                &meta,
+                &localization,
+                &assets,
+                &egui_textures,
+            );
+        });
+        egui::SidePanel::right("map_editor_layers").show(&ctx, |ui| {
+            layers_panel(
+                ui,
+                &mut state,
+                &mut view,
+                world,
+                &meta,
+                &localization,
+                &mut close_editor,
+            );
+        });
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none())
+            .show(&ctx, |ui| {
+                map_area(ui, &mut state, &mut view, world, &assets, &localization);
+            });
+
+        update_game_world(world, &mut state, &view);
+    }
+
+    if close_editor {
+        sessions.end_game();
+        sessions.start_menu();
+        session_options.delete = true;
+    }
+}
+
+/// Apply the editor camera and send the next editor input to the game session.
+fn update_game_world(world: &World, state: &mut MapEditorState, view: &MapView) {
+    let entities = world.resource::<Entities>();
+    let mut cameras = world.components.get::<Camera>().borrow_mut();
+    let mut camera_shakes = world.components.get::<CameraShake>().borrow_mut();
+    let mut camera_states = world.components.get::<CameraState>().borrow_mut();
+    for (_, (camera, camera_shake, camera_state)) in
+        entities.iter_with((&mut *cameras, &mut *camera_shakes, &mut *camera_states))
+    {
+        camera_state.disable_controller = true;
+        camera.size = CameraSize::FixedHeight(view.camera_height);
+        camera_shake.center = view.camera_pos.extend(camera_shake.center.z);
+    }
+
+    // Only one editor input can be made per frame, so we wait until the last one was applied.
+    let mut match_inputs = world.resource_mut::<MatchInputs>();
+    let editor_input = &mut match_inputs.players[EDITOR_PLAYER].editor_input;
+    if editor_input.is_none() {
+        *editor_input = state.pending_inputs.pop_front();
+    }
+}
+
+/// The tool selection and the palette of the current tool.
+fn tools_panel(
+    ui: &mut egui::Ui,
+    state: &mut MapEditorState,
+    view: &MapView,
+    meta: &GameMeta,
+    localization: &Localization<GameMeta>,
+    assets: &AssetServer,
+    egui_textures: &EguiTextures,
+) {
+    let icons = &meta.theme.editor.icons;
+    ui.horizontal(|ui| {
+        for (tool, icon, name) in [
+            (EditorTool::Select, &icons.select, "select"),
+            (EditorTool::Elements, &icons.elements, "elements"),
+            (EditorTool::Tiles, &icons.tiles, "tiles"),
+            (EditorTool::Collisions, &icons.collisions, "collisions"),
+        ] {
+            if icon_button(ui, egui_textures, icon, state.tool == tool)
+                .on_hover_text(localization.get(name))
+                .clicked()
+            {
+                state.tool = tool;
+                state.drag_offset = None;
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(view.can_undo, egui::Button::new(localization.get("undo")))
+            .clicked()
+        {
+            state.send(EditorInput::Undo);
+        }
+        if ui
+            .add_enabled(view.can_redo, egui::Button::new(localization.get("redo")))
+            .clicked()
+        {
+            state.send(EditorInput::Redo);
+        }
+    });
+    ui.separator();
+
+    match state.tool {
+        EditorTool::Select => {
+            ui.heading(localization.get("element"));
+            if let Some(element) = state
+                .selected_entity
+                .and_then(|entity| view.elements.iter().find(|x| x.entity == entity))
+            {
+                ui.label(assets.get(element.handle).name.as_str());
+                if let Some(mut size) = element.size {
+                    ui.horizontal(|ui| {
+                        ui.label(localization.get("size"));
+                        let width = ui.add(egui::DragValue::new(&mut size.x).clamp_range(1..=64));
+                        let height = ui.add(egui::DragValue::new(&mut size.y).clamp_range(1..=64));
+                        if width.changed() || height.changed() {
+                            state.send(EditorInput::ResizeElement {
+                                entity: element.entity,
+                                size,
+                            });
+                        }
+                    });
+                }
+                if ui.button(localization.get("delete-element")).clicked() {
+                    state.send(EditorInput::DeleteEntity {
+                        entity: element.entity,
+                    });
+                    state.selected_entity = None;
+                }
+            } else {
+                ui.label(localization.get("none"));
+            }
+        }
+        EditorTool::Elements => {
+            ui.heading(localization.get("elements"));
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let mut elements = meta
+                    .core
+                    .map_elements
+                    .iter()
+                    .map(|handle| {
+                        let element = assets.get(*handle);
+                        (element.category, element.name, *handle)
+                    })
+                    .collect::<Vec<_>>();
+                elements.sort_by_key(|(category, name, _)| (*category, *name));
+
+                let mut current_category = None;
+                for (category, name, handle) in elements {
+                    if current_category != Some(category) {
+                        current_category = Some(category);
+                        ui.label(egui::RichText::new(category.as_str()).strong());
+                    }
+                    if ui
+                        .selectable_label(state.element == Some(handle), name.as_str())
+                        .clicked()
+                    {
+                        state.element = Some(handle);
+                    }
+                }
+            });
+        }
+        EditorTool::Tiles | EditorTool::Collisions => {
+            let layer = state.layer;
+            let tilemap = view.layer_tilemaps.get(layer as usize).copied().flatten();
+
+            ui.heading(localization.get("tilemap"));
+            let tilemap_name = |handle: Option<Handle<Atlas>>| {
+                handle
+                    .and_then(|handle| meta.core.map_tilesets.iter().position(|x| *x == handle))
+                    .map(|i| format!("{} {}", localization.get("tilemap"), i + 1))
+                    .unwrap_or_else(|| localization.get("none").to_string())
+            };
+            egui::ComboBox::from_id_source("map_editor_tilemap")
+                .selected_text(tilemap_name(tilemap))
+                .show_ui(ui, |ui| {
+                    for handle in std::iter::once(None)
+                        .chain(meta.core.map_tilesets.iter().copied().map(Some))
+                    {
+                        if ui
+                            .selectable_label(tilemap == handle, tilemap_name(handle))
+                            .clicked()
+                            && tilemap != handle
+                        {
+                            state.send(EditorInput::SetTilemap {
+                                layer: layer as u8,
+                                handle,
+                            });
+                        }
+                    }
+                });
+
+            ui.heading(localization.get("collision"));
+            for (collision, name) in [
+                (TileCollisionKind::Empty, "empty"),
+                (TileCollisionKind::Solid, "solid"),
+                (TileCollisionKind::JumpThrough, "jump-through"),
+                (TileCollisionKind::Slope45UpRight, "slope-45-up-right"),
+                (TileCollisionKind::Slope45UpLeft, "slope-45-up-left"),
+                (
+                    TileCollisionKind::Slope22UpRightLow,
+                    "slope-22-up-right-low",
+                ),
+                (
+                    TileCollisionKind::Slope22UpRightHigh,
+                    "slope-22-up-right-high",
+                ),
+                (
+                    TileCollisionKind::Slope22UpLeftHigh,
+                    "slope-22-up-left-high",
+                ),
+                (TileCollisionKind::Slope22UpLeftLow, "slope-22-up-left-low"),
+            ] {
+                ui.radio_value(&mut state.collision, collision, localization.get(name));
+            }
+            ui.checkbox(&mut state.destructible, localization.get("destructible"));
+
+            if state.tool == EditorTool::Tiles {
+                ui.heading(localization.get("terrain"));
+                for handle in meta.core.map_autotiles.iter().copied() {
+                    let terrain = assets.get(handle);
+                    if ui
+                        .selectable_label(state.terrain == Some(handle), terrain.name.as_str())
+                        .clicked()
+                    {
+                        state.terrain = Some(handle);
+                    }
+                    if state.terrain == Some(handle)
+                        && tilemap.is_some_and(|tilemap| tilemap != terrain.tilemap)
+                    {
+                        ui.label(localization.get("terrain-tilemap-mismatch"));
+                    }
+                }
+
+                ui.heading(localization.get("tiles"));
+                if ui
+                    .selectable_label(
+                        state.terrain.is_none() && state.tile.is_none(),
+                        localization.get("delete"),
+                    )
+                    .clicked()
+                {
+                    state.tile = None;
+                    state.terrain = None;
+                }
+                if let Some(tilemap) = tilemap {
+                    let atlas = assets.get(tilemap);
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for idx in 0..atlas.columns * atlas.rows {
+                                let selected = state.terrain.is_none() && state.tile == Some(idx);
+                                if tile_button(ui, egui_textures, &atlas, idx, selected).clicked() {
+                                    state.tile = Some(idx);
+                                    state.terrain = None;
+                                }
+                            }
+                        });
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// The map info, the view settings, and the list of layers.
+fn layers_panel(
+    ui: &mut egui::Ui,
+    state: &mut MapEditorState,
+    view: &mut MapView,
+    world: &World,
+    meta: &GameMeta,
+    localization: &Localization<GameMeta>,
+    close_editor: &mut bool,
+) {
+    ui.label(egui::RichText::new(localization.get("map-editor-preview-warning")).small());
+    ui.separator();
+
+    ui.heading(localization.get("map-info"));
+    ui.horizontal(|ui| {
+        ui.label(localization.get("name"));
+        if state.map_name.is_empty() {
+            state.map_name = view.map.name.to_string();
+        }
+        let response = ui.text_edit_singleline(&mut state.map_name);
+        if response.lost_focus() && state.map_name != view.map.name.as_str() {
+            state.send(EditorInput::RenameMap {
+                name: state.map_name.clone(),
+            });
+        }
+    });
+    ui.horizontal(|ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button(localization.get("map-export")).clicked() {
+            let packs_dir = std::env::var("JUMPY_ASSET_PACKS").unwrap_or_else(|_| "packs".into());
+            let pack_dir = std::path::Path::new(&packs_dir).join(EDITOR_MAPS_PACK);
+            let result = MapExport::from_world(world, Some(EDITOR_MAPS_PACK))
+                .and_then(|map| map.save_to_pack(&pack_dir));
+            state.export_message = Some(match result {
+                Ok(path) => localization.get_with(
+                    "map-exported",
+                    &fluent_args! { "path" => path.display().to_string() },
+                ),
+                Err(e) => localization.get_with(
+                    "map-export-failed",
+                    &fluent_args! { "error" => e.to_string() },
+                ),
+            });
+        }
+        if ui.button(localization.get("copy-to-clipboard")).clicked() {
+            match MapExport::from_world(world, None).and_then(|map| map.to_yaml()) {
+                Ok(yaml) => ui.output_mut(|output| output.copied_text = yaml),
+                Err(e) => {
+                    state.export_message = Some(localization.get_with(
+                        "map-export-failed",
+                        &fluent_args! { "error" => e.to_string() },
+                    ))
+                }
+            }
+        }
+    });
+    if let Some(message) = &state.export_message {
+        ui.label(egui::RichText::new(message).small());
+    }
+    ui.checkbox(&mut state.show_grid, localization.get("show-grid"));
+    ui.label(localization.get_with(
+        "view-zoom",
+        &fluent_args! {
+            "percent" => (meta.core.camera.default_height / view.camera_height * 100.0).round()
+        },
+    ));
+    if ui.button(localization.get("view-reset")).clicked() {
+        view.camera_pos = view.size() / 2.0;
+        view.camera_height = meta.core.camera.default_height;
+    }
+    ui.separator();
+
+    ui.heading(localization.get("layers"));
+    let layer_count = view.map.layer_names.len() as u32;
+    // Draw the top layer first.
+    for (i, name) in view.map.layer_names.iter().enumerate().rev() {
+        let i = i as u32;
+        ui.horizontal(|ui| {
+            let icon = if view.layer_tilemaps[i as usize].is_some() {
+                localization.get("tile-layer-icon")
+            } else {
+                localization.get("element-layer-icon")
+            };
+            if ui
+                .selectable_label(state.layer == i, format!("{icon} {name}"))
+                .clicked()
+            {
+                state.layer = i;
+                state.layer_name = name.to_string();
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .button("🗑")
+                    .on_hover_text(localization.get("delete-layer"))
+                    .clicked()
+                {
+                    state.send(EditorInput::DeleteLayer { layer: i as u8 });
+                }
+                if ui
+                    .add_enabled(i > 0, egui::Button::new("⏷"))
+                    .on_hover_text(localization.get("move-layer-down"))
+                    .clicked()
+                {
+                    state.send(EditorInput::MoveLayer {
+                        layer: i as u8,
+                        down: false,
+                    });
+                    if state.layer == i {
+                        state.layer -= 1;
+                    }
+                }
+                if ui
+                    .add_enabled(i + 1 < layer_count, egui::Button::new("⏶"))
+                    .on_hover_text(localization.get("move-layer-up"))
+                    .clicked()
+                {
+                    state.send(EditorInput::MoveLayer {
+                        layer: i as u8,
+                        down: true,
+                    });
+                    if state.layer == i {
+                        state.layer += 1;
+                    }
+                }
+            });
+        });
+    }
+
+    if state.layer < layer_count {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut state.layer_name);
+            if ui.button(localization.get("rename")).clicked() && !state.layer_name.is_empty() {
+                state.send(EditorInput::RenameLayer {
+                    layer: state.layer as u8,
+                    name: state.layer_name.clone(),
+                });
+            }
+        });
+    }
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut state.new_layer_name);
+        if ui.button(localization.get("create-layer")).clicked() && !state.new_layer_name.is_empty()
+        {
+            let id = std::mem::take(&mut state.new_layer_name);
+            state.send(EditorInput::CreateLayer { id });
+        }
+    });
+    ui.separator();
+
+    if ui.button(localization.get("close")).clicked() {
+        *close_editor = true;
+    }
+}
+
+/// The map itself, where the tools are used and the camera is controlled.
+fn map_area(
+    ui: &mut egui::Ui,
+    state: &mut MapEditorState,
+    view: &mut MapView,
+    world: &World,
+    assets: &AssetServer,
+    localization: &Localization<GameMeta>,
+) {
+    let response = ui.interact(
+        ui.max_rect(),
+        ui.id().with("map_editor_map"),
+        egui::Sense::click_and_drag(),
+    );
+
+    // Pan with the middle mouse button or the arrow keys, and zoom with the scroll wheel.
+    let screen_rect = ui.ctx().screen_rect();
+    let scale = view.camera_height / screen_rect.height();
+    let mut pan = egui::Vec2::ZERO;
+    if response.dragged_by(egui::PointerButton::Middle) {
+        pan -= response.drag_delta();
+    }
+    if !ui.ctx().wants_keyboard_input() {
+        ui.input(|input| {
+            let distance = PAN_SPEED * input.stable_dt;
+            for (key, direction) in [
+                (egui::Key::ArrowLeft, egui::vec2(-1.0, 0.0)),
+                (egui::Key::ArrowRight, egui::vec2(1.0, 0.0)),
+                (egui::Key::ArrowUp, egui::vec2(0.0, -1.0)),
+                (egui::Key::ArrowDown, egui::vec2(0.0, 1.0)),
+            ] {
+                if input.key_down(key) {
+                    pan += direction * distance;
+                }
+            }
+        });
+    }
+    view.camera_pos += vec2(pan.x, -pan.y) * scale;
+    if response.hovered() {
+        let scroll = ui.input(|input| input.scroll_delta.y);
+        view.camera_height = (view.camera_height * (1.0 - scroll * ZOOM_SPEED))
+            .clamp(*CAMERA_HEIGHT_RANGE.start(), *CAMERA_HEIGHT_RANGE.end());
+    }
+
+    let transform = ScreenTransform {
+        screen_center: screen_rect.center(),
+        camera_pos: view.camera_pos,
+        scale: view.camera_height / screen_rect.height(),
+    };
+    let pointer = response
+        .interact_pointer_pos()
+        .or(response.hover_pos())
+        .map(|pos| transform.to_world(pos));
+    let pointer_tile = pointer.and_then(|pos| view.tile_pos(pos));
+    let layer = state.layer;
+    let has_layer = layer < view.map.layer_names.len() as u32;
+
+    match state.tool {
+        EditorTool::Select => {
+            if response.drag_started_by(egui::PointerButton::Primary)
+                || response.clicked_by(egui::PointerButton::Primary)
+            {
+                let hit = pointer.and_then(|pos| {
+                    view.elements
+                        .iter()
+                        .rev()
+                        .find(|element| {
+                            let rect = element_rect(element, &assets.get(element.handle).editor);
+                            pos.cmpge(rect.min).all() && pos.cmple(rect.max).all()
+                        })
+                        .map(|element| (element.entity, element.pos - pos))
+                });
+                state.selected_entity = hit.map(|(entity, _)| entity);
+                state.drag_offset = hit.map(|(_, offset)| offset);
+            }
+            if let (Some(entity), Some(offset), Some(pos)) =
+                (state.selected_entity, state.drag_offset, pointer)
+            {
+                if response.dragged_by(egui::PointerButton::Primary) {
+                    state.send(EditorInput::MoveEntity {
+                        entity,
+                        pos: pos + offset,
+                    });
+                } else {
+                    state.drag_offset = None;
+                }
+            }
+            if let Some(entity) = state.selected_entity {
+                if ui.input(|input| input.key_pressed(egui::Key::Delete))
+                    && !ui.ctx().wants_keyboard_input()
+                {
+                    state.send(EditorInput::DeleteEntity { entity });
+                    state.selected_entity = None;
+                }
+            }
+        }
+        EditorTool::Elements => {
+            if let (Some(handle), Some(pos), true) = (state.element, pointer, has_layer) {
+                if response.clicked_by(egui::PointerButton::Primary) {
+                    state.send(EditorInput::SpawnElement {
+                        handle,
+                        translation: pos,
+                        layer: layer as u8,
+                    });
+                }
+            }
+        }
+        EditorTool::Tiles => {
+            let painting = response.dragged_by(egui::PointerButton::Primary)
+                || response.clicked_by(egui::PointerButton::Primary);
+            let erasing = response.dragged_by(egui::PointerButton::Secondary)
+                || response.clicked_by(egui::PointerButton::Secondary);
+            match pointer_tile {
+                Some(pos) if (painting || erasing) && has_layer => {
+                    if state.last_painted != Some(pos) {
+                        state.last_painted = Some(pos);
+                        if let Some(terrain) = state.terrain {
+                            state.send(EditorInput::PaintTerrain {
+                                layer: layer as u8,
+                                pos,
+                                terrain,
+                                erase: erasing,
+                            });
+                        } else {
+                            let tile = if painting { state.tile } else { None };
+                            state.send(EditorInput::SetTile {
+                                layer: layer as u8,
+                                pos,
+                                tilemap_tile_idx: tile,
+                                collision: if tile.is_some() {
+                                    state.collision
+                                } else {
+                                    TileCollisionKind::Empty
+                                },
+                                destructible: tile.is_some() && state.destructible,
+                            });
+                        }
+                    }
+                }
+                _ => state.last_painted = None,
+            }
+        }
+        EditorTool::Collisions => {
+            if let (Some(pos), true) = (pointer_tile, has_layer) {
+                if response.clicked_by(egui::PointerButton::Primary) {
+                    if let Some(idx) = tile_at(world, layer, pos) {
+                        state.send(EditorInput::SetTile {
+                            layer: layer as u8,
+                            pos,
+                            tilemap_tile_idx: Some(idx),
+                            collision: state.collision,
+                            destructible: state.destructible,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Draw the overlays
+    let painter = ui.painter();
+    let grid_stroke = egui::Stroke::new(1.0, egui::Color32::from_white_alpha(40));
+    if state.show_grid {
+        let size = view.size();
+        for x in 0..=view.map.grid_size.x {
+            let x = x as f32 * view.map.tile_size.x;
+            painter.line_segment(
+                [
+                    transform.to_screen(vec2(x, 0.0)),
+                    transform.to_screen(vec2(x, size.y)),
+                ],
+                grid_stroke,
+            );
+        }
+        for y in 0..=view.map.grid_size.y {
+            let y = y as f32 * view.map.tile_size.y;
+            painter.line_segment(
+                [
+                    transform.to_screen(vec2(0.0, y)),
+                    transform.to_screen(vec2(size.x, y)),
+                ],
+                grid_stroke,
+            );
+        }
+    }
+
+    if matches!(state.tool, EditorTool::Tiles | EditorTool::Collisions) {
+        if let Some(pos) = pointer_tile {
+            let min = pos.as_vec2() * view.map.tile_size;
+            let rect = Rect {
+                min,
+                max: min + view.map.tile_size,
+            };
+            painter.rect_stroke(
+                transform.rect_to_screen(rect),
+                0.0,
+                egui::Stroke::new(2.0, egui::Color32::WHITE),
+            );
+        }
+    }
+
+    if matches!(state.tool, EditorTool::Select | EditorTool::Elements) {
+        for element in &view.elements {
+            let element_meta = assets.get(element.handle);
+            let rect = transform.rect_to_screen(element_rect(element, &element_meta.editor));
+            let selected = state.selected_entity == Some(element.entity);
+            let color = if selected {
+                egui::Color32::YELLOW
+            } else {
+                egui::Color32::from_white_alpha(120)
+            };
+            painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, color));
+            if element_meta.editor.show_name || selected {
+                painter.text(
+                    rect.center_top(),
+                    egui::Align2::CENTER_BOTTOM,
+                    element_meta.name.as_str(),
+                    egui::FontId::proportional(12.0),
+                    color,
+                );
+            }
+        }
+    }
+
+    if let Some(pos) = pointer {
+        painter.text(
+            ui.max_rect().left_bottom() + egui::vec2(4.0, -4.0),
+            egui::Align2::LEFT_BOTTOM,
+            localization.get_with(
+                "cursor-position",
+                &fluent_args! {
+                    "x" => pos.x.round(),
+                    "y" => pos.y.round(),
+                },
+            ),
+            egui::FontId::proportional(14.0),
+            egui::Color32::WHITE,
+        );
+    }
+}
+
+/// Get the rect that an element can be grabbed by in the editor.
+fn element_rect(element: &MapViewElement, editor: &ElementEditorMeta) -> Rect {
+    let center = element.pos + editor.grab_offset;
+    let grab_size = element.grab_size.unwrap_or(editor.grab_size);
+    Rect {
+        min: center - grab_size / 2.0,
+        max: center + grab_size / 2.0,
+    }
+}
+
+/// Get the tile index of the tile at the given position on a layer, if there is one.
+fn tile_at(world: &World, layer: u32, pos: UVec2) -> Option<u32> {
+    let entities = world.resource::<Entities>();
+    let tile_layers = world.components.get::<TileLayer>().borrow();
+    let layer_metas = world.components.get::<SpawnedMapLayerMeta>().borrow();
+    let tiles = world.components.get::<Tile>().borrow();
+    let (_, (tile_layer, _)) = entities
+        .iter_with((&*tile_layers, &*layer_metas))
+        .find(|(_, (_, layer_meta))| layer_meta.layer_idx == layer)?;
+    let tile = tile_layer.get(pos)?;
+    tiles.get(tile).map(|tile| tile.idx)
+}
+
+/// Draw the outline of a palette item.
+fn palette_outline(ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, selected: bool) {
+    let color = if selected {
+        egui::Color32::YELLOW
+    } else if response.hovered() {
+        egui::Color32::WHITE
+    } else {
+        return;
+    };
+    ui.painter()
+        .rect_stroke(rect, 0.0, egui::Stroke::new(2.0, color));
+}
+
+/// A button showing an editor icon.
+fn icon_button(
+    ui: &mut egui::Ui,
+    egui_textures: &EguiTextures,
+    icon: &ImageMeta,
+    selected: bool,
+) -> egui::Response {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::Vec2::splat(PALETTE_ITEM_SIZE), egui::Sense::click());
+    if let Some(texture_id) = egui_textures.0.get(&icon.image) {
+        let mut mesh = egui::Mesh::with_texture(*texture_id);
+        mesh.add_rect_with_uv(
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        ui.painter().add(mesh);
+    }
+    palette_outline(ui, rect, &response, selected);
+    response
+}
+
+/// A button showing a tile from a tilemap.
+fn tile_button(
+    ui: &mut egui::Ui,
+    egui_textures: &EguiTextures,
+    atlas: &Atlas,
+    idx: u32,
+    selected: bool,
+) -> egui::Response {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::Vec2::splat(PALETTE_ITEM_SIZE), egui::Sense::click());
+    if let Some(texture_id) = egui_textures.0.get(&atlas.image) {
+        let tile_pos = atlas.tile_pos(idx);
+        let uv_min = tile_pos / atlas.size();
+        let uv_max = (tile_pos + atlas.tile_size) / atlas.size();
+        let mut mesh = egui::Mesh::with_texture(*texture_id);
+        mesh.add_rect_with_uv(
+            rect,
+            egui::Rect::from_min_max(
+                egui::pos2(uv_min.x, uv_min.y),
+                egui::pos2(uv_max.x, uv_max.y),
+            ),
+            egui::Color32::WHITE,
+        );
+        ui.painter().add(mesh);
+    }
+    palette_outline(ui, rect, &response, selected);
+    response
+}
@@ -32,6 +32,7 @@ fn pause_menu_system(
 ) {
     let mut back_to_menu = false;
     let mut restart_game = false;
+    let mut watch_replay = false;
     let mut close_pause_menu = false;
     let mut close_settings_menu = false;
     let mut select_map = None;
@@ -76,6 +77,7 @@ fn pause_menu_system(
                                             ui,
                                             session,
                                             &mut restart_game,
+                                            &mut watch_replay,
                                             &mut back_to_menu,
                                             &mut close_pause_menu,
                                             is_online,
@@ -128,14 +130,19 @@ fn pause_menu_system(
     } else if restart_game {
         sessions.restart_game(None, false);
         pause_menu.menu_open = false;
-    } else if let Some(maps) = select_map {
-        let match_info = sessions
+    } else if watch_replay {
+        let replay = sessions
             .get(SessionNames::GAME)
-            .unwrap()
-            .world
-            .resource::<MatchInputs>()
-            .deref()
-            .clone();
+            .and_then(|session| session.world.get_resource::<ReplayRecording>())
+            .map(|recording| recording.0.lock().unwrap().clone());
+        if let Some(replay) = replay {
+            super::replay_viewer::start_replay_viewer(&mut sessions, &assets, Arc::new(replay));
+        }
+        pause_menu.menu_open = false;
+    } else if let Some(maps) = select_map {
+        let game_session = sessions.get(SessionNames::GAME).unwrap();
+        let match_info = game_session.world.resource::<MatchInputs>().deref().clone();
+        let rules = game_session.world.resource::<MatchRules>().deref().clone();
         sessions.end_game();
         sessions.start_game(crate::core::MatchPlugin {
             maps,
@@ -145,8 +152,9 @@ fn pause_menu_system(
                 ..match_info.players[i]
             }),
             plugins: meta.get_plugins(&assets),
-            session_runner: Box::<JumpyDefaultMatchRunner>::default(),
+            session_runner: Box::new(JumpyDefaultMatchRunner::new()),
             score: default(),
+            rules,
         });
         pause_menu.menu_open = false;
     }
@@ -167,6 +175,7 @@ fn main_pause_menu(
         &mut bool,
         &mut bool,
         &mut bool,
+        &mut bool,
         bool,
     )>,
     meta: Root<GameMeta>,
@@ -174,7 +183,8 @@ fn main_pause_menu(
     controls: Res<GlobalPlayerControls>,
     scoring_menu: Res<ScoringMenuState>,
 ) {
-    let (ui, session, restart_game, back_to_menu, close_pause_menu, is_online) = &mut *param;
+    let (ui, session, restart_game, watch_replay, back_to_menu, close_pause_menu, is_online) =
+        &mut *param;
 
     // Unpause the game
     if controls.values().any(|x| x.pause_just_pressed) {
@@ -251,6 +261,20 @@ fn main_pause_menu(
             {
                 **restart_game = true;
             }
+
+            // Watch replay button, only available for local matches that are being recorded
+            if !*is_online
+                && session.world.get_resource::<ReplayRecording>().is_some()
+                && BorderedButton::themed(
+                    &meta.theme.buttons.normal,
+                    localization.get("watch-replay"),
+                )
+                .min_size(vec2(width, 0.0))
+                .show(ui)
+                .clicked()
+            {
+                **watch_replay = true;
+            }
         });
 
         // Re-add edit button once map editor is back in game.
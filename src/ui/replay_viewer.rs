@@ -0,0 +1,290 @@
+//! Replay viewer with playback controls.
+//!
+//! The replay is simulated by a [`ReplayViewerRunner`] in the regular game session, while this
+//! module's session draws the controls and communicates with the runner through the
+//! [`ReplayViewerState`] resource in the game session.
+
+use crate::{prelude::*, ui::scoring::ScoringMenuState};
+
+/// The number of frames between the world snapshots that are used to jump back in the replay.
+const SNAPSHOT_INTERVAL: u64 = 5 * FPS as u64;
+
+/// Environment variable that may be set to a replay file to watch when the game starts.
+pub const WATCH_REPLAY_ENV_VAR: &str = "JUMPY_REPLAY";
+
+/// The playback speeds that can be selected in the replay viewer.
+const PLAYBACK_SPEEDS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+pub fn session_plugin(session: &mut Session) {
+    session.add_system_to_stage(Update, replay_viewer_system);
+}
+
+/// Start watching a replay, replacing the current match if there is any.
+pub fn start_replay_viewer(sessions: &mut Sessions, assets: &AssetServer, replay: Arc<Replay>) {
+    let match_plugin =
+        replay.match_plugin(assets, Box::new(ReplayViewerRunner::new(replay.clone())));
+    sessions.end_game();
+    sessions.start_game(match_plugin);
+}
+
+/// The playback state of the replay viewer.
+///
+/// This is inserted as a resource in the game session by the [`ReplayViewerRunner`], and modified
+/// by the replay viewer UI to control the playback.
+#[derive(HasSchema, Clone, Debug)]
+pub struct ReplayViewerState {
+    pub paused: bool,
+    /// The playback speed multiplier.
+    pub speed: f64,
+    /// Set to simulate a single frame while paused.
+    pub step: bool,
+    /// Set to jump to a frame of the replay.
+    pub seek: Option<u32>,
+    /// The replay frame that will be simulated next.
+    pub frame: u32,
+    /// The frame that the current round started on. Earlier frames can't be jumped to.
+    pub round_start_frame: u32,
+    /// The total number of frames in the replay.
+    pub frame_count: u32,
+}
+
+impl Default for ReplayViewerState {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            speed: 1.0,
+            step: false,
+            seek: None,
+            frame: 0,
+            round_start_frame: 0,
+            frame_count: 0,
+        }
+    }
+}
+
+/// A snapshot of the game world during replay playback.
+struct ReplaySnapshot {
+    world: World,
+    playback: ReplayPlayback,
+}
+
+/// Session runner that plays back a [`Replay`] with the controls from the [`ReplayViewerState`].
+///
+/// World snapshots are taken every [`SNAPSHOT_INTERVAL`] frames so that the viewer can jump back
+/// by restoring the closest snapshot and simulating forward from there. Snapshots are only kept for
+/// the current round, because every round is simulated in a new session.
+pub struct ReplayViewerRunner {
+    pub playback: ReplayPlayback,
+    pub state: ReplayViewerState,
+    accumulator: f64,
+    last_run: Option<Instant>,
+    snapshots: Vec<ReplaySnapshot>,
+}
+
+impl ReplayViewerRunner {
+    pub fn new(replay: Arc<Replay>) -> Self {
+        Self {
+            state: ReplayViewerState {
+                frame_count: replay.frame_count(),
+                ..default()
+            },
+            playback: ReplayPlayback::new(replay),
+            accumulator: 0.0,
+            last_run: None,
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Simulate the next frame of the replay. Returns `false` if the replay or round is over.
+    fn advance(&mut self, world: &mut World, stages: &mut SystemStages) -> bool {
+        if !self.playback.apply_frame(world) {
+            return false;
+        }
+
+        world
+            .resource_mut::<Time>()
+            .advance_exact(Duration::from_secs_f64(1.0 / FPS as f64));
+        stages.run(world);
+
+        // The first snapshot is taken after the first frame so that the startup systems have
+        // already run when it is restored.
+        if self.playback.round_frame() % SNAPSHOT_INTERVAL == 1 {
+            self.snapshots.push(ReplaySnapshot {
+                world: world.clone(),
+                playback: self.playback.clone(),
+            });
+        }
+
+        true
+    }
+
+    /// Jump to the given frame, restoring a snapshot if it is in the past.
+    fn seek(&mut self, frame: u32, world: &mut World, stages: &mut SystemStages) {
+        if frame < self.playback.frame() {
+            let snapshot = self
+                .snapshots
+                .iter()
+                .rev()
+                .find(|snapshot| snapshot.playback.frame() <= frame)
+                .or(self.snapshots.first());
+            if let Some(snapshot) = snapshot {
+                *world = snapshot.world.clone();
+                self.playback = snapshot.playback.clone();
+            }
+        }
+
+        while self.playback.frame() < frame {
+            if !self.advance(world, stages) {
+                break;
+            }
+        }
+    }
+}
+
+impl SessionRunner for ReplayViewerRunner {
+    fn step(&mut self, frame_start: Instant, world: &mut World, stages: &mut SystemStages) {
+        pub const STEP: f64 = 1.0 / FPS as f64;
+        let last_run = self.last_run.unwrap_or(frame_start);
+        let delta = (frame_start - last_run).as_secs_f64();
+        self.last_run = Some(frame_start);
+
+        // Pick up any changes made by the viewer UI.
+        if let Some(state) = world.get_resource::<ReplayViewerState>() {
+            self.state = state.clone();
+        }
+
+        if let Some(frame) = self.state.seek.take() {
+            self.seek(frame, world, stages);
+            self.accumulator = 0.0;
+        } else if self.state.paused {
+            if self.state.step {
+                self.advance(world, stages);
+            }
+            self.accumulator = 0.0;
+        } else {
+            self.accumulator += delta * self.state.speed;
+            while self.accumulator >= STEP {
+                self.accumulator -= STEP;
+                if !self.advance(world, stages) {
+                    self.accumulator = 0.0;
+                    break;
+                }
+            }
+        }
+        self.state.step = false;
+        self.state.frame = self.playback.frame();
+
+        // Restoring a snapshot replaces the world, so we always write the state back.
+        world.insert_resource(self.state.clone());
+    }
+
+    fn restart_session(&mut self) {
+        self.playback.restart_round();
+        self.state.round_start_frame = self.playback.frame();
+        self.accumulator = 0.0;
+        self.last_run = None;
+        self.snapshots.clear();
+    }
+
+    fn disable_local_input(&mut self, _disable_input: bool) {}
+}
+
+fn replay_viewer_system(
+    ctx: Res<EguiCtx>,
+    localization: Localization<GameMeta>,
+    assets: Res<AssetServer>,
+    mut sessions: ResMut<Sessions>,
+    mut scoring_menu: ResMut<ScoringMenuState>,
+) {
+    // If the `JUMPY_REPLAY` env var is present, start watching the replay file it points to.
+    {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        static DID_CHECK_ENV_VAR: AtomicBool = AtomicBool::new(false);
+        if DID_CHECK_ENV_VAR
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            if let Some(path) = std::env::var_os(WATCH_REPLAY_ENV_VAR) {
+                match Replay::load(path.as_ref()) {
+                    Ok(replay) => {
+                        sessions.delete(SessionNames::MAIN_MENU);
+                        start_replay_viewer(&mut sessions, &assets, Arc::new(replay));
+                    }
+                    Err(e) => error!("Could not load replay {path:?}: {e}"),
+                }
+                return;
+            }
+        }
+    }
+
+    let mut close_viewer = false;
+    {
+        let Some(session) = sessions.get_mut(SessionNames::GAME) else {
+            return;
+        };
+        let Some(mut state) = session.world.get_resource_mut::<ReplayViewerState>() else {
+            return;
+        };
+
+        // Nobody is controlling the recorded players, so confirm the scoring menu for them.
+        if scoring_menu.active {
+            scoring_menu
+                .ready_players
+                .extend((0..MAX_PLAYERS).map(PlayerIdx));
+        }
+
+        egui::TopBottomPanel::bottom("replay_viewer").show(&ctx, |ui| {
+            ui.horizontal(|ui| {
+                let play_pause = if state.paused {
+                    localization.get("play")
+                } else {
+                    localization.get("pause")
+                };
+                if ui.button(play_pause).clicked() {
+                    state.paused = !state.paused;
+                }
+
+                if ui
+                    .add_enabled(state.paused, egui::Button::new(localization.get("step")))
+                    .clicked()
+                {
+                    state.step = true;
+                }
+
+                for speed in PLAYBACK_SPEEDS {
+                    if ui
+                        .selectable_label(state.speed == speed, format!("{speed}x"))
+                        .clicked()
+                    {
+                        state.speed = speed;
+                    }
+                }
+
+                ui.label(localization.get_with(
+                    "replay-frame",
+                    &fluent_args! {
+                        "frame" => state.frame,
+                        "total" => state.frame_count,
+                    },
+                ));
+
+                // Only jump once the slider is released, because every jump re-simulates frames.
+                let mut frame = state.frame;
+                let range = state.round_start_frame..=state.frame_count;
+                let slider = ui.add(egui::Slider::new(&mut frame, range).show_value(false));
+                if slider.drag_released() || (slider.changed() && !slider.dragged()) {
+                    state.seek = Some(frame);
+                }
+
+                if ui.button(localization.get("close")).clicked() {
+                    close_viewer = true;
+                }
+            });
+        });
+    }
+
+    if close_viewer {
+        sessions.end_game();
+        sessions.start_menu();
+    }
+}
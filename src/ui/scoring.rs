@@ -95,6 +95,12 @@ fn scoring_menu_system(
             .world
             .get_resource::<SyncingInfo>()
             .and_then(|x| x.socket().cloned());
+        #[cfg(not(target_arch = "wasm32"))]
+        let inbox = session
+            .world
+            .get_resource::<ReliableMessageInbox>()
+            .map(|inbox| (*inbox).clone())
+            .unwrap_or_default();
 
         // Build Vec<PlayerScoreInfo> sorted by player indices
         let mut player_entities: Vec<(Entity, &PlayerIdx)> =
@@ -162,7 +168,7 @@ fn scoring_menu_system(
 
         #[cfg(not(target_arch = "wasm32"))]
         if let Some(socket) = network_socket.as_ref() {
-            handle_scoring_messages(socket, &mut state);
+            handle_scoring_messages(socket, &inbox, &mut state);
         }
 
         // Check if all non-ai players are ready
@@ -416,22 +422,25 @@ fn player_score_panel(
 #[cfg(not(target_arch = "wasm32"))]
 fn handle_scoring_messages(
     network_socket: &(impl NetworkSocket + ?Sized),
+    inbox: &ReliableMessageInbox,
     state: &mut ScoringMenuState,
 ) {
     // TODO handle disconnects
-    let datas: Vec<(u32, Vec<u8>)> = network_socket.recv_reliable();
+    // Messages that aren't for the scoring menu, such as checksums, are left in the inbox.
+    let messages = inbox.take(network_socket.recv_reliable(), |data| {
+        postcard::from_bytes::<ScoringMessage>(data)
+            .ok()
+            .filter(|message| message.magic == SCORING_MESSAGE_MAGIC)
+    });
     let local_player_idx = network_socket.player_idx();
-    for (_, data) in datas {
-        match postcard::from_bytes::<ScoringMessage>(&data) {
-            Ok(message) => match message.data {
-                ScoringMessageEnum::PlayerReady(player) => {
-                    if message.magic == SCORING_MESSAGE_MAGIC && player != local_player_idx {
-                        state.ready_players.insert(PlayerIdx(player));
-                        debug!("Received message player {} ready", player);
-                    }
+    for (_, message) in messages {
+        match message.data {
+            ScoringMessageEnum::PlayerReady(player) => {
+                if player != local_player_idx {
+                    state.ready_players.insert(PlayerIdx(player));
+                    debug!("Received message player {} ready", player);
                 }
-            },
-            Err(e) => warn!("Ignoring network message that was not understood: {e}"),
+            }
         }
     }
 }
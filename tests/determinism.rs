@@ -0,0 +1,175 @@
+//! Determinism regression tests.
+//!
+//! Rollback netcode depends on every element and system producing exactly the same simulation
+//! from the same inputs. These tests simulate every stable and experimental map with four AI
+//! players and compare the [`WorldChecksum`] of every frame between runs.
+//!
+//! The number of frames simulated per map can be changed with the `JUMPY_DETERMINISM_FRAMES`
+//! environment variable.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::Path;
+
+use jumpy::{headless, prelude::*};
+
+/// The seed used for the global random number generator in every run.
+const SEED: u64 = 0x5eed;
+
+/// The default number of frames to simulate per map.
+const DEFAULT_FRAMES: usize = 10 * FPS as usize;
+
+fn frame_count() -> usize {
+    std::env::var("JUMPY_DETERMINISM_FRAMES")
+        .ok()
+        .and_then(|frames| frames.parse().ok())
+        .unwrap_or(DEFAULT_FRAMES)
+}
+
+fn new_game() -> Game {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    headless::new_game(&root.join("assets"), &root.join("packs"))
+}
+
+/// Get the name and handle of every stable and experimental map.
+fn maps(game: &Game) -> Vec<(String, Handle<MapMeta>)> {
+    let asset_server = game.shared_resource::<AssetServer>().unwrap();
+    let core = &asset_server.root::<GameMeta>().core;
+    core.stable_maps
+        .iter()
+        .chain(core.experimental_maps.iter())
+        .map(|handle| (asset_server.get(*handle).name.to_string(), *handle))
+        .collect()
+}
+
+/// Start a match on the map with four AI players and a seeded random number generator.
+fn start_match(game: &mut Game, map: Handle<MapMeta>) {
+    let game_meta = game
+        .shared_resource::<AssetServer>()
+        .unwrap()
+        .root::<GameMeta>()
+        .clone();
+    headless::start_match(
+        game,
+        MapPool::from_single_map(map),
+        headless::ai_players(&game_meta, MAX_PLAYERS as usize),
+        default(),
+        default(),
+    );
+    game_session(game)
+        .world
+        .insert_resource(GlobalRng::with_seed(SEED));
+}
+
+fn game_session(game: &mut Game) -> &mut Session {
+    game.sessions
+        .get_mut(SessionNames::GAME)
+        .expect("Game session is not running")
+}
+
+/// Get the session frame and checksum of the last simulated frame.
+///
+/// Returns `None` if the round has just ended and the new session hasn't simulated any frames.
+fn last_checksum(game: &mut Game) -> Option<(u32, WorldChecksum)> {
+    let checksums = game_session(game).world.get_resource::<WorldChecksums>()?;
+    checksums.history.back().copied()
+}
+
+/// Simulate `frames` frames and collect the checksum of each of them.
+fn run(game: &mut Game, frames: usize) -> Vec<(u32, WorldChecksum)> {
+    (0..frames as u32)
+        .map(|frame| {
+            headless::step(game);
+            let checksum = last_checksum(game).map(|(_, checksum)| checksum);
+            (frame, checksum.unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Simulate up to `frames` frames in the current session and collect their checksums, stopping
+/// early if the round ends and the session is replaced.
+fn run_session(game: &mut Game, frames: usize) -> Vec<(u32, WorldChecksum)> {
+    let mut checksums = Vec::with_capacity(frames);
+    for _ in 0..frames {
+        headless::step(game);
+        let Some((frame, checksum)) = last_checksum(game) else {
+            break;
+        };
+        if checksums.last().is_some_and(|(last, _)| *last >= frame) {
+            break;
+        }
+        checksums.push((frame, checksum));
+    }
+    checksums
+}
+
+/// Describe the first difference between two runs, if there is any.
+fn first_difference(a: &[(u32, WorldChecksum)], b: &[(u32, WorldChecksum)]) -> Option<String> {
+    a.iter().zip(b).find_map(|((frame, a), (_, b))| {
+        a.first_difference(b)
+            .map(|component| format!("{component} differs on frame {frame}"))
+    })
+}
+
+#[test]
+fn maps_are_deterministic() {
+    let frames = frame_count();
+    let mut game = new_game();
+
+    let mut failures = Vec::new();
+    for (name, map) in maps(&game) {
+        start_match(&mut game, map);
+        let first = run(&mut game, frames);
+        start_match(&mut game, map);
+        let second = run(&mut game, frames);
+
+        if let Some(difference) = first_difference(&first, &second) {
+            failures.push(format!("{name}: {difference}"));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Simulation is not deterministic:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn restored_snapshots_are_deterministic() {
+    let frames = frame_count();
+    let mut game = new_game();
+
+    let mut failures = Vec::new();
+    for (name, map) in maps(&game) {
+        start_match(&mut game, map);
+        run(&mut game, frames / 2);
+
+        let mut snapshot = game_session(&mut game).snapshot();
+        let first = run_session(&mut game, frames / 2);
+
+        // The match may have moved on to the next round, so start over before restoring.
+        start_match(&mut game, map);
+        game_session(&mut game).restore(&mut snapshot);
+        let second = run_session(&mut game, first.len());
+
+        let difference = if first.len() != second.len() {
+            Some(format!(
+                "round ended after {} frames instead of {}",
+                second.len(),
+                first.len()
+            ))
+        } else {
+            first_difference(&first, &second)
+        };
+        if let Some(difference) = difference {
+            failures.push(format!("{name}: {difference} after restoring snapshot"));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Simulation is not deterministic after restoring a snapshot:\n{}",
+        failures.join("\n")
+    );
+}
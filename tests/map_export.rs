@@ -0,0 +1,102 @@
+//! Map export tests.
+//!
+//! These run a headless match, so that the elements of the map have been hydrated and simulated
+//! before the map is exported.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::Path;
+
+use jumpy::{headless, prelude::*};
+
+fn new_game() -> Game {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    headless::new_game(&root.join("assets"), &root.join("packs"))
+}
+
+fn game_session(game: &mut Game) -> &mut Session {
+    game.sessions
+        .get_mut(SessionNames::GAME)
+        .expect("Game session is not running")
+}
+
+#[test]
+fn moving_platforms_are_exported_where_they_were_placed() {
+    let mut game = new_game();
+    let (game_meta, map, platform) = {
+        let asset_server = game.shared_resource::<AssetServer>().unwrap();
+        let game_meta = asset_server.root::<GameMeta>().clone();
+        let platform = game_meta
+            .core
+            .map_elements
+            .iter()
+            .copied()
+            .find(|element| asset_server.get(*element).name == "Moving Platform")
+            .expect("The moving platform is not a map element");
+        let map = *game_meta.core.stable_maps.iter().next().unwrap();
+        (game_meta, map, platform)
+    };
+
+    // Players that don't do anything, so that the round doesn't end.
+    let mut players = headless::ai_players(&game_meta, 2);
+    for player in &mut players {
+        player.is_ai = false;
+    }
+    headless::start_match(
+        &mut game,
+        MapPool::from_single_map(map),
+        players,
+        default(),
+        default(),
+    );
+    headless::step(&mut game);
+
+    // Place a platform on the map like the editor does.
+    let placed = vec2(200.0, 200.0);
+    game_session(&mut game).world.run_system(
+        move |mut entities: ResMutInit<Entities>,
+              mut element_handles: CompMut<ElementHandle>,
+              mut transforms: CompMut<Transform>,
+              mut spawned_map_layer_metas: CompMut<SpawnedMapLayerMeta>| {
+            let entity = entities.create();
+            element_handles.insert(entity, ElementHandle(platform));
+            transforms.insert(
+                entity,
+                Transform::from_translation(placed.extend(z_depth_for_map_layer(0))),
+            );
+            spawned_map_layer_metas.insert(entity, SpawnedMapLayerMeta { layer_idx: 0 });
+        },
+        (),
+    );
+    for _ in 0..FPS as usize {
+        headless::step(&mut game);
+    }
+
+    let world = &game_session(&mut game).world;
+    let platform_pos = world
+        .run_system(
+            |entities: Res<Entities>,
+             moving_platforms: Comp<MovingPlatform>,
+             transforms: Comp<Transform>| {
+                entities
+                    .iter_with((&moving_platforms, &transforms))
+                    .map(|(_, (_, transform))| transform.translation.xy())
+                    .next()
+            },
+            (),
+        )
+        .expect("The moving platform was not hydrated");
+    assert!(
+        platform_pos.distance(placed) > 10.0,
+        "The platform didn't move from {placed} ({platform_pos})"
+    );
+
+    let export = MapExport::from_world(world, None).unwrap();
+    let exported = export.layers[0]
+        .elements
+        .iter()
+        .filter(|element| element.element.ends_with("moving_platform.element.yaml"))
+        .collect::<Vec<_>>();
+    assert_eq!(exported.len(), 1, "The platform is exported once");
+    assert_eq!(exported[0].pos, placed.to_array());
+}
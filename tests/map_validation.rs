@@ -0,0 +1,226 @@
+//! Tests for [`validate_map`], using small fixture maps made with the game's tilesets and player
+//! spawners, with a single mistake added to them.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::Path;
+
+use jumpy::{headless, prelude::*};
+
+/// The size of the fixture maps, in tiles.
+const GRID_SIZE: UVec2 = UVec2::new(16, 8);
+const TILE_SIZE: Vec2 = Vec2::new(16.0, 16.0);
+
+fn assets() -> AssetServer {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let game = headless::new_game(&root.join("assets"), &root.join("packs"));
+    let assets = game.shared_resource::<AssetServer>().unwrap();
+    (*assets).clone()
+}
+
+/// Find a tilemap and the player spawner element used by the stable maps.
+fn fixture_handles(assets: &AssetServer) -> (Handle<Atlas>, Handle<ElementMeta>) {
+    let maps = headless::all_maps(assets);
+    let layers = || {
+        maps.iter()
+            .flat_map(|map| assets.get(*map).layers.iter().cloned().collect::<Vec<_>>())
+    };
+    let tilemap = layers()
+        .find_map(|layer| layer.tilemap.option())
+        .expect("No map has a tilemap");
+    let spawner = layers()
+        .flat_map(|layer| layer.elements.iter().cloned().collect::<Vec<_>>())
+        .map(|element_spawn| element_spawn.element)
+        .find(|element| {
+            let data = assets.get(*element).data;
+            assets.get(data).try_cast_ref::<PlayerSpawner>().is_ok()
+        })
+        .expect("No map has a player spawner");
+    (tilemap, spawner)
+}
+
+/// Get the position of the center of a tile.
+fn tile_center(x: u32, y: u32) -> Vec2 {
+    (vec2(x as f32, y as f32) + 0.5) * TILE_SIZE
+}
+
+/// Create a map without problems: a floor with a player spawner for every player standing on it.
+fn fixture(assets: &AssetServer) -> MapMeta {
+    let (tilemap, spawner) = fixture_handles(assets);
+    let floor = (0..GRID_SIZE.x)
+        .map(|x| MapTileMeta {
+            pos: uvec2(x, 0),
+            idx: 0,
+            collision: TileCollisionKind::Solid,
+            destructible: false,
+        })
+        .collect();
+    let spawners = [2, 5, 10, 13]
+        .into_iter()
+        .map(|x| ElementSpawn {
+            pos: tile_center(x, 1),
+            element: spawner,
+            size: UVec2::ZERO,
+        })
+        .collect();
+
+    MapMeta {
+        name: "fixture".into(),
+        grid_size: GRID_SIZE,
+        tile_size: TILE_SIZE,
+        layers: [
+            MapLayerMeta {
+                id: "tiles".into(),
+                tilemap: Set(tilemap),
+                tiles: floor,
+                elements: default(),
+            },
+            MapLayerMeta {
+                id: "spawners".into(),
+                tilemap: Unset,
+                tiles: default(),
+                elements: spawners,
+            },
+        ]
+        .into_iter()
+        .collect(),
+        ..default()
+    }
+}
+
+#[test]
+fn fixture_is_valid() {
+    let assets = assets();
+    assert_eq!(validate_map(&fixture(&assets), &assets), Vec::new());
+}
+
+#[test]
+fn too_few_spawners() {
+    let assets = assets();
+    let mut map = fixture(&assets);
+    map.layers[1].elements = map.layers[1].elements.iter().skip(1).cloned().collect();
+
+    assert_eq!(
+        validate_map(&map, &assets),
+        vec![MapProblem::TooFewSpawners {
+            count: MAX_PLAYERS as usize - 1,
+            required: MAX_PLAYERS as usize,
+        }]
+    );
+}
+
+#[test]
+fn spawner_inside_solid() {
+    let assets = assets();
+    for collision in [TileCollisionKind::Solid, TileCollisionKind::Slope45UpRight] {
+        let mut map = fixture(&assets);
+        map.layers[0].tiles.push(MapTileMeta {
+            pos: uvec2(2, 1),
+            idx: 0,
+            collision,
+            destructible: false,
+        });
+
+        let problems = validate_map(&map, &assets);
+        assert!(
+            problems.contains(&MapProblem::SpawnerInsideSolid {
+                layer: "spawners".into(),
+                pos: tile_center(2, 1),
+                tile: uvec2(2, 1),
+            }),
+            "{collision:?} tile not reported: {problems:?}"
+        );
+    }
+}
+
+#[test]
+fn spawner_inside_jump_through_is_valid() {
+    let assets = assets();
+    let mut map = fixture(&assets);
+    map.layers[0].tiles.push(MapTileMeta {
+        pos: uvec2(2, 1),
+        idx: 0,
+        collision: TileCollisionKind::JumpThrough,
+        destructible: false,
+    });
+
+    assert_eq!(validate_map(&map, &assets), Vec::new());
+}
+
+#[test]
+fn element_out_of_bounds() {
+    let assets = assets();
+    let mut map = fixture(&assets);
+    let pos = vec2(-TILE_SIZE.x, TILE_SIZE.y);
+    map.layers[1].elements[0].pos = pos;
+
+    let problems = validate_map(&map, &assets);
+    assert!(
+        problems.iter().any(|problem| matches!(
+            problem,
+            MapProblem::ElementOutOfBounds { layer, pos: p, .. }
+                if layer.as_str() == "spawners" && *p == pos
+        )),
+        "{problems:?}"
+    );
+}
+
+#[test]
+fn tile_index_out_of_range() {
+    let assets = assets();
+    let mut map = fixture(&assets);
+    map.layers[0].tiles[3].idx = u32::MAX;
+
+    let problems = validate_map(&map, &assets);
+    assert!(
+        matches!(
+            problems.as_slice(),
+            [MapProblem::TileIndexOutOfRange { pos, idx: u32::MAX, .. }] if *pos == uvec2(3, 0)
+        ),
+        "{problems:?}"
+    );
+}
+
+#[test]
+fn tiles_without_tilemap() {
+    let assets = assets();
+    let mut map = fixture(&assets);
+    map.layers[0].tilemap = Unset;
+
+    assert_eq!(
+        validate_map(&map, &assets),
+        vec![MapProblem::TilesWithoutTilemap {
+            layer: "tiles".into(),
+            tile_count: GRID_SIZE.x as usize,
+        }]
+    );
+}
+
+#[test]
+fn unreachable_spawner() {
+    let assets = assets();
+    let mut map = fixture(&assets);
+    // Wall off the left half of the map from the right half.
+    for y in 1..GRID_SIZE.y {
+        map.layers[0].tiles.push(MapTileMeta {
+            pos: uvec2(GRID_SIZE.x / 2, y),
+            idx: 0,
+            collision: TileCollisionKind::Solid,
+            destructible: false,
+        });
+    }
+
+    let problems = validate_map(&map, &assets);
+    for from in [2, 5] {
+        for to in [10, 13] {
+            assert!(
+                problems.contains(&MapProblem::UnreachableSpawner {
+                    from: tile_center(from, 1),
+                    to: tile_center(to, 1),
+                }),
+                "Spawners at {from} and {to} not reported: {problems:?}"
+            );
+        }
+    }
+    assert_eq!(problems.len(), 4, "{problems:?}");
+}